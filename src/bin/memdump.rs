@@ -1,4 +1,5 @@
 use satori::proc::maps;
+use satori::error::{Error, Result};
 use std::cmp::{max, min};
 use std::fs::File;
 use std::io::{BufWriter, prelude::*};
@@ -65,10 +66,10 @@ fn main() {
     };
 }
 
-fn mem_dump(pid: u64, begin: u64, end: u64, output: &str) -> Result<i32, String> {
+fn mem_dump(pid: u64, begin: u64, end: u64, output: &str) -> Result<i32> {
     // check range
     if begin >= end {
-        return Err(String::from("Invalid begin-end range"));
+        return Err(Error::invalid("begin-end range", format!("{:#x}-{:#x}", begin, end)));
     }
 
     // read process maps
@@ -83,18 +84,18 @@ fn mem_dump(pid: u64, begin: u64, end: u64, output: &str) -> Result<i32, String>
         }
     }
     if first.is_none() {
-        return Err(String::from("begin address out of range"));
+        return Err(Error::NotFound(format!("page of begin address {:#x}", begin)));
     }
 
     // open file
     let mem_path = format!("/proc/{}/mem", pid);
-    let mut mem = match File::open(mem_path) {
+    let mut mem = match File::open(&mem_path) {
         Ok(f) => f,
-        Err(e) => return Err(e.to_string()),
+        Err(e) => return Err(Error::io(mem_path, e)),
     };
     let mut out = match File::create(output) {
         Ok(f) => BufWriter::new(f),
-        Err(e) => return Err(e.to_string()),
+        Err(e) => return Err(Error::io(output, e)),
     };
 
     // dump data
@@ -116,14 +117,8 @@ fn mem_dump(pid: u64, begin: u64, end: u64, output: &str) -> Result<i32, String>
 
         mem.seek(std::io::SeekFrom::Start(page_begin)).unwrap();
         out.seek(std::io::SeekFrom::Start(page_begin - begin)).unwrap();
-        match mem.read(&mut buf) {
-            Ok(_) => { },
-            Err(e) => return Err(e.to_string()),
-        };
-        match out.write_all(&mut buf) {
-            Ok(_) => { },
-            Err(e) => return Err(e.to_string()),
-        };
+        mem.read(&mut buf)?;
+        out.write_all(&mut buf)?;
         count += 1;
     }
 
@@ -1,168 +1,549 @@
-use std::fs::File;
-use std::io::BufReader;
-use satori::elf::elf32::{
-    ELFHeader,
-    SectionTable,
-    StringTable,
-    //ProgramTable,
-    //SymbolTable,
-};
+use std::io::prelude::*;
+use satori::elf::{arm_exidx::{ArmExidx, Model}, compress::Compression, dwarf::{self, LineProgram}, dynamic, elf64, file::{Elf, Symbol}, note, relocation::RelocationSection, value};
+use satori::error::Result;
+use clap::{Arg, App};
 
 
 fn main() {
-    let args: Vec<String> = std::env::args().collect();
-    if args.len() != 2 {
-        println!("Usage: {} <file path>", args[0]);
-        return;
-    }
+    let matches = App::new("ELF reader")
+        .about("Display information of ELF files")
+        .version("0.2.0")
+        .author("irisu white <irisu@uprprc.net>")
+        .arg(Arg::with_name("header")
+            .short("H").long("header")
+            .help("show ELF header"))
+        .arg(Arg::with_name("sections")
+            .short("S").long("sections")
+            .help("show section headers"))
+        .arg(Arg::with_name("section-groups")
+            .short("g").long("section-groups")
+            .help("show section groups with signature and member sections"))
+        .arg(Arg::with_name("segments")
+            .short("l").long("program-headers")
+            .alias("segments")
+            .help("show program headers and sections in each segment"))
+        .arg(Arg::with_name("notes")
+            .short("n").long("notes")
+            .help("show note sections, or note segments without section headers"))
+        .arg(Arg::with_name("dynamic")
+            .short("d").long("dynamic")
+            .help("show dynamic section"))
+        .arg(Arg::with_name("relocs")
+            .short("r").long("relocs")
+            .help("show relocations"))
+        .arg(Arg::with_name("symbols")
+            .short("s").long("symbols")
+            .help("show symbol table"))
+        .arg(Arg::with_name("dyn-syms")
+            .long("dyn-syms")
+            .help("show dynamic symbol table"))
+        .arg(Arg::with_name("version-info")
+            .short("V").long("version-info")
+            .help("show symbol version sections"))
+        .arg(Arg::with_name("use-dynamic")
+            .short("D").long("use-dynamic")
+            .help("show tables found by PT_DYNAMIC, ignore section headers"))
+        .arg(Arg::with_name("unwind")
+            .short("u").long("unwind")
+            .help("show unwind tables with the rules of each address"))
+        .arg(Arg::with_name("debug-line")
+            .long("debug-line")
+            .help("show rows of the .debug_line line number programs"))
+        .arg(Arg::with_name("addr2line")
+            .long("addr2line")
+            .value_name("ADDRESS")
+            .takes_value(true)
+            .number_of_values(1)
+            .multiple(true)
+            .help("show source file and line of hex address, may be repeated"))
+        .arg(Arg::with_name("demangle")
+            .short("C").long("demangle")
+            .help("show C++ and Rust symbol names demangled"))
+        .arg(Arg::with_name("file")
+            .required(true)
+            .index(1)
+            .help("ELF file path"))
+        .get_matches();
 
-    let mut elf = match File::open(&args[1]) {
-        Ok(f) => BufReader::new(f),
+    // class and byte order are detected by Elf
+    let path = matches.value_of("file").unwrap();
+    let mut elf = match Elf::open(path) {
+        Ok(elf) => elf,
         Err(e) => {
             eprintln!("Error: {}", e);
             return;
         }
     };
+    for warning in &elf.warnings {
+        eprintln!("Warning: {}", warning);
+    }
+
+    let demangle = matches.is_present("demangle");
+
+    // header and sections are shown when nothing is selected
+    let nothing = !matches.is_present("header") && !matches.is_present("sections") &&
+        !matches.is_present("section-groups") &&
+        !matches.is_present("segments") && !matches.is_present("notes") &&
+        !matches.is_present("dynamic") && !matches.is_present("symbols") &&
+        !matches.is_present("dyn-syms") && !matches.is_present("use-dynamic") &&
+        !matches.is_present("version-info") && !matches.is_present("relocs") &&
+        !matches.is_present("debug-line") && !matches.is_present("addr2line") &&
+        !matches.is_present("unwind");
 
     // elf header
-    let elf_header = match ELFHeader::load(&mut elf) {
-        Ok(hdr) => hdr,
-        Err(e) => {
+    if nothing || matches.is_present("header") {
+        println!("{:#x?}", elf.header);
+    }
+
+    // format section header
+    if nothing || matches.is_present("sections") {
+        for index in 0..elf.sections().len() {
+            // a section whose compression header is broken is still listed
+            let compression = elf.section_compression(index).unwrap_or(None);
+            let section = &elf.sections()[index];
+            print_section(section.index, &section.name, &section.header, elf.header.e_machine, compression);
+        }
+    }
+
+    // section groups
+    if matches.is_present("section-groups") {
+        if let Err(e) = print_groups(&mut elf) {
             eprintln!("Error: {}", e);
-            return;
         }
-    };
-    println!("{:#x?}", elf_header);
+    }
 
-    // section header
-    let section_table = match SectionTable::load(&mut elf, &elf_header) {
-        Ok(hdr) => hdr,
-        Err(e) => {
+    // program headers
+    if matches.is_present("segments") {
+        if let Err(e) = print_segments(&mut elf) {
             eprintln!("Error: {}", e);
-            return;
         }
-    };
-    //println!("{:#x?}", section_table);
+    }
 
-    // format section header
-    if let Err(e) = print_section_table(&mut elf, &section_table) {
-        eprintln!("Error: {}", e);
+    // notes
+    if matches.is_present("notes") {
+        if let Err(e) = print_notes(&mut elf) {
+            eprintln!("Error: {}", e);
+        }
+    }
+
+    // dynamic section
+    if matches.is_present("dynamic") {
+        if let Err(e) = print_dynamic(&mut elf) {
+            eprintln!("Error: {}", e);
+        }
+    }
+
+    // relocations, of PT_DYNAMIC when -D is given
+    if matches.is_present("relocs") {
+        let relocations = match matches.is_present("use-dynamic") {
+            true => elf.dynamic_relocations(),
+            false => elf.relocations(),
+        };
+        match relocations {
+            Ok(tables) => print_relocations(&tables, demangle),
+            Err(e) => eprintln!("Error: {}", e),
+        };
     }
 
-    // program header
-    //let program_table = match ProgramTable::load(&mut elf, &elf_header) {
-    //    Ok(phdr) => phdr,
-    //    Err(e) => {
-    //        eprintln!("Error: {}", e);
-    //        return;
-    //    }
-    //};
-    //println!("{:#x?}", program_table);
+    // tables of PT_DYNAMIC
+    if matches.is_present("use-dynamic") && !matches.is_present("relocs") {
+        if let Err(e) = print_dynamic_info(&mut elf, demangle) {
+            eprintln!("Error: {}", e);
+        }
+    }
 
     // symbol table
-    //for s in &section_table.data {
-    //    if s.sh_type != elf_rs::value::SHT_SYMTAB {
-    //        continue;
-    //    }
-    //    let symbol_table = match SymbolTable::load(&mut elf, s) {
-    //        Ok(st) => st,
-    //        Err(e) => {
-    //            eprintln!("Error: {}", e);
-    //            return;
-    //        }
-    //    };
-    //    println!("{:#x?}", symbol_table);
-    //    break;
-    //}
+    if matches.is_present("symbols") {
+        match elf.symbols() {
+            Ok(symbols) => print_symbols(".symtab", &symbols, demangle),
+            Err(e) => eprintln!("Error: {}", e),
+        };
+    }
+    if matches.is_present("dyn-syms") {
+        match elf.dynamic_symbols() {
+            Ok(symbols) => print_symbols(".dynsym", &symbols, demangle),
+            Err(e) => eprintln!("Error: {}", e),
+        };
+    }
+
+    // symbol versions
+    if matches.is_present("version-info") {
+        if let Err(e) = print_versions(&mut elf) {
+            eprintln!("Error: {}", e);
+        }
+    }
+
+    // unwind tables
+    if matches.is_present("unwind") {
+        if let Err(e) = print_unwind(&mut elf) {
+            eprintln!("Error: {}", e);
+        }
+    }
+
+    // line number programs
+    if matches.is_present("debug-line") {
+        match elf.line_programs() {
+            Ok(programs) => print_line_programs(&programs),
+            Err(e) => eprintln!("Error: {}", e),
+        };
+    }
+    if let Some(addresses) = matches.values_of("addr2line") {
+        let programs = match elf.line_programs() {
+            Ok(programs) => programs,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                return;
+            }
+        };
+        for text in addresses {
+            // hex with or without 0x, as addr2line takes it
+            match u64::from_str_radix(text.trim_start_matches("0x").trim_start_matches("0X"), 16) {
+                Ok(address) => match dwarf::find_location(&programs, address) {
+                    Some(location) => println!("{:#x}: {}", address, location),
+                    None => println!("{:#x}: ??:0", address),
+                },
+                Err(_) => eprintln!("Error: invalid address {}", text),
+            }
+        }
+    }
 }
 
-#[allow(dead_code)]
-pub fn print_section_table<T>(elf: &mut T, st: &SectionTable) -> Result<(), String>
-    where T: std::io::Read + std::io::Seek
+fn print_versions<T>(elf: &mut Elf<T>) -> Result<()>
+    where T: Read + Seek
 {
-    let name_section = &st.data[st.name as usize];
-    let name_table = match StringTable::load(elf, name_section) {
-        Ok(table) => table,
-        Err(e) => return Err(e),
+    let versions = match elf.versions()? {
+        Some(v) => v,
+        None => {
+            println!("no version information");
+            return Ok(());
+        }
     };
 
-    for (idx, shdr) in st.data.iter().enumerate() {
-        let name = name_table.get(shdr.sh_name as usize);
-        // parse flag
-        let flag = shdr.sh_flags;
-        let mut flag_str = format!("{:#x}", flag);
-        if flag != 0 {
-            flag_str.push_str(" {");
-            if (flag & 0x1) != 0 {
-                flag_str.push_str(" WRITE");
-            }
-            if (flag & 0x2) != 0 {
-                flag_str.push_str(" ALLOC");
-            }
-            if (flag & 0x4) != 0 {
-                flag_str.push_str(" EXECINSTR");
-            }
-            if (flag & 0xF0000000) != 0 {
-                flag_str.push_str(" MASKPROC");
-            }
-            if (flag & 0x0FFFFFF8) != 0 {
-                flag_str.push_str(" OTHER");
-            }
-            flag_str.push_str(" }");
-        }
-        // match type
-        let type_str  = match shdr.sh_type {
-            0 => "SHT_NULL",
-            1 => "SHT_PROGBITS",
-            2 => "SHT_SYMTAB",
-            3 => "SHT_STRTAB",
-            4 => "SHT_RELA",
-            5 => "SHT_HASH",
-            6 => "SHT_DYNAMIC",
-            7 => "SHT_NOTE",
-            8 => "SHT_NOBITS",
-            9 => "SHT_REL",
-            10 => "SHT_SHLIB",
-            11 => "SHT_DYNSYM",
-            0x70000000 => "SHT_LOPROC",
-            0x7fffffff => "SHT_HIPROC",
-            0x80000000 => "SHT_LOUSER",
-            0xffffffff => "SHT_HIUSER",
-            _ => "INVALID",
-        };
-        // match link and info
-        let link = match shdr.sh_link {
-            0 => "SHN_UNDEF".to_string(),
-            0xff00 => "SHN_LOPROC".to_string(),
-            0xff1f => "SHN_HIPROC".to_string(),
-            0xfff1 => "SHN_ABS".to_string(),
-            0xfff2 => "SHN_COMMON".to_string(),
-            0xffff => "SHN_HIRESERVE".to_string(),
-            n => format!("{}", n),
+    println!("version definitions {{");
+    for def in &versions.defs {
+        let base = if (def.flags & value::VER_FLG_BASE) != 0 { " BASE" } else { "" };
+        println!("	{}: {} hash {:#x}{} parents [{}]", def.index, def.name(), def.hash, base,
+            def.names.iter().skip(1).cloned().collect::<Vec<String>>().join(", "));
+    }
+    println!("}}");
+    println!("version needs {{");
+    for need in &versions.needs {
+        println!("	{} {{", need.file);
+        for version in &need.versions {
+            let weak = if (version.flags & value::VER_FLG_WEAK) != 0 { " WEAK" } else { "" };
+            println!("		{}: {} hash {:#x}{}", version.index, version.name, version.hash, weak);
+        }
+        println!("	}}");
+    }
+    println!("}}");
+
+    Ok(())
+}
+
+fn print_dynamic_info<T>(elf: &mut Elf<T>, demangle: bool) -> Result<()>
+    where T: Read + Seek
+{
+    let info = match elf.dynamic_info()? {
+        Some(info) => info,
+        None => {
+            println!("no dynamic section");
+            return Ok(());
+        }
+    };
+
+    print_symbols("DT_SYMTAB", &info.symbols, demangle);
+    print_relocations(&elf.dynamic_relocations()?, demangle);
+    println!("init {{");
+    if let Some(init) = info.init {
+        println!("\tDT_INIT: {:#x}", init);
+    }
+    if let Some(fini) = info.fini {
+        println!("\tDT_FINI: {:#x}", fini);
+    }
+    for (name, array) in &[("DT_PREINIT_ARRAY", &info.preinit_array),
+        ("DT_INIT_ARRAY", &info.init_array), ("DT_FINI_ARRAY", &info.fini_array)] {
+        let pointers: Vec<String> = array.iter().map(|p| format!("{:#x}", p)).collect();
+        println!("\t{}: [{}]", name, pointers.join(", "));
+    }
+    println!("}}");
+
+    Ok(())
+}
+
+fn print_relocations(tables: &[RelocationSection], demangle: bool) {
+    for table in tables {
+        println!("relocations {} at {:#x} {{", table.name, table.offset);
+        for rel in &table.relocations {
+            let addend = match rel.addend {
+                Some(n) if n < 0 => format!(" - {:#x}", -(n as i128)),
+                Some(n) => format!(" + {:#x}", n),
+                None => String::new(),
+            };
+            let name = match demangle {
+                true => rel.demangled_symbol_name(),
+                false => rel.symbol_name(),
+            };
+            println!("\t{:#x} {:#x} {} {}{}", rel.offset, rel.info, rel.type_name(), name, addend);
+        }
+        println!("}}");
+    }
+}
+
+fn print_symbols(table: &str, symbols: &[Symbol], demangle: bool) {
+    println!("symbols {} {{", table);
+    for (idx, symbol) in symbols.iter().enumerate() {
+        let entry = &symbol.entry;
+        // indices past SHN_LORESERVE come resolved through SHT_SYMTAB_SHNDX
+        let shndx = match entry.st_shndx as usize {
+            value::SHN_UNDEF => "UND".to_string(),
+            value::SHN_ABS => "ABS".to_string(),
+            value::SHN_COMMON => "COM".to_string(),
+            _ => format!("{}", symbol.shndx),
         };
-        let info = match shdr.sh_info {
-            0 => "SHN_UNDEF".to_string(),
-            0xff00 => "SHN_LOPROC".to_string(),
-            0xff1f => "SHN_HIPROC".to_string(),
-            0xfff1 => "SHN_ABS".to_string(),
-            0xfff2 => "SHN_COMMON".to_string(),
-            0xffff => "SHN_HIRESERVE".to_string(),
-            n => format!("{}", n),
+        let name = match demangle {
+            true => symbol.versioned_demangled_name(),
+            false => symbol.versioned_name(),
         };
-        // format
-        println!("section {} {{", idx);
-        println!("\tname: {} ({:#x})", name, shdr.sh_name);
-        println!("\ttype: {} ({:#x})", type_str, shdr.sh_type);
-        println!("\tflags {}", flag_str);
-        println!("\taddr: {:#x}", shdr.sh_addr);
-        println!("\toffset: {:#x}", shdr.sh_offset);
-        println!("\tsize: {:#x}", shdr.sh_size);
-        println!("\tlink: {}", link);
-        println!("\tinfo: {}", info);
-        println!("\taddr align: {}", shdr.sh_addralign);
-        println!("\tentry size: {:#x}", shdr.sh_entsize);
+        println!("\t{}: {:#x} {} {} {} {} {} {}", idx, entry.st_value, entry.st_size,
+            entry.symbol_type(), entry.bind(), entry.visibility(), shndx, name);
+    }
+    println!("}}");
+}
+
+fn print_groups<T>(elf: &mut Elf<T>) -> Result<()>
+    where T: Read + Seek
+{
+    for group in elf.groups()? {
+        let flags = format!("{} ({:#x})", if group.is_comdat() { "COMDAT" } else { "" }, group.flags);
+        println!("group {} {{", group.index);
+        println!("\tsignature: {}", group.signature);
+        println!("\tflags: {}", flags.trim_start());
+        let names: String = group.members.iter()
+            .map(|i| match elf.sections().get(*i as usize) {
+                Some(section) => format!(" {}", section.name),
+                None => format!(" {}", i),
+            })
+            .collect();
+        println!("\tsections:{}", names);
+        println!("}}");
+    }
+    Ok(())
+}
+
+fn print_segments<T>(elf: &mut Elf<T>) -> Result<()>
+    where T: Read + Seek
+{
+    let interpreter = elf.interpreter()?;
+    let mapping = elf.section_mapping();
+    for (idx, phdr) in elf.segments().iter().enumerate() {
+        println!("segment {} {{", idx);
+        println!("\ttype: {} ({:#x})", phdr.segment_type(elf.header.e_machine), phdr.p_type);
+        println!("\tflags: {} ({:#x})", phdr.flags(), phdr.p_flags);
+        println!("\toffset: {:#x}", phdr.p_offset);
+        println!("\tvaddr: {:#x}", phdr.p_vaddr);
+        println!("\tpaddr: {:#x}", phdr.p_paddr);
+        println!("\tfile size: {:#x}", phdr.p_filesz);
+        println!("\tmem size: {:#x}", phdr.p_memsz);
+        println!("\talign: {:#x}", phdr.p_align);
+        if let (value::PT_INTERP, Some(path)) = (phdr.p_type, &interpreter) {
+            println!("\tinterpreter: {}", path);
+        }
+        let names: String = mapping[idx].iter().map(|i| format!(" {}", elf.sections()[*i].name)).collect();
+        println!("\tsections:{}", names);
+        println!("}}");
+    }
+    Ok(())
+}
+
+fn print_notes<T>(elf: &mut Elf<T>) -> Result<()>
+    where T: Read + Seek
+{
+    let machine = elf.header.e_machine;
+    for table in elf.notes()? {
+        match &table.section {
+            Some(name) => println!("notes {} {{", name),
+            None => println!("notes at offset {:#x} {{", table.offset),
+        }
+        for entry in table.notes() {
+            println!("\t{} {} ({:#x}) size {:#x}: {}", entry.name, note::type_name(entry.name, entry.n_type),
+                entry.n_type, entry.desc.len(), entry.decode(machine));
+        }
+        println!("}}");
+    }
+    Ok(())
+}
+
+fn print_unwind<T>(elf: &mut Elf<T>) -> Result<()>
+    where T: Read + Seek
+{
+    let exidx = elf.arm_exidx()?;
+    if let Some(exidx) = &exidx {
+        print_arm_exidx(exidx)?;
+    }
+    if let Some(hdr) = elf.eh_frame_hdr()? {
+        println!("eh_frame_hdr at {:#x}: eh_frame {:#x}, {} entries", hdr.vaddr, hdr.eh_frame_ptr, hdr.table.len());
+    }
+    let eh_frame = match elf.eh_frame()? {
+        Some(eh_frame) => eh_frame,
+        None => {
+            if exidx.is_none() {
+                println!("no unwind table");
+            }
+            return Ok(());
+        }
+    };
+    println!("eh_frame at {:#x} {{", eh_frame.vaddr);
+    for cie in &eh_frame.cies {
+        println!("\tcie {:#x} version {} {:?} code {} data {} ra r{}", cie.offset, cie.version,
+            cie.augmentation, cie.code_alignment_factor, cie.data_alignment_factor, cie.return_address_register);
+    }
+    for fde in &eh_frame.fdes {
+        println!("\tfde {:#x} cie {:#x} pc {:#x}..{:#x} {{", fde.offset, eh_frame.cies[fde.cie].offset,
+            fde.pc_begin, fde.pc_begin.wrapping_add(fde.pc_range));
+        for row in eh_frame.unwind_table(fde)? {
+            let rules: String = row.registers.iter().map(|(r, rule)| format!(" r{}={}", r, rule)).collect();
+            println!("\t\t{:#x}: cfa {}{}", row.address, row.cfa, rules);
+        }
+        println!("\t}}");
+    }
+    println!("}}");
+    Ok(())
+}
+
+fn print_arm_exidx(exidx: &ArmExidx) -> Result<()> {
+    println!("arm exidx at {:#x} {{", exidx.vaddr);
+    for entry in &exidx.entries {
+        match &entry.model {
+            Model::CantUnwind => {
+                println!("\t{:#x} cantunwind", entry.function);
+                continue;
+            },
+            Model::Compact { personality, table: None, .. } => println!("\t{:#x} pr{} inline {{", entry.function, personality),
+            Model::Compact { personality, table: Some(table), .. } =>
+                println!("\t{:#x} pr{} extab {:#x} {{", entry.function, personality, table),
+            Model::Generic { personality, table, .. } =>
+                println!("\t{:#x} personality {:#x} extab {:#x} {{", entry.function, personality, table),
+        }
+        for instruction in entry.instructions()? {
+            println!("\t\t{}", instruction);
+        }
+        if let Some(row) = entry.unwind_row()? {
+            let rules: String = row.registers.iter().map(|(r, rule)| format!(" r{}={}", r, rule)).collect();
+            println!("\t\tcfa {}{}", row.cfa, rules);
+        }
+        println!("\t}}");
+    }
+    println!("}}");
+    Ok(())
+}
+
+fn print_line_programs(programs: &[LineProgram]) {
+    for program in programs {
+        println!("line program at {:#x} version {} {{", program.offset, program.version);
+        for row in &program.rows {
+            let path = program.file_path(row.file).unwrap_or_else(|| String::from("??"));
+            match row.end_sequence {
+                true => println!("\t{:#x} end of sequence", row.address),
+                false => println!("\t{:#x} {}:{}:{}{}", row.address, path, row.line, row.column,
+                    if row.is_stmt { " stmt" } else { "" }),
+            }
+        }
         println!("}}");
     }
+}
+
+fn print_dynamic<T>(elf: &mut Elf<T>) -> Result<()>
+    where T: Read + Seek
+{
+    let dynamic = match elf.dynamic()? {
+        Some(d) => d,
+        None => {
+            println!("no dynamic section");
+            return Ok(());
+        }
+    };
+
+    println!("dynamic {{");
+    for entry in &dynamic.entries {
+        let name = dynamic::tag_name(entry.d_tag);
+        let value_str = match dynamic.entry_string(entry) {
+            Some(s) => format!("{} ({:#x})", s, entry.d_val),
+            None => match entry.d_tag {
+                value::DT_FLAGS => format!("{:#x} {}", entry.d_val, flags_string(entry.d_val, &[
+                    (value::DF_ORIGIN, "ORIGIN"),
+                    (value::DF_SYMBOLIC, "SYMBOLIC"),
+                    (value::DF_TEXTREL, "TEXTREL"),
+                    (value::DF_BIND_NOW, "BIND_NOW"),
+                    (value::DF_STATIC_TLS, "STATIC_TLS"),
+                ])),
+                value::DT_FLAGS_1 => format!("{:#x} {}", entry.d_val, flags_string(entry.d_val, &[
+                    (value::DF_1_NOW, "NOW"),
+                    (value::DF_1_GLOBAL, "GLOBAL"),
+                    (value::DF_1_GROUP, "GROUP"),
+                    (value::DF_1_NODELETE, "NODELETE"),
+                    (value::DF_1_LOADFLTR, "LOADFLTR"),
+                    (value::DF_1_INITFIRST, "INITFIRST"),
+                    (value::DF_1_NOOPEN, "NOOPEN"),
+                    (value::DF_1_ORIGIN, "ORIGIN"),
+                    (value::DF_1_DIRECT, "DIRECT"),
+                    (value::DF_1_INTERPOSE, "INTERPOSE"),
+                    (value::DF_1_NODEFLIB, "NODEFLIB"),
+                    (value::DF_1_NODUMP, "NODUMP"),
+                    (value::DF_1_PIE, "PIE"),
+                ])),
+                _ => format!("{:#x}", entry.d_val),
+            },
+        };
+        println!("\t{} ({:#x}): {}", name, entry.d_tag, value_str);
+    }
+    println!("}}");
 
     Ok(())
 }
+
+fn flags_string(flags: u64, names: &[(u64, &str)]) -> String {
+    let mut s = String::from("{");
+    let mut known = 0;
+    for (flag, name) in names {
+        if (flags & flag) != 0 {
+            s.push(' ');
+            s.push_str(name);
+        }
+        known |= flag;
+    }
+    if (flags & !known) != 0 {
+        s.push_str(" OTHER");
+    }
+    s.push_str(" }");
+    s
+}
+
+fn print_section(idx: usize, name: &str, shdr: &elf64::SectionHeader, machine: u16, compression: Option<Compression>) {
+    // link and info are section indices of any width, reserved indices do not apply
+    let link = match shdr.sh_link as usize {
+        value::SHN_UNDEF => "SHN_UNDEF".to_string(),
+        n => format!("{}", n),
+    };
+    let info = match shdr.sh_info as usize {
+        value::SHN_UNDEF => "SHN_UNDEF".to_string(),
+        n => format!("{}", n),
+    };
+    // format
+    println!("section {} {{", idx);
+    println!("\tname: {} ({:#x})", name, shdr.sh_name);
+    println!("\ttype: {} ({:#x})", shdr.section_type(machine), shdr.sh_type);
+    // no key letters without flags
+    let flags = format!("{} ({:#x})", shdr.flags(machine), shdr.sh_flags);
+    println!("\tflags: {}", flags.trim_start());
+    println!("\taddr: {:#x}", shdr.sh_addr);
+    println!("\toffset: {:#x}", shdr.sh_offset);
+    println!("\tsize: {:#x}", shdr.sh_size);
+    if let Some(compression) = compression {
+        println!("\tcompressed: {}, uncompressed size {:#x}", compression.format, compression.size);
+    }
+    println!("\tlink: {}", link);
+    println!("\tinfo: {}", info);
+    println!("\taddr align: {}", shdr.sh_addralign);
+    println!("\tentry size: {:#x}", shdr.sh_entsize);
+    println!("}}");
+}
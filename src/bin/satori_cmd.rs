@@ -1,11 +1,20 @@
 use satori::proc::maps;
 use satori::proc::mem;
 use satori::proc::utils;
+use satori::elf::fixer;
+use satori::error::{Error, Result};
 
 use clap::{Arg, App, SubCommand};
 
 
-fn main() -> Result<(), String> {
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<()> {
     let matches = App::new("Satori Watcher")
         .about("Read, write and parse /proc info")
         .version("0.0.1")
@@ -28,7 +37,10 @@ fn main() -> Result<(), String> {
                 .help("target library name"))
             .arg(Arg::with_name("output")
                 .required(true)
-                .help("output path")))
+                .help("output path"))
+            .arg(Arg::with_name("fix")
+                .short("f").long("fix")
+                .help("rebuild section headers of dumped library")))
         .arg(Arg::with_name("package")
             .required(true)
             .index(1)
@@ -82,9 +94,21 @@ fn main() -> Result<(), String> {
         let maps = maps::read_maps(pid)?;
         let library_name = dump_library.value_of("library").unwrap();
         let output_path = dump_library.value_of("output").unwrap();
-        match mem::dump_library(pid, &maps, library_name, output_path) {
-            Ok(..) => { },
-            Err(e) => return Err(e.to_string()),
+        mem::dump_library(pid, &maps, library_name, output_path)?;
+        // dump starts at the lowest page of library
+        if dump_library.is_present("fix") {
+            let base = match maps.iter().filter(|v| v.pathname == library_name).map(|v| v.begin).min() {
+                Some(b) => b,
+                None => return Err(Error::NotFound(format!("{} in maps", library_name))),
+            };
+            let image = match std::fs::read(output_path) {
+                Ok(data) => data,
+                Err(e) => return Err(Error::io(output_path, e)),
+            };
+            let fixed = fixer::fix_dump(&image, base)?;
+            if let Err(e) = std::fs::write(output_path, fixed) {
+                return Err(Error::io(output_path, e));
+            }
         }
     }
 
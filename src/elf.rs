@@ -1,2 +1,24 @@
+pub mod address;
+pub mod arm_exidx;
+pub mod compress;
+pub mod demangle;
+pub mod dwarf;
+pub mod dynamic;
+pub mod eh_frame;
 pub mod elf32;
+pub mod elf64;
+pub mod endian;
+pub mod file;
+pub mod fixer;
+pub mod hash;
+pub mod leb128;
+pub mod note;
+pub mod patch;
+pub mod relocation;
+pub mod section;
+pub mod segment;
+pub mod symbol;
 pub mod value;
+pub mod version;
+pub mod view;
+pub mod writer;
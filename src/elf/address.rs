@@ -0,0 +1,65 @@
+use crate::elf::{elf64, value};
+
+
+// where the content of a virtual address comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Location {
+    // file offset holding it
+    File(u64),
+    // past p_filesz of its PT_LOAD, zero-filled by the loader as .bss is,
+    // with the distance from the end of file content
+    ZeroFill(u64),
+}
+
+impl Location {
+    pub fn offset(&self) -> Option<u64> {
+        match self {
+            Location::File(offset) => Some(*offset),
+            Location::ZeroFill(_) => None,
+        }
+    }
+}
+
+// index of the PT_LOAD mapping vaddr, zero-filled tail included
+pub fn segment_for_vaddr(segments: &[elf64::ProgramHeader], vaddr: u64) -> Option<usize> {
+    segments.iter().position(|phdr| {
+        phdr.p_type == value::PT_LOAD && vaddr >= phdr.p_vaddr && vaddr - phdr.p_vaddr < phdr.p_memsz
+    })
+}
+
+// index of the first PT_LOAD mapping file offset
+pub fn segment_for_offset(segments: &[elf64::ProgramHeader], offset: u64) -> Option<usize> {
+    segments.iter().position(|phdr| {
+        phdr.p_type == value::PT_LOAD && offset >= phdr.p_offset && offset - phdr.p_offset < phdr.p_filesz
+    })
+}
+
+// file location of vaddr, None if no PT_LOAD maps it
+pub fn vaddr_to_offset(segments: &[elf64::ProgramHeader], vaddr: u64) -> Option<Location> {
+    let phdr = &segments[segment_for_vaddr(segments, vaddr)?];
+    let delta = vaddr - phdr.p_vaddr;
+    if delta >= phdr.p_filesz {
+        return Some(Location::ZeroFill(delta - phdr.p_filesz));
+    }
+    phdr.p_offset.checked_add(delta).map(Location::File)
+}
+
+// address file offset is mapped at, by the first PT_LOAD when pages are mapped twice
+pub fn offset_to_vaddr(segments: &[elf64::ProgramHeader], offset: u64) -> Option<u64> {
+    let phdr = &segments[segment_for_offset(segments, offset)?];
+    phdr.p_vaddr.checked_add(offset - phdr.p_offset)
+}
+
+// Index of the allocated section holding vaddr. Empty sections hold nothing
+// and .tbss is left out, its addresses are only a template for each thread.
+pub fn section_for_vaddr<'a, I>(sections: I, vaddr: u64) -> Option<usize>
+    where I: IntoIterator<Item = &'a elf64::SectionHeader>
+{
+    let alloc = value::SHF_ALLOC as u64;
+    let tls = value::SHF_TLS as u64;
+    sections.into_iter().position(|shdr| {
+        (shdr.sh_flags & alloc) != 0 &&
+            !(shdr.sh_type == value::SHT_NOBITS && (shdr.sh_flags & tls) != 0) &&
+            vaddr >= shdr.sh_addr && vaddr - shdr.sh_addr < shdr.sh_size
+    })
+}
@@ -0,0 +1,323 @@
+use std::fmt;
+use crate::{
+    elf::{eh_frame::{CfaRule, RegisterRule, UnwindRow}, elf32::ExidxEntry, endian::Endian, leb128, value},
+    error::{Error, Result},
+};
+
+
+// DWARF numbers of ARM registers, as UnwindRow holds them
+const SP: u64 = 13;
+const LR: u64 = 14;
+const PC: u64 = 15;
+const WCGR0: u64 = 104;
+const WR0: u64 = 112;
+const D0: u64 = 256;
+
+// entry of .ARM.exidx with its offsets resolved
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexEntry {
+    // offset in .ARM.exidx
+    pub offset: u64,
+    pub function: u64,
+    pub model: Model,
+}
+
+// how the frame of a function is unwound
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Model {
+    CantUnwind,
+    // __aeabi_unwind_cpp_pr0, pr1 or pr2 with its unwind opcodes, which are
+    // inline in .ARM.exidx when table is None
+    Compact { personality: u8, table: Option<u64>, opcodes: Vec<u8> },
+    // Personality routine of the language and its .ARM.extab entry. Opcodes
+    // are read as the routines of GCC and clang lay them out, as for pr1.
+    Generic { personality: u64, table: u64, opcodes: Vec<u8> },
+}
+
+// content of .ARM.exidx, sorted by function
+#[derive(Debug, Clone)]
+pub struct ArmExidx {
+    pub vaddr: u64,
+    pub entries: Vec<IndexEntry>,
+}
+
+// unwind instruction, vsp is the virtual stack pointer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnwindOp {
+    AddVsp(i64),
+    // vsp = r[n]
+    SetVsp(u8),
+    // r0-r15 by bit, the lowest one at vsp
+    PopCore(u16),
+    // FSTMFDX saved a pad word after the registers
+    PopVfp { first: u8, count: u8, fstmfdx: bool },
+    PopWmmx { first: u8, count: u8 },
+    // wCGR0-wCGR3 by bit
+    PopWmmxControl(u8),
+    Refuse,
+}
+
+impl ArmExidx {
+    // Data of .ARM.exidx loaded at vaddr. Entries out of line are read from
+    // extab loaded at extab_vaddr, normally .ARM.extab.
+    pub fn parse(data: &[u8], vaddr: u64, extab: &[u8], extab_vaddr: u64, endian: Endian) -> Result<ArmExidx> {
+        let mut entries: Vec<IndexEntry> = Vec::with_capacity(data.len() / 8);
+        for (index, chunk) in data.chunks_exact(8).enumerate() {
+            let offset = index as u64 * 8;
+            let place = vaddr.wrapping_add(offset);
+            let entry = ExidxEntry::parse(chunk, endian);
+            if (entry.fnoffset & 0x8000_0000) != 0 {
+                return Err(Error::invalid("exidx function offset", format!("{:#x} at {:#x}", entry.fnoffset, offset)));
+            }
+            let model = match entry.insn {
+                value::EXIDX_CANTUNWIND => Model::CantUnwind,
+                // only pr0 fits inline
+                insn if (insn & 0x8000_0000) != 0 => match (insn >> 24) & 0x0f {
+                    0 => Model::Compact { personality: 0, table: None, opcodes: insn.to_be_bytes()[1..].to_vec() },
+                    n => return Err(Error::invalid("exidx personality", format!("{} inline at {:#x}", n, offset))),
+                },
+                insn => read_table(extab, extab_vaddr, prel31(insn, place.wrapping_add(4)), endian)?,
+            };
+            entries.push(IndexEntry { offset, function: prel31(entry.fnoffset, place), model });
+        }
+        Ok(ArmExidx { vaddr, entries })
+    }
+
+    // entry of the function holding address, by binary search
+    pub fn find(&self, address: u64) -> Option<&IndexEntry> {
+        let index = self.entries.partition_point(|e| e.function <= address);
+        self.entries.get(index.checked_sub(1)?)
+    }
+
+    // Starts of every function by address, those of stripped files included.
+    // Linkers end the table with an EXIDX_CANTUNWIND entry at the end of .text.
+    pub fn function_starts(&self) -> Vec<u64> {
+        let mut starts: Vec<u64> = self.entries.iter().map(|e| e.function).collect();
+        starts.sort_unstable();
+        starts.dedup();
+        starts
+    }
+
+    // rules of the function holding address, None if it cannot be unwound
+    pub fn unwind_row(&self, address: u64) -> Result<Option<UnwindRow>> {
+        match self.find(address) {
+            Some(entry) => entry.unwind_row(),
+            None => Ok(None),
+        }
+    }
+}
+
+impl IndexEntry {
+    pub fn instructions(&self) -> Result<Vec<UnwindOp>> {
+        match &self.model {
+            Model::CantUnwind => Ok(vec![UnwindOp::Refuse]),
+            Model::Compact { opcodes, .. } | Model::Generic { opcodes, .. } => decode(opcodes),
+        }
+    }
+
+    // Rules once the prologue ran, the only place instructions describe.
+    // vsp starts as sp and ends as the CFA. None if it cannot be unwound.
+    pub fn unwind_row(&self) -> Result<Option<UnwindRow>> {
+        let mut base = SP;
+        let mut vsp: i64 = 0;
+        // registers by their offset from base
+        let mut saved: Vec<(u64, i64)> = Vec::new();
+        for op in self.instructions()? {
+            match op {
+                UnwindOp::AddVsp(n) => vsp = vsp.wrapping_add(n),
+                // the offsets of registers popped before would be lost
+                UnwindOp::SetVsp(register) if saved.is_empty() => {
+                    base = register as u64;
+                    vsp = 0;
+                },
+                UnwindOp::PopCore(mask) if (mask & (1 << SP)) == 0 => {
+                    for register in (0..16).filter(|r| (mask & (1 << r)) != 0) {
+                        pop(&mut saved, &mut vsp, register, 4);
+                    }
+                },
+                UnwindOp::PopVfp { first, count, fstmfdx } => {
+                    for register in first..first + count {
+                        pop(&mut saved, &mut vsp, D0 + register as u64, 8);
+                    }
+                    if fstmfdx {
+                        vsp = vsp.wrapping_add(4);
+                    }
+                },
+                UnwindOp::PopWmmx { first, count } => {
+                    for register in first..first + count {
+                        pop(&mut saved, &mut vsp, WR0 + register as u64, 8);
+                    }
+                },
+                UnwindOp::PopWmmxControl(mask) => {
+                    for register in (0..4).filter(|r| (mask & (1 << r)) != 0) {
+                        pop(&mut saved, &mut vsp, WCGR0 + register, 4);
+                    }
+                },
+                UnwindOp::Refuse => return Ok(None),
+                op => return Err(Error::invalid("ARM unwind instructions",
+                    format!("{} at {:#x} has no CFA", op, self.function))),
+            }
+        }
+        let mut registers: Vec<(u64, RegisterRule)> = saved.into_iter()
+            .map(|(register, offset)| (register, RegisterRule::Offset(offset.wrapping_sub(vsp))))
+            .collect();
+        // returns to lr unless pc was popped
+        if !registers.iter().any(|(r, _)| *r == PC) {
+            registers.push((PC, RegisterRule::Register(LR)));
+        }
+        registers.sort_by_key(|(r, _)| *r);
+        Ok(Some(UnwindRow {
+            address: self.function,
+            cfa: CfaRule::RegisterOffset { register: base, offset: vsp },
+            registers,
+        }))
+    }
+}
+
+// register loaded from vsp, a later load of it wins
+fn pop(saved: &mut Vec<(u64, i64)>, vsp: &mut i64, register: u64, size: i64) {
+    saved.retain(|(r, _)| *r != register);
+    saved.push((register, *vsp));
+    *vsp = vsp.wrapping_add(size);
+}
+
+// unwind instructions of opcodes up to the first finish
+pub fn decode(opcodes: &[u8]) -> Result<Vec<UnwindOp>> {
+    let mut instructions: Vec<UnwindOp> = Vec::new();
+    let mut cursor = opcodes;
+    while let Some((&opcode, rest)) = cursor.split_first() {
+        cursor = rest;
+        let position = opcodes.len() - rest.len() - 1;
+        let spare = || Error::invalid("ARM unwind opcode", format!("spare {:#x} at {}", opcode, position));
+        let mut operand = || -> Result<u8> {
+            let (&byte, rest) = cursor.split_first()
+                .ok_or_else(|| Error::invalid("ARM unwind opcode", format!("{:#x} at {} has no operand", opcode, position)))?;
+            cursor = rest;
+            Ok(byte)
+        };
+        let instruction = match opcode {
+            0x00..=0x3f => UnwindOp::AddVsp(((opcode as i64 & 0x3f) << 2) + 4),
+            0x40..=0x7f => UnwindOp::AddVsp(-(((opcode as i64 & 0x3f) << 2) + 4)),
+            0x80..=0x8f => match ((opcode as u16 & 0x0f) << 8) | operand()? as u16 {
+                0 => UnwindOp::Refuse,
+                mask => UnwindOp::PopCore(mask << 4),
+            },
+            // sp and pc are reserved
+            0x9d | 0x9f => return Err(spare()),
+            0x90..=0x9f => UnwindOp::SetVsp(opcode & 0x0f),
+            0xa0..=0xaf => {
+                let last = 4 + (opcode & 0x07);
+                let mask = ((1u16 << (last + 1)) - 1) & !0x0f;
+                match opcode & 0x08 {
+                    0 => UnwindOp::PopCore(mask),
+                    _ => UnwindOp::PopCore(mask | (1 << LR)),
+                }
+            },
+            0xb0 => break,
+            0xb1 => match operand()? {
+                mask if mask != 0 && (mask & 0xf0) == 0 => UnwindOp::PopCore(mask as u16),
+                _ => return Err(spare()),
+            },
+            0xb2 => {
+                let n = leb128::read_uleb128(&mut cursor)
+                    .ok_or_else(|| Error::invalid("ARM unwind opcode", format!("0xb2 at {} has no ULEB128", position)))?;
+                UnwindOp::AddVsp((n as i64).wrapping_shl(2).wrapping_add(0x204))
+            },
+            0xb3 => vfp(operand()?, 0, true)?,
+            0xb8..=0xbf => UnwindOp::PopVfp { first: 8, count: (opcode & 0x07) + 1, fstmfdx: true },
+            0xc0..=0xc5 => UnwindOp::PopWmmx { first: 10, count: (opcode & 0x07) + 1 },
+            0xc6 => {
+                let registers = operand()?;
+                let (first, count) = (registers >> 4, (registers & 0x0f) + 1);
+                if first + count > 16 {
+                    return Err(Error::invalid("ARM unwind opcode", format!("wR{} and {} more at {}", first, count - 1, position)));
+                }
+                UnwindOp::PopWmmx { first, count }
+            },
+            0xc7 => match operand()? {
+                mask if mask != 0 && (mask & 0xf0) == 0 => UnwindOp::PopWmmxControl(mask),
+                _ => return Err(spare()),
+            },
+            0xc8 => vfp(operand()?, 16, false)?,
+            0xc9 => vfp(operand()?, 0, false)?,
+            0xd0..=0xd7 => UnwindOp::PopVfp { first: 8, count: (opcode & 0x07) + 1, fstmfdx: false },
+            _ => return Err(spare()),
+        };
+        instructions.push(instruction);
+    }
+    Ok(instructions)
+}
+
+// operand sssscccc pops d[base + ssss] up to d[base + ssss + cccc]
+fn vfp(registers: u8, base: u8, fstmfdx: bool) -> Result<UnwindOp> {
+    let (first, count) = (base + (registers >> 4), (registers & 0x0f) + 1);
+    if first + count > 32 {
+        return Err(Error::invalid("ARM unwind opcode", format!("d{} and {} more", first, count - 1)));
+    }
+    Ok(UnwindOp::PopVfp { first, count, fstmfdx })
+}
+
+// entry of .ARM.extab at table
+fn read_table(extab: &[u8], extab_vaddr: u64, table: u64, endian: Endian) -> Result<Model> {
+    let word = |n: u64| -> Result<u32> {
+        let start = table.checked_sub(extab_vaddr).map(|offset| offset + n * 4)
+            .filter(|start| start + 4 <= extab.len() as u64)
+            .ok_or_else(|| Error::invalid("exidx table", format!("{:#x} is not in .ARM.extab", table + n * 4)))?;
+        Ok(endian.read_u32(&mut &extab[start as usize..]))
+    };
+    let first = word(0)?;
+    if (first & 0x8000_0000) == 0 {
+        let personality = prel31(first, table);
+        // count of words after this one, then three opcodes
+        let data = word(1)?;
+        let mut opcodes = data.to_be_bytes()[1..].to_vec();
+        for n in 0..(data >> 24) as u64 {
+            opcodes.extend_from_slice(&word(2 + n)?.to_be_bytes());
+        }
+        return Ok(Model::Generic { personality, table, opcodes });
+    }
+    let personality = ((first >> 24) & 0x0f) as u8;
+    let opcodes = match personality {
+        0 => first.to_be_bytes()[1..].to_vec(),
+        1 | 2 => {
+            let mut opcodes = first.to_be_bytes()[2..].to_vec();
+            for n in 0..((first >> 16) & 0xff) as u64 {
+                opcodes.extend_from_slice(&word(1 + n)?.to_be_bytes());
+            }
+            opcodes
+        },
+        n => return Err(Error::invalid("exidx personality", format!("{} at {:#x}", n, table))),
+    };
+    Ok(Model::Compact { personality, table: Some(table), opcodes })
+}
+
+// 31-bit signed offset from place, addresses are 32-bit
+fn prel31(word: u32, place: u64) -> u64 {
+    let offset = ((word << 1) as i32 >> 1) as i64;
+    place.wrapping_add(offset as u64) & 0xffff_ffff
+}
+
+// register lists as readelf -u writes them
+impl fmt::Display for UnwindOp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let range = |prefix: &str, first: u8, count: u8| match count {
+            1 => format!("{}{}", prefix, first),
+            _ => format!("{}{}-{}{}", prefix, first, prefix, first + count - 1),
+        };
+        let list = |prefix: &str, mask: u16| -> String {
+            let names: Vec<String> = (0..16).filter(|r| (mask & (1 << r)) != 0).map(|r| format!("{}{}", prefix, r)).collect();
+            names.join(", ")
+        };
+        match *self {
+            UnwindOp::AddVsp(n) if n < 0 => write!(f, "vsp = vsp - {}", n.wrapping_neg()),
+            UnwindOp::AddVsp(n) => write!(f, "vsp = vsp + {}", n),
+            UnwindOp::SetVsp(register) => write!(f, "vsp = r{}", register),
+            UnwindOp::PopCore(mask) => write!(f, "pop {{{}}}", list("r", mask)),
+            UnwindOp::PopVfp { first, count, fstmfdx: true } => write!(f, "pop {{{}}} fstmfdx", range("d", first, count)),
+            UnwindOp::PopVfp { first, count, fstmfdx: false } => write!(f, "pop {{{}}}", range("d", first, count)),
+            UnwindOp::PopWmmx { first, count } => write!(f, "pop {{{}}}", range("wR", first, count)),
+            UnwindOp::PopWmmxControl(mask) => write!(f, "pop {{{}}}", list("wCGR", mask as u16)),
+            UnwindOp::Refuse => write!(f, "refuse to unwind"),
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use std::{fmt, io::Read};
+use flate2::read::ZlibDecoder;
+use ruzstd::decoding::StreamingDecoder;
+use crate::{
+    elf::{elf32, elf64, endian::Endian, value},
+    error::{Error, Result},
+};
+
+
+// Uncompressed size is taken from the file, buffers are only reserved up to
+// this ahead and grow with what the stream really holds.
+const MAX_RESERVE: u64 = 1 << 24;
+
+// layout of compressed section data
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    // SHF_COMPRESSED with ELFCOMPRESS_ZLIB
+    Zlib,
+    // SHF_COMPRESSED with ELFCOMPRESS_ZSTD
+    Zstd,
+    // .zdebug_* of older GNU toolchains, "ZLIB" and the big endian size before a zlib stream
+    ZlibGnu,
+}
+
+// compressed section with its sizes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Compression {
+    pub format: Format,
+    // sh_size, header included
+    pub compressed_size: u64,
+    pub size: u64,
+    pub align: u64,
+    // bytes of header before the stream
+    header_size: usize,
+}
+
+impl Compression {
+    // Compression of a section from the start of its stored data, None if it
+    // is stored as is. .zdebug sections without "ZLIB" are not compressed.
+    pub fn detect(name: &str, header: &elf64::SectionHeader, data: &[u8], class: u8, endian: Endian)
+        -> Result<Option<Compression>>
+    {
+        let compressed_size = header.sh_size;
+        if header.sh_type == value::SHT_NOBITS {
+            return Ok(None);
+        }
+        if (header.sh_flags & value::SHF_COMPRESSED as u64) != 0 {
+            let header_size = if class == value::ELFCLASS32 { 12 } else { 24 };
+            if data.len() < header_size {
+                return Err(Error::truncated(format!("compression header of {}", name), header.sh_offset, header_size as u64));
+            }
+            let chdr = match class {
+                value::ELFCLASS32 => elf64::CompressionHeader::from(&elf32::CompressionHeader::parse(data, endian)),
+                _ => elf64::CompressionHeader::parse(data, endian),
+            };
+            let format = match chdr.ch_type {
+                value::ELFCOMPRESS_ZLIB => Format::Zlib,
+                value::ELFCOMPRESS_ZSTD => Format::Zstd,
+                n => return Err(Error::invalid("ch_type", format!("{:#x} of {}", n, name))),
+            };
+            return Ok(Some(Compression { format, compressed_size, size: chdr.ch_size, align: chdr.ch_addralign, header_size }));
+        }
+        if name.starts_with(".zdebug") && data.len() >= 12 && data.starts_with(b"ZLIB") {
+            let size = Endian::Big.read_u64(&mut &data[4..]);
+            return Ok(Some(Compression { format: Format::ZlibGnu, compressed_size, size, align: 1, header_size: 12 }));
+        }
+        Ok(None)
+    }
+
+    // uncompressed content of the stored data the compression was detected in
+    pub fn decompress(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let stream = data.get(self.header_size..).unwrap_or(&[]);
+        let mut buf: Vec<u8> = Vec::with_capacity(self.size.min(MAX_RESERVE) as usize);
+        let result = match self.format {
+            Format::Zlib | Format::ZlibGnu => ZlibDecoder::new(stream).take(self.size).read_to_end(&mut buf),
+            Format::Zstd => match StreamingDecoder::new(stream) {
+                Ok(decoder) => decoder.take(self.size).read_to_end(&mut buf),
+                Err(e) => return Err(Error::invalid("zstd stream", e.to_string())),
+            },
+        };
+        if let Err(e) = result {
+            return Err(Error::invalid(format!("{} stream", self.format), e.to_string()));
+        }
+        if buf.len() as u64 != self.size {
+            return Err(Error::invalid(format!("{} stream", self.format),
+                format!("{:#x} bytes instead of {:#x}", buf.len(), self.size)));
+        }
+        Ok(buf)
+    }
+}
+
+// as --compress-debug-sections names them
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Format::Zlib => write!(f, "zlib"),
+            Format::Zstd => write!(f, "zstd"),
+            Format::ZlibGnu => write!(f, "zlib-gnu"),
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use std::fmt;
+
+
+// mangling scheme recognized by its prefix
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scheme {
+    // Rust legacy (_ZN..17h<hash>E) and v0 (_R)
+    Rust,
+    // Itanium C++ ABI (_Z), used by GCC and Clang on every ELF target
+    Cpp,
+}
+
+impl fmt::Display for Scheme {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Scheme::Rust => write!(f, "Rust"),
+            Scheme::Cpp => write!(f, "C++"),
+        }
+    }
+}
+
+// Readable form of a mangled name, None when it is not mangled or cannot
+// be parsed. Legacy Rust names are valid Itanium names as well, so Rust is
+// tried first. The hash of legacy Rust names is left out, as c++filt does.
+pub fn demangle(name: &str) -> Option<String> {
+    demangle_with_scheme(name).map(|(demangled, _)| demangled)
+}
+
+pub fn demangle_with_scheme(name: &str) -> Option<(String, Scheme)> {
+    if let Ok(symbol) = rustc_demangle::try_demangle(name) {
+        return Some((format!("{:#}", symbol), Scheme::Rust));
+    }
+    // cpp_demangle also accepts bare types, as "i" for int
+    if !name.starts_with("_Z") {
+        return None;
+    }
+    let symbol = cpp_demangle::Symbol::new(name.as_bytes()).ok()?;
+    let demangled = symbol.demangle().ok()?;
+    let demangled = special_name(name, &demangled).unwrap_or(demangled);
+    Some((demangled, Scheme::Cpp))
+}
+
+// cpp_demangle writes vtables and thunks as "{vtable(T)}" or
+// "{virtual override thunk({offset(-16)}, f())}", c++filt as "vtable for T"
+// and "non-virtual thunk to f()"
+fn special_name(name: &str, demangled: &str) -> Option<String> {
+    let (open, offsets, label) = match name.get(..4)? {
+        "_ZTV" => ("{vtable(", 0, "vtable for "),
+        "_ZTT" => ("{vtt(", 0, "VTT for "),
+        "_ZTh" => ("{virtual override thunk(", 1, "non-virtual thunk to "),
+        "_ZTv" => ("{virtual override thunk(", 1, "virtual thunk to "),
+        "_ZTc" => ("{virtual override thunk(", 2, "covariant return thunk to "),
+        _ => return None,
+    };
+    let mut inner = demangled.strip_prefix(open)?.strip_suffix(")}")?;
+    for _ in 0..offsets {
+        inner = &inner[inner.find("}, ")? + 3..];
+    }
+    Some(format!("{}{}", label, inner))
+}
+
+// demangled name, or name itself when not mangled
+pub fn demangle_or_raw(name: &str) -> String {
+    demangle(name).unwrap_or_else(|| name.to_string())
+}
@@ -0,0 +1,467 @@
+use std::{convert::TryFrom, fmt};
+use crate::{
+    elf::{endian::Endian, leb128, value},
+    error::{Error, Result},
+};
+
+
+// sections a line table is read from, empty when missing
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LineSections<'a> {
+    pub debug_line: &'a [u8],
+    // strings of DW_FORM_line_strp, DWARF 5
+    pub debug_line_str: &'a [u8],
+    // strings of DW_FORM_strp
+    pub debug_str: &'a [u8],
+}
+
+// entry of the file name table
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileEntry {
+    pub path: String,
+    pub directory: u64,
+}
+
+// row of the line number matrix
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LineRow {
+    pub address: u64,
+    pub file: u64,
+    pub line: u64,
+    pub column: u64,
+    pub is_stmt: bool,
+    // first address past the sequence, the row maps nothing
+    pub end_sequence: bool,
+}
+
+// line number program of one unit in .debug_line, run into its rows
+#[derive(Debug, Clone, Default)]
+pub struct LineProgram {
+    pub offset: u64,
+    pub version: u16,
+    pub address_size: u8,
+    pub minimum_instruction_length: u8,
+    pub maximum_operations_per_instruction: u8,
+    pub default_is_stmt: bool,
+    pub line_base: i8,
+    pub line_range: u8,
+    pub opcode_base: u8,
+    pub standard_opcode_lengths: Vec<u8>,
+    // DWARF 5 puts the compilation directory first, older versions leave it out
+    pub directories: Vec<String>,
+    // DWARF 5 counts from 0, older versions from 1
+    pub files: Vec<FileEntry>,
+    pub rows: Vec<LineRow>,
+}
+
+// source position of an address
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceLocation {
+    pub path: String,
+    pub line: u64,
+    pub column: u64,
+}
+
+// Bounds-checked reader over a DWARF section. Offsets of errors are
+// relative to the section, as readelf and objdump print them.
+pub(crate) struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    end: usize,
+    endian: Endian,
+    what: &'static str,
+}
+
+// state of the line number state machine
+struct LineState {
+    row: LineRow,
+    op_index: u64,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(data: &'a [u8], endian: Endian, what: &'static str) -> Reader<'a> {
+        Reader { data, pos: 0, end: data.len(), endian, what }
+    }
+
+    pub fn offset(&self) -> usize {
+        self.pos
+    }
+
+    // bytes left to read
+    pub fn len(&self) -> usize {
+        self.end - self.pos
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pos >= self.end
+    }
+
+    // reader of the next size bytes, which are skipped here
+    pub fn split(&mut self, size: u64) -> Result<Reader<'a>> {
+        let start = self.pos;
+        self.take(size)?;
+        Ok(Reader { data: self.data, pos: start, end: self.pos, endian: self.endian, what: self.what })
+    }
+
+    pub fn take(&mut self, size: u64) -> Result<&'a [u8]> {
+        let end = usize::try_from(size).ok()
+            .and_then(|size| self.pos.checked_add(size))
+            .filter(|end| *end <= self.end)
+            .ok_or_else(|| Error::truncated(self.what, self.pos as u64, size))?;
+        let data = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(data)
+    }
+
+    pub fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub fn u16(&mut self) -> Result<u16> {
+        let mut data = self.take(2)?;
+        Ok(self.endian.read_u16(&mut data))
+    }
+
+    pub fn u32(&mut self) -> Result<u32> {
+        let mut data = self.take(4)?;
+        Ok(self.endian.read_u32(&mut data))
+    }
+
+    pub fn u64(&mut self) -> Result<u64> {
+        let mut data = self.take(8)?;
+        Ok(self.endian.read_u64(&mut data))
+    }
+
+    // unsigned number of 1, 2, 4 or 8 bytes
+    pub fn uint(&mut self, size: u64) -> Result<u64> {
+        match size {
+            1 => self.u8().map(u64::from),
+            2 => self.u16().map(u64::from),
+            4 => self.u32().map(u64::from),
+            8 => self.u64(),
+            n => Err(Error::invalid(self.what, format!("{}-byte number at offset {:#x}", n, self.pos))),
+        }
+    }
+
+    pub fn uleb128(&mut self) -> Result<u64> {
+        let mut cursor = &self.data[self.pos..self.end];
+        let n = leb128::read_uleb128(&mut cursor)
+            .ok_or_else(|| Error::invalid(self.what, format!("LEB128 at offset {:#x}", self.pos)))?;
+        self.pos = self.end - cursor.len();
+        Ok(n)
+    }
+
+    pub fn sleb128(&mut self) -> Result<i64> {
+        let mut cursor = &self.data[self.pos..self.end];
+        let n = leb128::read_sleb128(&mut cursor)
+            .ok_or_else(|| Error::invalid(self.what, format!("LEB128 at offset {:#x}", self.pos)))?;
+        self.pos = self.end - cursor.len();
+        Ok(n)
+    }
+
+    // null-terminated string, without the null
+    pub fn cstr(&mut self) -> Result<&'a [u8]> {
+        let rest = &self.data[self.pos..self.end];
+        let len = rest.iter().position(|c| *c == 0)
+            .ok_or_else(|| Error::truncated(self.what, self.pos as u64, rest.len() as u64 + 1))?;
+        self.pos += len + 1;
+        Ok(&rest[..len])
+    }
+
+    // unit length, 0xffffffff escapes the 64-bit DWARF format
+    pub fn initial_length(&mut self) -> Result<(u64, bool)> {
+        match self.u32()? {
+            0xffff_ffff => Ok((self.u64()?, true)),
+            n if n >= 0xffff_fff0 => Err(Error::invalid(self.what, format!("unit length {:#x}", n))),
+            n => Ok((n as u64, false)),
+        }
+    }
+
+    // section offset, 8 bytes in the 64-bit DWARF format
+    pub fn section_offset(&mut self, dwarf64: bool) -> Result<u64> {
+        match dwarf64 {
+            true => self.u64(),
+            false => self.u32().map(u64::from),
+        }
+    }
+}
+
+// line programs of every unit in .debug_line, address_size is taken from
+// the ELF class for versions before 5, which do not record it
+pub fn parse_line_programs(sections: &LineSections, endian: Endian, address_size: u8) -> Result<Vec<LineProgram>> {
+    let mut reader = Reader::new(sections.debug_line, endian, ".debug_line");
+    let mut programs: Vec<LineProgram> = Vec::new();
+    while !reader.is_empty() {
+        programs.push(LineProgram::parse(&mut reader, sections, address_size)?);
+    }
+    Ok(programs)
+}
+
+// source position of address by the first program covering it
+pub fn find_location(programs: &[LineProgram], address: u64) -> Option<SourceLocation> {
+    programs.iter().find_map(|program| {
+        let row = program.find_row(address)?;
+        Some(SourceLocation {
+            path: program.file_path(row.file).unwrap_or_else(|| String::from("??")),
+            line: row.line,
+            column: row.column,
+        })
+    })
+}
+
+impl LineProgram {
+    fn parse(reader: &mut Reader, sections: &LineSections, address_size: u8) -> Result<LineProgram> {
+        let offset = reader.offset() as u64;
+        let (length, dwarf64) = reader.initial_length()?;
+        let mut unit = reader.split(length)?;
+        let mut program = LineProgram { offset, address_size, ..Default::default() };
+        program.version = unit.u16()?;
+        if !(2..=5).contains(&program.version) {
+            return Err(Error::invalid("DWARF version", format!("{} of line program at {:#x}", program.version, offset)));
+        }
+        if program.version >= 5 {
+            program.address_size = unit.u8()?;
+            let _segment_selector_size = unit.u8()?;
+        }
+        let header_length = unit.section_offset(dwarf64)?;
+        let mut header = unit.split(header_length)?;
+        program.minimum_instruction_length = header.u8()?;
+        program.maximum_operations_per_instruction = match program.version {
+            2 | 3 => 1,
+            _ => header.u8()?,
+        };
+        program.default_is_stmt = header.u8()? != 0;
+        program.line_base = header.u8()? as i8;
+        program.line_range = header.u8()?;
+        program.opcode_base = header.u8()?;
+        if program.line_range == 0 {
+            return Err(Error::invalid("line_range", format!("0 in line program at {:#x}", offset)));
+        }
+        program.standard_opcode_lengths = header.take(program.opcode_base.saturating_sub(1) as u64)?.to_vec();
+        if program.version >= 5 {
+            program.parse_entries_v5(&mut header, sections, dwarf64)?;
+        } else {
+            program.parse_entries(&mut header)?;
+        }
+        program.run(&mut unit)?;
+        Ok(program)
+    }
+
+    // null-terminated lists of DWARF 2 to 4
+    fn parse_entries(&mut self, header: &mut Reader) -> Result<()> {
+        loop {
+            let directory = header.cstr()?;
+            if directory.is_empty() {
+                break;
+            }
+            self.directories.push(String::from_utf8_lossy(directory).into_owned());
+        }
+        loop {
+            let path = header.cstr()?;
+            if path.is_empty() {
+                break;
+            }
+            let directory = header.uleb128()?;
+            let _mtime = header.uleb128()?;
+            let _size = header.uleb128()?;
+            self.files.push(FileEntry { path: String::from_utf8_lossy(path).into_owned(), directory });
+        }
+        Ok(())
+    }
+
+    // lists of DWARF 5, each described by (content type, form) pairs
+    fn parse_entries_v5(&mut self, header: &mut Reader, sections: &LineSections, dwarf64: bool) -> Result<()> {
+        for list in 0..2 {
+            let format_count = header.u8()?;
+            let mut format: Vec<(u64, u64)> = Vec::new();
+            for _ in 0..format_count {
+                format.push((header.uleb128()?, header.uleb128()?));
+            }
+            let count = header.uleb128()?;
+            for _ in 0..count {
+                let mut entry = FileEntry::default();
+                for (content, form) in &format {
+                    let (number, string) = read_form(header, *form, sections, dwarf64)?;
+                    match *content {
+                        value::DW_LNCT_PATH => entry.path = string.unwrap_or_default(),
+                        value::DW_LNCT_DIRECTORY_INDEX => entry.directory = number,
+                        _ => (),
+                    }
+                }
+                match list {
+                    0 => self.directories.push(entry.path),
+                    _ => self.files.push(entry),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // run the opcodes after the header, appending a row for each emitted
+    fn run(&mut self, program: &mut Reader) -> Result<()> {
+        let mut state = self.initial_state();
+        while !program.is_empty() {
+            let opcode = program.u8()?;
+            if opcode >= self.opcode_base {
+                let adjusted = opcode - self.opcode_base;
+                self.advance(&mut state, (adjusted / self.line_range) as u64);
+                let delta = self.line_base as i64 + (adjusted % self.line_range) as i64;
+                state.row.line = state.row.line.wrapping_add(delta as u64);
+                self.rows.push(state.row);
+                continue;
+            }
+            match opcode {
+                0 => {
+                    let size = program.uleb128()?;
+                    let mut extended = program.split(size)?;
+                    if size == 0 {
+                        continue;
+                    }
+                    match extended.u8()? {
+                        value::DW_LNE_END_SEQUENCE => {
+                            state.row.end_sequence = true;
+                            self.rows.push(state.row);
+                            state = self.initial_state();
+                        },
+                        value::DW_LNE_SET_ADDRESS => {
+                            state.row.address = extended.uint(size - 1)?;
+                            state.op_index = 0;
+                        },
+                        value::DW_LNE_DEFINE_FILE => {
+                            let path = String::from_utf8_lossy(extended.cstr()?).into_owned();
+                            let directory = extended.uleb128()?;
+                            self.files.push(FileEntry { path, directory });
+                        },
+                        // DW_LNE_set_discriminator and vendor opcodes
+                        _ => (),
+                    }
+                },
+                value::DW_LNS_COPY => self.rows.push(state.row),
+                value::DW_LNS_ADVANCE_PC => {
+                    let advance = program.uleb128()?;
+                    self.advance(&mut state, advance);
+                },
+                value::DW_LNS_ADVANCE_LINE => {
+                    let delta = program.sleb128()?;
+                    state.row.line = state.row.line.wrapping_add(delta as u64);
+                },
+                value::DW_LNS_SET_FILE => state.row.file = program.uleb128()?,
+                value::DW_LNS_SET_COLUMN => state.row.column = program.uleb128()?,
+                value::DW_LNS_NEGATE_STMT => state.row.is_stmt = !state.row.is_stmt,
+                value::DW_LNS_CONST_ADD_PC => {
+                    let adjusted = 255 - self.opcode_base;
+                    self.advance(&mut state, (adjusted / self.line_range) as u64);
+                },
+                value::DW_LNS_FIXED_ADVANCE_PC => {
+                    state.row.address = state.row.address.wrapping_add(program.u16()? as u64);
+                    state.op_index = 0;
+                },
+                // basic block, prologue and epilogue marks and ISA are not kept
+                value::DW_LNS_SET_BASIC_BLOCK | value::DW_LNS_SET_PROLOGUE_END | value::DW_LNS_SET_EPILOGUE_BEGIN => (),
+                // DW_LNS_set_isa and opcodes newer than the reader, skipped by their declared operands
+                n => {
+                    for _ in 0..self.standard_opcode_lengths[n as usize - 1] {
+                        program.uleb128()?;
+                    }
+                },
+            }
+        }
+        Ok(())
+    }
+
+    fn initial_state(&self) -> LineState {
+        LineState {
+            row: LineRow {
+                file: 1,
+                line: 1,
+                is_stmt: self.default_is_stmt,
+                ..Default::default()
+            },
+            op_index: 0,
+        }
+    }
+
+    // operation advance, op_index only matters to VLIW targets
+    fn advance(&self, state: &mut LineState, operations: u64) {
+        let min_length = self.minimum_instruction_length as u64;
+        match self.maximum_operations_per_instruction as u64 {
+            0 | 1 => state.row.address = state.row.address.wrapping_add(min_length.wrapping_mul(operations)),
+            max => {
+                let index = state.op_index.wrapping_add(operations);
+                state.row.address = state.row.address.wrapping_add(min_length.wrapping_mul(index / max));
+                state.op_index = index % max;
+            },
+        }
+    }
+
+    // Last row at or below address in the sequence holding it. Rows of a
+    // sequence rise in address, the end_sequence row closes its range.
+    pub fn find_row(&self, address: u64) -> Option<&LineRow> {
+        self.rows.windows(2)
+            .find(|pair| !pair[0].end_sequence && pair[0].address <= address && address < pair[1].address)
+            .map(|pair| &pair[0])
+    }
+
+    // path of file as written by the compiler, joined to its directory
+    // unless absolute. Directory 0 of older versions is the compilation
+    // directory, which only .debug_info records, so the path is kept relative.
+    pub fn file_path(&self, file: u64) -> Option<String> {
+        let (entry, directory) = match self.version {
+            5 => {
+                let entry = self.files.get(file as usize)?;
+                (entry, self.directories.get(entry.directory as usize))
+            },
+            _ => {
+                let entry = self.files.get((file as usize).checked_sub(1)?)?;
+                let directory = (entry.directory as usize).checked_sub(1).and_then(|d| self.directories.get(d));
+                (entry, directory)
+            },
+        };
+        match directory {
+            Some(directory) if !entry.path.starts_with('/') && !directory.is_empty() =>
+                Some(format!("{}/{}", directory.trim_end_matches('/'), entry.path)),
+            _ => Some(entry.path.clone()),
+        }
+    }
+}
+
+// value of form as a number or a string, blocks are skipped
+fn read_form(reader: &mut Reader, form: u64, sections: &LineSections, dwarf64: bool) -> Result<(u64, Option<String>)> {
+    let string = |table: &[u8], offset: u64| -> Option<String> {
+        let rest = table.get(usize::try_from(offset).ok()?..)?;
+        let end = rest.iter().position(|c| *c == 0)?;
+        Some(String::from_utf8_lossy(&rest[..end]).into_owned())
+    };
+    let (number, text) = match form {
+        value::DW_FORM_STRING => (0, Some(String::from_utf8_lossy(reader.cstr()?).into_owned())),
+        value::DW_FORM_LINE_STRP => (0, string(sections.debug_line_str, reader.section_offset(dwarf64)?)),
+        value::DW_FORM_STRP => (0, string(sections.debug_str, reader.section_offset(dwarf64)?)),
+        // indexes of .debug_str_offsets need the base in .debug_info
+        value::DW_FORM_STRX | value::DW_FORM_UDATA => (reader.uleb128()?, None),
+        value::DW_FORM_STRX1 | value::DW_FORM_DATA1 => (reader.uint(1)?, None),
+        value::DW_FORM_STRX2 | value::DW_FORM_DATA2 => (reader.uint(2)?, None),
+        value::DW_FORM_STRX4 | value::DW_FORM_DATA4 => (reader.uint(4)?, None),
+        value::DW_FORM_DATA8 => (reader.uint(8)?, None),
+        value::DW_FORM_STRX3 | value::DW_FORM_DATA16 | value::DW_FORM_BLOCK |
+        value::DW_FORM_BLOCK1 | value::DW_FORM_BLOCK2 | value::DW_FORM_BLOCK4 => {
+            let size = match form {
+                value::DW_FORM_STRX3 => 3,
+                value::DW_FORM_DATA16 => 16,
+                value::DW_FORM_BLOCK => reader.uleb128()?,
+                value::DW_FORM_BLOCK1 => reader.uint(1)?,
+                value::DW_FORM_BLOCK2 => reader.uint(2)?,
+                _ => reader.uint(4)?,
+            };
+            reader.take(size)?;
+            (0, None)
+        },
+        n => return Err(Error::invalid("DW_FORM", format!("{:#x} in line table header", n))),
+    };
+    Ok((number, text))
+}
+
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.path, self.line)
+    }
+}
@@ -0,0 +1,170 @@
+use crate::elf::{elf64, file::Symbol, value};
+
+
+// entries of the dynamic section with the DT_STRTAB content they refer to
+#[derive(Debug, Default)]
+pub struct Dynamic {
+    pub entries: Vec<elf64::DynamicEntry>,
+    pub strtab: Vec<u8>,
+}
+
+impl Dynamic {
+    pub fn new() -> Dynamic {
+        Self::default()
+    }
+
+    // value of first entry with tag
+    pub fn get(&self, tag: i64) -> Option<u64> {
+        self.entries.iter()
+            .find(|e| e.d_tag == tag)
+            .map(|e| e.d_val)
+    }
+
+    // values of all entries with tag, DT_NEEDED may appear many times
+    pub fn get_all(&self, tag: i64) -> Vec<u64> {
+        self.entries.iter()
+            .filter(|e| e.d_tag == tag)
+            .map(|e| e.d_val)
+            .collect()
+    }
+
+    // string at offset of DT_STRTAB
+    pub fn string(&self, offset: u64) -> Option<String> {
+        let pos = offset as usize;
+        if pos >= self.strtab.len() {
+            return None;
+        }
+        let end = self.strtab[pos..].iter()
+            .position(|c| *c == b'\0')
+            .map_or(self.strtab.len(), |n| pos + n);
+        Some(String::from_utf8_lossy(&self.strtab[pos..end]).into_owned())
+    }
+
+    // resolved string of entry, None if tag is not string-valued
+    pub fn entry_string(&self, entry: &elf64::DynamicEntry) -> Option<String> {
+        if !is_string_tag(entry.d_tag) {
+            return None;
+        }
+        self.string(entry.d_val)
+    }
+
+    pub fn needed(&self) -> Vec<String> {
+        self.get_all(value::DT_NEEDED).into_iter()
+            .filter_map(|offset| self.string(offset))
+            .collect()
+    }
+
+    pub fn soname(&self) -> Option<String> {
+        self.get(value::DT_SONAME).and_then(|offset| self.string(offset))
+    }
+
+    pub fn rpath(&self) -> Option<String> {
+        self.get(value::DT_RPATH).and_then(|offset| self.string(offset))
+    }
+
+    pub fn runpath(&self) -> Option<String> {
+        self.get(value::DT_RUNPATH).and_then(|offset| self.string(offset))
+    }
+}
+
+// tables located by PT_DYNAMIC alone, for files without usable section headers
+#[derive(Debug, Default)]
+pub struct DynamicInfo {
+    // DT_SYMTAB, count derived from DT_HASH or DT_GNU_HASH
+    pub symbols: Vec<Symbol>,
+    // DT_REL and DT_RELA
+    pub rel: Vec<elf64::RelEntry>,
+    pub rela: Vec<elf64::RelaEntry>,
+    // DT_JMPREL, entry kind is given by DT_PLTREL
+    pub plt_rel: Vec<elf64::RelEntry>,
+    pub plt_rela: Vec<elf64::RelaEntry>,
+    // DT_ANDROID_REL and DT_ANDROID_RELA, unpacked from APS2
+    pub android_rel: Vec<elf64::RelEntry>,
+    pub android_rela: Vec<elf64::RelaEntry>,
+    // DT_RELR and DT_ANDROID_RELR, relative relocations with addends in place
+    pub relr: Vec<elf64::RelEntry>,
+    // DT_INIT and DT_FINI
+    pub init: Option<u64>,
+    pub fini: Option<u64>,
+    // pointers as stored in file, RELATIVE relocations may still apply to them
+    pub init_array: Vec<u64>,
+    pub fini_array: Vec<u64>,
+    pub preinit_array: Vec<u64>,
+}
+
+impl DynamicInfo {
+    pub fn new() -> DynamicInfo {
+        Self::default()
+    }
+}
+
+// tags whose d_val is an offset of DT_STRTAB
+pub fn is_string_tag(tag: i64) -> bool {
+    matches!(tag, value::DT_NEEDED | value::DT_SONAME | value::DT_RPATH |
+        value::DT_RUNPATH | value::DT_AUXILIARY | value::DT_FILTER)
+}
+
+pub fn tag_name(tag: i64) -> &'static str {
+    match tag {
+        value::DT_NULL => "DT_NULL",
+        value::DT_NEEDED => "DT_NEEDED",
+        value::DT_PLTRELSZ => "DT_PLTRELSZ",
+        value::DT_PLTGOT => "DT_PLTGOT",
+        value::DT_HASH => "DT_HASH",
+        value::DT_STRTAB => "DT_STRTAB",
+        value::DT_SYMTAB => "DT_SYMTAB",
+        value::DT_RELA => "DT_RELA",
+        value::DT_RELASZ => "DT_RELASZ",
+        value::DT_RELAENT => "DT_RELAENT",
+        value::DT_STRSZ => "DT_STRSZ",
+        value::DT_SYMENT => "DT_SYMENT",
+        value::DT_INIT => "DT_INIT",
+        value::DT_FINI => "DT_FINI",
+        value::DT_SONAME => "DT_SONAME",
+        value::DT_RPATH => "DT_RPATH",
+        value::DT_SYMBOLIC => "DT_SYMBOLIC",
+        value::DT_REL => "DT_REL",
+        value::DT_RELSZ => "DT_RELSZ",
+        value::DT_RELENT => "DT_RELENT",
+        value::DT_PLTREL => "DT_PLTREL",
+        value::DT_DEBUG => "DT_DEBUG",
+        value::DT_TEXTREL => "DT_TEXTREL",
+        value::DT_JMPREL => "DT_JMPREL",
+        value::DT_BIND_NOW => "DT_BIND_NOW",
+        value::DT_INIT_ARRAY => "DT_INIT_ARRAY",
+        value::DT_FINI_ARRAY => "DT_FINI_ARRAY",
+        value::DT_INIT_ARRAYSZ => "DT_INIT_ARRAYSZ",
+        value::DT_FINI_ARRAYSZ => "DT_FINI_ARRAYSZ",
+        value::DT_RUNPATH => "DT_RUNPATH",
+        value::DT_FLAGS => "DT_FLAGS",
+        value::DT_PREINIT_ARRAY => "DT_PREINIT_ARRAY",
+        value::DT_PREINIT_ARRAYSZ => "DT_PREINIT_ARRAYSZ",
+        value::DT_SYMTAB_SHNDX => "DT_SYMTAB_SHNDX",
+        value::DT_RELRSZ => "DT_RELRSZ",
+        value::DT_RELR => "DT_RELR",
+        value::DT_RELRENT => "DT_RELRENT",
+        value::DT_ANDROID_REL => "DT_ANDROID_REL",
+        value::DT_ANDROID_RELSZ => "DT_ANDROID_RELSZ",
+        value::DT_ANDROID_RELA => "DT_ANDROID_RELA",
+        value::DT_ANDROID_RELASZ => "DT_ANDROID_RELASZ",
+        value::DT_ANDROID_RELR => "DT_ANDROID_RELR",
+        value::DT_ANDROID_RELRSZ => "DT_ANDROID_RELRSZ",
+        value::DT_ANDROID_RELRENT => "DT_ANDROID_RELRENT",
+        value::DT_GNU_HASH => "DT_GNU_HASH",
+        value::DT_TLSDESC_PLT => "DT_TLSDESC_PLT",
+        value::DT_TLSDESC_GOT => "DT_TLSDESC_GOT",
+        value::DT_VERSYM => "DT_VERSYM",
+        value::DT_RELACOUNT => "DT_RELACOUNT",
+        value::DT_RELCOUNT => "DT_RELCOUNT",
+        value::DT_FLAGS_1 => "DT_FLAGS_1",
+        value::DT_VERDEF => "DT_VERDEF",
+        value::DT_VERDEFNUM => "DT_VERDEFNUM",
+        value::DT_VERNEED => "DT_VERNEED",
+        value::DT_VERNEEDNUM => "DT_VERNEEDNUM",
+        value::DT_AUXILIARY => "DT_AUXILIARY",
+        value::DT_FILTER => "DT_FILTER",
+        n if (value::DT_LOOS..=value::DT_HIOS).contains(&n) => "DT_LOOS+",
+        n if (value::DT_LOPROC..=value::DT_HIPROC).contains(&n) => "DT_LOPROC+",
+        _ => "UNKNOWN",
+    }
+}
@@ -0,0 +1,525 @@
+use std::{fmt, ops::Range};
+use crate::{
+    elf::{dwarf::Reader, endian::Endian, value},
+    error::{Error, Result},
+};
+
+
+// Distinct registers with a rule in one row. Every row of an unwind table
+// is a copy of these, hostile instructions must not grow them without end.
+const MAX_RULES: usize = 256;
+
+// common information entry of .eh_frame
+#[derive(Debug, Clone, Default)]
+pub struct Cie {
+    pub offset: u64,
+    pub version: u8,
+    pub augmentation: String,
+    pub code_alignment_factor: u64,
+    pub data_alignment_factor: i64,
+    pub return_address_register: u64,
+    // encoding of pc_begin, pc_range and DW_CFA_set_loc of its FDEs
+    pub fde_encoding: u8,
+    pub lsda_encoding: u8,
+    // address of the personality routine, or of the pointer to it when indirect
+    pub personality: Option<u64>,
+    pub signal_frame: bool,
+    // initial instructions, by offset in EhFrame::data
+    pub instructions: Range<usize>,
+}
+
+// frame description entry, one for each function
+#[derive(Debug, Clone, Default)]
+pub struct Fde {
+    pub offset: u64,
+    // index in EhFrame::cies
+    pub cie: usize,
+    pub pc_begin: u64,
+    pub pc_range: u64,
+    // language-specific data area, the C++ exception table
+    pub lsda: Option<u64>,
+    pub instructions: Range<usize>,
+}
+
+// content of .eh_frame with its entries decoded
+#[derive(Debug, Clone)]
+pub struct EhFrame {
+    pub vaddr: u64,
+    pub data: Vec<u8>,
+    pub cies: Vec<Cie>,
+    pub fdes: Vec<Fde>,
+    address_size: u8,
+    endian: Endian,
+}
+
+// search table of .eh_frame_hdr, sorted by initial location
+#[derive(Debug, Clone)]
+pub struct EhFrameHdr {
+    pub vaddr: u64,
+    pub version: u8,
+    pub eh_frame_ptr: u64,
+    // (initial location, FDE address)
+    pub table: Vec<(u64, u64)>,
+}
+
+// function start and size covered by an FDE
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FunctionRange {
+    pub start: u64,
+    pub size: u64,
+}
+
+// canonical frame address, the stack pointer of the caller before the call
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CfaRule {
+    RegisterOffset { register: u64, offset: i64 },
+    Expression(Vec<u8>),
+}
+
+// where the caller's value of a register is
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegisterRule {
+    Undefined,
+    SameValue,
+    // saved at CFA + offset
+    Offset(i64),
+    // value is CFA + offset
+    ValOffset(i64),
+    Register(u64),
+    Expression(Vec<u8>),
+    ValExpression(Vec<u8>),
+}
+
+// rules from address up to the next row, registers without one keep their value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnwindRow {
+    pub address: u64,
+    pub cfa: CfaRule,
+    // sorted by register number
+    pub registers: Vec<(u64, RegisterRule)>,
+}
+
+// bases of relative pointer encodings
+#[derive(Clone, Copy)]
+struct PointerBase {
+    // address of the section start, DW_EH_PE_pcrel adds its offset to it
+    section: u64,
+    // DW_EH_PE_datarel, the .eh_frame_hdr start
+    data: Option<u64>,
+    address_size: u8,
+}
+
+impl EhFrame {
+    // data of .eh_frame loaded at vaddr, up to its zero terminator if any
+    pub fn parse(data: Vec<u8>, vaddr: u64, class: u8, endian: Endian) -> Result<EhFrame> {
+        let address_size = if class == value::ELFCLASS32 { 4 } else { 8 };
+        let base = PointerBase { section: vaddr, data: None, address_size };
+        let mut cies: Vec<Cie> = Vec::new();
+        let mut fdes: Vec<Fde> = Vec::new();
+        let mut reader = Reader::new(&data, endian, ".eh_frame");
+        while !reader.is_empty() {
+            let offset = reader.offset() as u64;
+            let (length, dwarf64) = reader.initial_length()?;
+            if length == 0 {
+                break;
+            }
+            let mut entry = reader.split(length)?;
+            let id_offset = entry.offset() as u64;
+            // CIE id is 0, FDEs point back to their CIE from here
+            let id = entry.section_offset(dwarf64)?;
+            if id == 0 {
+                cies.push(parse_cie(&mut entry, offset, base)?);
+                continue;
+            }
+            let cie = id_offset.checked_sub(id)
+                .and_then(|cie_offset| cies.iter().position(|c| c.offset == cie_offset))
+                .ok_or_else(|| Error::invalid("CIE pointer", format!("{:#x} of FDE at {:#x}", id, offset)))?;
+            fdes.push(parse_fde(&mut entry, offset, cie, &cies[cie], base)?);
+        }
+        Ok(EhFrame { vaddr, data, cies, fdes, address_size, endian })
+    }
+
+    // FDE covering address
+    pub fn find_fde(&self, address: u64) -> Option<&Fde> {
+        self.fdes.iter().find(|f| address >= f.pc_begin && address - f.pc_begin < f.pc_range)
+    }
+
+    // FDE at vaddr, as .eh_frame_hdr points to them
+    pub fn fde_at(&self, vaddr: u64) -> Option<&Fde> {
+        let offset = vaddr.checked_sub(self.vaddr)?;
+        self.fdes.iter().find(|f| f.offset == offset)
+    }
+
+    // functions of every FDE by address, those of stripped files included
+    pub fn functions(&self) -> Vec<FunctionRange> {
+        let mut functions: Vec<FunctionRange> = self.fdes.iter()
+            .filter(|f| f.pc_range != 0)
+            .map(|f| FunctionRange { start: f.pc_begin, size: f.pc_range })
+            .collect();
+        functions.sort_by_key(|f| (f.start, f.size));
+        functions.dedup();
+        functions
+    }
+
+    // rows of the FDE, CIE initial instructions first
+    pub fn unwind_table(&self, fde: &Fde) -> Result<Vec<UnwindRow>> {
+        let cie = &self.cies[fde.cie];
+        let mut rows: Vec<UnwindRow> = Vec::new();
+        let mut row = UnwindRow {
+            address: fde.pc_begin,
+            cfa: CfaRule::RegisterOffset { register: 0, offset: 0 },
+            registers: Vec::new(),
+        };
+        self.execute(cie, cie.instructions.clone(), &mut row, None, &mut rows)?;
+        let initial = row.clone();
+        self.execute(cie, fde.instructions.clone(), &mut row, Some(&initial), &mut rows)?;
+        rows.push(row);
+        Ok(rows)
+    }
+
+    // row covering address, None if no FDE does
+    pub fn unwind_row(&self, address: u64) -> Result<Option<UnwindRow>> {
+        let fde = match self.find_fde(address) {
+            Some(fde) => fde,
+            None => return Ok(None),
+        };
+        let rows = self.unwind_table(fde)?;
+        Ok(rows.into_iter().take_while(|row| row.address <= address).last())
+    }
+
+    fn execute(&self, cie: &Cie, instructions: Range<usize>, row: &mut UnwindRow,
+        initial: Option<&UnwindRow>, rows: &mut Vec<UnwindRow>) -> Result<()>
+    {
+        let data = self.data.get(..instructions.end).unwrap_or(&[]);
+        let mut reader = Reader::new(data, self.endian, "call frame instructions");
+        reader.take(instructions.start as u64)?;
+        let base = PointerBase { section: self.vaddr, data: None, address_size: self.address_size };
+        let code_factor = cie.code_alignment_factor;
+        let data_factor = cie.data_alignment_factor;
+        let factored = |n: i64| n.wrapping_mul(data_factor);
+        let mut stack: Vec<UnwindRow> = Vec::new();
+        while !reader.is_empty() {
+            let position = reader.offset();
+            let opcode = reader.u8()?;
+            let operand = (opcode & 0x3f) as u64;
+            match opcode & 0xc0 {
+                value::DW_CFA_ADVANCE_LOC => {
+                    advance(rows, row, row.address.wrapping_add(operand.wrapping_mul(code_factor)));
+                    continue;
+                },
+                value::DW_CFA_OFFSET => {
+                    let offset = factored(reader.uleb128()? as i64);
+                    set_rule(row, operand, RegisterRule::Offset(offset))?;
+                    continue;
+                },
+                value::DW_CFA_RESTORE => {
+                    restore(row, initial, operand)?;
+                    continue;
+                },
+                _ => (),
+            }
+            match opcode {
+                value::DW_CFA_NOP => (),
+                value::DW_CFA_SET_LOC => {
+                    let address = read_pointer(&mut reader, cie.fde_encoding, base)?;
+                    advance(rows, row, address);
+                },
+                value::DW_CFA_ADVANCE_LOC1 | value::DW_CFA_ADVANCE_LOC2 | value::DW_CFA_ADVANCE_LOC4 => {
+                    let delta = reader.uint(1 << (opcode - value::DW_CFA_ADVANCE_LOC1))?;
+                    advance(rows, row, row.address.wrapping_add(delta.wrapping_mul(code_factor)));
+                },
+                value::DW_CFA_OFFSET_EXTENDED => {
+                    let register = reader.uleb128()?;
+                    let offset = factored(reader.uleb128()? as i64);
+                    set_rule(row, register, RegisterRule::Offset(offset))?;
+                },
+                value::DW_CFA_OFFSET_EXTENDED_SF => {
+                    let register = reader.uleb128()?;
+                    let offset = factored(reader.sleb128()?);
+                    set_rule(row, register, RegisterRule::Offset(offset))?;
+                },
+                value::DW_CFA_GNU_NEGATIVE_OFFSET_EXTENDED => {
+                    let register = reader.uleb128()?;
+                    let offset = factored((reader.uleb128()? as i64).wrapping_neg());
+                    set_rule(row, register, RegisterRule::Offset(offset))?;
+                },
+                value::DW_CFA_VAL_OFFSET => {
+                    let register = reader.uleb128()?;
+                    let offset = factored(reader.uleb128()? as i64);
+                    set_rule(row, register, RegisterRule::ValOffset(offset))?;
+                },
+                value::DW_CFA_VAL_OFFSET_SF => {
+                    let register = reader.uleb128()?;
+                    let offset = factored(reader.sleb128()?);
+                    set_rule(row, register, RegisterRule::ValOffset(offset))?;
+                },
+                value::DW_CFA_RESTORE_EXTENDED => {
+                    let register = reader.uleb128()?;
+                    restore(row, initial, register)?;
+                },
+                value::DW_CFA_UNDEFINED => {
+                    let register = reader.uleb128()?;
+                    set_rule(row, register, RegisterRule::Undefined)?;
+                },
+                value::DW_CFA_SAME_VALUE => {
+                    let register = reader.uleb128()?;
+                    set_rule(row, register, RegisterRule::SameValue)?;
+                },
+                value::DW_CFA_REGISTER => {
+                    let register = reader.uleb128()?;
+                    let other = reader.uleb128()?;
+                    set_rule(row, register, RegisterRule::Register(other))?;
+                },
+                value::DW_CFA_EXPRESSION | value::DW_CFA_VAL_EXPRESSION => {
+                    let register = reader.uleb128()?;
+                    let size = reader.uleb128()?;
+                    let expression = reader.take(size)?.to_vec();
+                    let rule = match opcode {
+                        value::DW_CFA_EXPRESSION => RegisterRule::Expression(expression),
+                        _ => RegisterRule::ValExpression(expression),
+                    };
+                    set_rule(row, register, rule)?;
+                },
+                // the address of the row is not part of the state
+                value::DW_CFA_REMEMBER_STATE => stack.push(row.clone()),
+                value::DW_CFA_RESTORE_STATE => {
+                    let state = stack.pop().ok_or_else(|| Error::invalid("DW_CFA_restore_state",
+                        format!("no remembered state at offset {:#x}", position)))?;
+                    row.cfa = state.cfa;
+                    row.registers = state.registers;
+                },
+                value::DW_CFA_DEF_CFA => {
+                    let register = reader.uleb128()?;
+                    let offset = reader.uleb128()? as i64;
+                    row.cfa = CfaRule::RegisterOffset { register, offset };
+                },
+                value::DW_CFA_DEF_CFA_SF => {
+                    let register = reader.uleb128()?;
+                    let offset = factored(reader.sleb128()?);
+                    row.cfa = CfaRule::RegisterOffset { register, offset };
+                },
+                value::DW_CFA_DEF_CFA_REGISTER => {
+                    let register = reader.uleb128()?;
+                    let offset = match row.cfa {
+                        CfaRule::RegisterOffset { offset, .. } => offset,
+                        CfaRule::Expression(_) => 0,
+                    };
+                    row.cfa = CfaRule::RegisterOffset { register, offset };
+                },
+                value::DW_CFA_DEF_CFA_OFFSET | value::DW_CFA_DEF_CFA_OFFSET_SF => {
+                    let offset = match opcode {
+                        value::DW_CFA_DEF_CFA_OFFSET => reader.uleb128()? as i64,
+                        _ => factored(reader.sleb128()?),
+                    };
+                    if let CfaRule::RegisterOffset { register, .. } = row.cfa {
+                        row.cfa = CfaRule::RegisterOffset { register, offset };
+                    }
+                },
+                value::DW_CFA_DEF_CFA_EXPRESSION => {
+                    let size = reader.uleb128()?;
+                    row.cfa = CfaRule::Expression(reader.take(size)?.to_vec());
+                },
+                value::DW_CFA_GNU_ARGS_SIZE => {
+                    reader.uleb128()?;
+                },
+                // register windows of SPARC, return address signing of AArch64
+                value::DW_CFA_GNU_WINDOW_SAVE => (),
+                n => return Err(Error::invalid("call frame instruction", format!("{:#x} at offset {:#x}", n, position))),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl EhFrameHdr {
+    pub fn parse(data: &[u8], vaddr: u64, class: u8, endian: Endian) -> Result<EhFrameHdr> {
+        let address_size = if class == value::ELFCLASS32 { 4 } else { 8 };
+        let base = PointerBase { section: vaddr, data: Some(vaddr), address_size };
+        let mut reader = Reader::new(data, endian, ".eh_frame_hdr");
+        let version = reader.u8()?;
+        if version != 1 {
+            return Err(Error::invalid(".eh_frame_hdr version", version.to_string()));
+        }
+        let eh_frame_ptr_encoding = reader.u8()?;
+        let fde_count_encoding = reader.u8()?;
+        let table_encoding = reader.u8()?;
+        let eh_frame_ptr = read_pointer(&mut reader, eh_frame_ptr_encoding, base)?;
+        let mut table: Vec<(u64, u64)> = Vec::new();
+        // the search table is optional
+        if fde_count_encoding != value::DW_EH_PE_OMIT && table_encoding != value::DW_EH_PE_OMIT {
+            let count = read_pointer(&mut reader, fde_count_encoding, base)?;
+            for _ in 0..count {
+                let location = read_pointer(&mut reader, table_encoding, base)?;
+                let fde = read_pointer(&mut reader, table_encoding, base)?;
+                table.push((location, fde));
+            }
+        }
+        Ok(EhFrameHdr { vaddr, version, eh_frame_ptr, table })
+    }
+
+    // address of the FDE which may cover address, by binary search
+    pub fn lookup(&self, address: u64) -> Option<u64> {
+        let index = self.table.partition_point(|(location, _)| *location <= address);
+        self.table.get(index.checked_sub(1)?).map(|(_, fde)| *fde)
+    }
+}
+
+fn parse_cie(entry: &mut Reader, offset: u64, base: PointerBase) -> Result<Cie> {
+    let mut cie = Cie {
+        offset,
+        version: entry.u8()?,
+        fde_encoding: value::DW_EH_PE_ABSPTR,
+        lsda_encoding: value::DW_EH_PE_OMIT,
+        ..Default::default()
+    };
+    if !matches!(cie.version, 1 | 3 | 4) {
+        return Err(Error::invalid("CIE version", format!("{} at {:#x}", cie.version, offset)));
+    }
+    cie.augmentation = String::from_utf8_lossy(entry.cstr()?).into_owned();
+    // pointer to exception data of old GCC
+    if cie.augmentation.starts_with("eh") {
+        entry.take(base.address_size as u64)?;
+    }
+    if cie.version == 4 {
+        let _address_size = entry.u8()?;
+        let _segment_size = entry.u8()?;
+    }
+    cie.code_alignment_factor = entry.uleb128()?;
+    cie.data_alignment_factor = entry.sleb128()?;
+    cie.return_address_register = match cie.version {
+        1 => entry.u8()? as u64,
+        _ => entry.uleb128()?,
+    };
+    if cie.augmentation.starts_with('z') {
+        let size = entry.uleb128()?;
+        let mut data = entry.split(size)?;
+        for c in cie.augmentation.chars().skip(1) {
+            match c {
+                'L' => cie.lsda_encoding = data.u8()?,
+                'P' => {
+                    let encoding = data.u8()?;
+                    cie.personality = Some(read_pointer(&mut data, encoding, base)?);
+                },
+                'R' => cie.fde_encoding = data.u8()?,
+                'S' => cie.signal_frame = true,
+                // AArch64 B key and MTE tagged frames
+                'B' | 'G' => (),
+                // the rest is described by the size read above
+                _ => break,
+            }
+        }
+    } else if !cie.augmentation.is_empty() && cie.augmentation != "eh" {
+        return Err(Error::invalid("CIE augmentation", format!("{:?} at {:#x}", cie.augmentation, offset)));
+    }
+    cie.instructions = entry.offset()..entry.offset() + entry.len();
+    Ok(cie)
+}
+
+fn parse_fde(entry: &mut Reader, offset: u64, index: usize, cie: &Cie, base: PointerBase) -> Result<Fde> {
+    let mut fde = Fde { offset, cie: index, ..Default::default() };
+    fde.pc_begin = read_pointer(entry, cie.fde_encoding, base)?;
+    // a size, only the format of the encoding applies
+    fde.pc_range = read_pointer(entry, cie.fde_encoding & 0x0f, base)?;
+    if cie.augmentation.starts_with('z') {
+        let size = entry.uleb128()?;
+        let mut data = entry.split(size)?;
+        if cie.lsda_encoding != value::DW_EH_PE_OMIT && cie.augmentation.contains('L') {
+            fde.lsda = Some(read_pointer(&mut data, cie.lsda_encoding, base)?);
+        }
+    }
+    fde.instructions = entry.offset()..entry.offset() + entry.len();
+    Ok(fde)
+}
+
+// Encoded pointer at reader. DW_EH_PE_indirect pointers are left as the
+// address holding the value, which is only known once relocated.
+fn read_pointer(reader: &mut Reader, encoding: u8, base: PointerBase) -> Result<u64> {
+    let position = base.section.wrapping_add(reader.offset() as u64);
+    let application = encoding & 0x70;
+    if application == value::DW_EH_PE_ALIGNED {
+        let size = base.address_size as usize;
+        reader.take(((size - reader.offset() % size) % size) as u64)?;
+    }
+    let value = match encoding & 0x0f {
+        value::DW_EH_PE_ABSPTR => reader.uint(base.address_size as u64)?,
+        value::DW_EH_PE_ULEB128 => reader.uleb128()?,
+        value::DW_EH_PE_UDATA2 => reader.uint(2)?,
+        value::DW_EH_PE_UDATA4 => reader.uint(4)?,
+        value::DW_EH_PE_UDATA8 => reader.uint(8)?,
+        value::DW_EH_PE_SLEB128 => reader.sleb128()? as u64,
+        value::DW_EH_PE_SDATA2 => reader.u16()? as i16 as u64,
+        value::DW_EH_PE_SDATA4 => reader.u32()? as i32 as u64,
+        value::DW_EH_PE_SDATA8 => reader.u64()?,
+        _ => return Err(Error::invalid("pointer encoding", format!("{:#x}", encoding))),
+    };
+    let relative_to = match application {
+        value::DW_EH_PE_ABSPTR | value::DW_EH_PE_ALIGNED => 0,
+        value::DW_EH_PE_PCREL => position,
+        value::DW_EH_PE_DATAREL => base.data
+            .ok_or_else(|| Error::invalid("pointer encoding", format!("{:#x} outside .eh_frame_hdr", encoding)))?,
+        // DW_EH_PE_textrel and DW_EH_PE_funcrel have no base in ELF
+        _ => return Err(Error::invalid("pointer encoding", format!("{:#x}", encoding))),
+    };
+    let pointer = relative_to.wrapping_add(value);
+    match base.address_size {
+        4 => Ok(pointer & 0xffff_ffff),
+        _ => Ok(pointer),
+    }
+}
+
+// close the current row when the address moves on
+fn advance(rows: &mut Vec<UnwindRow>, row: &mut UnwindRow, address: u64) {
+    if address != row.address {
+        rows.push(row.clone());
+        row.address = address;
+    }
+}
+
+fn set_rule(row: &mut UnwindRow, register: u64, rule: RegisterRule) -> Result<()> {
+    match row.registers.binary_search_by_key(&register, |(r, _)| *r) {
+        Ok(index) => row.registers[index].1 = rule,
+        Err(_) if row.registers.len() >= MAX_RULES =>
+            return Err(Error::invalid("call frame instructions", format!("more than {} registers", MAX_RULES))),
+        Err(index) => row.registers.insert(index, (register, rule)),
+    }
+    Ok(())
+}
+
+// rule of the CIE initial instructions, none when run by the CIE itself
+fn restore(row: &mut UnwindRow, initial: Option<&UnwindRow>, register: u64) -> Result<()> {
+    match initial.and_then(|r| r.register(register)) {
+        Some(rule) => set_rule(row, register, rule.clone())?,
+        None => row.registers.retain(|(r, _)| *r != register),
+    }
+    Ok(())
+}
+
+impl UnwindRow {
+    pub fn register(&self, register: u64) -> Option<&RegisterRule> {
+        self.registers.binary_search_by_key(&register, |(r, _)| *r).ok().map(|i| &self.registers[i].1)
+    }
+}
+
+// as readelf --debug-dump=frames-interp, with DWARF register numbers
+impl fmt::Display for CfaRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CfaRule::RegisterOffset { register, offset } => write!(f, "r{}{:+}", register, offset),
+            CfaRule::Expression(_) => write!(f, "exp"),
+        }
+    }
+}
+
+impl fmt::Display for RegisterRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RegisterRule::Undefined => write!(f, "u"),
+            RegisterRule::SameValue => write!(f, "s"),
+            RegisterRule::Offset(offset) => write!(f, "c{:+}", offset),
+            RegisterRule::ValOffset(offset) => write!(f, "v{:+}", offset),
+            RegisterRule::Register(register) => write!(f, "r{}", register),
+            RegisterRule::Expression(_) => write!(f, "exp"),
+            RegisterRule::ValExpression(_) => write!(f, "vexp"),
+        }
+    }
+}
@@ -1,12 +1,12 @@
 use bytes::Buf;
-use std::io::{
-    prelude::*,
-    SeekFrom,
+use std::io::prelude::*;
+use crate::{
+    elf::{elf64, endian::Endian, section::{SectionFlags, SectionType}, segment::{SegmentFlags, SegmentType}, symbol::{SymbolBind, SymbolType, SymbolVisibility}, value},
+    error::{read_at, read_vec_at, Error, Result},
 };
-use crate::elf::value;
 
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct ELFHeader {
     pub e_ident: [u8; 16],
     pub e_type: u16,
@@ -24,38 +24,71 @@ pub struct ELFHeader {
     pub e_shstrndx: u16,
 }
 
+// headers of ELF32 files are widened from u32 by elf64, narrowing back is lossless
+impl From<&elf64::ELFHeader> for ELFHeader {
+    fn from(ehdr: &elf64::ELFHeader) -> ELFHeader {
+        ELFHeader {
+            e_ident: ehdr.e_ident,
+            e_type: ehdr.e_type,
+            e_machine: ehdr.e_machine,
+            e_version: ehdr.e_version,
+            e_entry: ehdr.e_entry as u32,
+            e_phoff: ehdr.e_phoff as u32,
+            e_shoff: ehdr.e_shoff as u32,
+            e_flags: ehdr.e_flags,
+            e_ehsize: ehdr.e_ehsize,
+            e_phentsize: ehdr.e_phentsize,
+            e_phnum: ehdr.e_phnum,
+            e_shentsize: ehdr.e_shentsize,
+            e_shnum: ehdr.e_shnum,
+            e_shstrndx: ehdr.e_shstrndx,
+        }
+    }
+}
+
 impl ELFHeader {
     pub fn new() -> ELFHeader {
         Self::default()
     }
 
-    pub fn load<T>(elf: &mut T) -> Result<ELFHeader, String>
+    pub fn load<T>(elf: &mut T) -> Result<ELFHeader>
         where T: Read
     {
         // sizeof(ELFHeader) == 0x34
         let mut data: [u8; 0x34] = [0; 0x34];
-        match elf.read(&mut data) {
-            Ok(n) => if n != 0x34 { return Err(String::from("invalid length")); },
-            Err(e) => return Err(e.to_string()),
-        };
+        if let Err(e) = elf.read_exact(&mut data) {
+            return Err(match e.kind() {
+                std::io::ErrorKind::UnexpectedEof => Error::truncated("ELF header", 0, 0x34),
+                _ => Error::from(e),
+            });
+        }
+        ELFHeader::parse(&data)
+    }
 
-        let mut cursor = &data[..];
+    // data holds at least 0x34 bytes of header
+    pub fn parse(data: &[u8]) -> Result<ELFHeader> {
+        if data.len() < 0x34 {
+            return Err(Error::truncated("ELF header", 0, 0x34));
+        }
+        let mut cursor = data;
         let mut e_ident: [u8; 16] = [0; 16];
         cursor.copy_to_slice(&mut e_ident);
-        let e_type: u16 = cursor.get_u16_le();
-        let e_machine: u16 = cursor.get_u16_le();
-        let e_version: u32 = cursor.get_u32_le();
-        let e_entry: u32 = cursor.get_u32_le();
-        let e_phoff: u32 = cursor.get_u32_le();
-        let e_shoff: u32 = cursor.get_u32_le();
-        let e_flags: u32 = cursor.get_u32_le();
-        let e_ehsize: u16 = cursor.get_u16_le();
-        let e_phentsize: u16 = cursor.get_u16_le();
-        let e_phnum: u16 = cursor.get_u16_le();
-        let e_shentsize: u16 = cursor.get_u16_le();
-        let e_shnum: u16 = cursor.get_u16_le();
-        let e_shstrndx: u16 = cursor.get_u16_le();
-        
+        // the rest of header follows EI_DATA
+        let endian = Endian::from_ident(&e_ident)?;
+        let e_type: u16 = endian.read_u16(&mut cursor);
+        let e_machine: u16 = endian.read_u16(&mut cursor);
+        let e_version: u32 = endian.read_u32(&mut cursor);
+        let e_entry: u32 = endian.read_u32(&mut cursor);
+        let e_phoff: u32 = endian.read_u32(&mut cursor);
+        let e_shoff: u32 = endian.read_u32(&mut cursor);
+        let e_flags: u32 = endian.read_u32(&mut cursor);
+        let e_ehsize: u16 = endian.read_u16(&mut cursor);
+        let e_phentsize: u16 = endian.read_u16(&mut cursor);
+        let e_phnum: u16 = endian.read_u16(&mut cursor);
+        let e_shentsize: u16 = endian.read_u16(&mut cursor);
+        let e_shnum: u16 = endian.read_u16(&mut cursor);
+        let e_shstrndx: u16 = endian.read_u16(&mut cursor);
+
         Ok(ELFHeader {
             e_ident, e_type, e_machine, e_version,
             e_entry, e_phoff, e_shoff, e_flags,
@@ -63,6 +96,31 @@ impl ELFHeader {
             e_shentsize, e_shnum, e_shstrndx,
         })
     }
+
+    pub fn endian(&self) -> Endian {
+        match self.e_ident[value::EI_DATA] {
+            value::ELFDATA2MSB => Endian::Big,
+            _ => Endian::Little,
+        }
+    }
+
+    pub fn write_to(&self, buf: &mut Vec<u8>) {
+        let endian = self.endian();
+        buf.extend_from_slice(&self.e_ident);
+        endian.write_u16(buf, self.e_type);
+        endian.write_u16(buf, self.e_machine);
+        endian.write_u32(buf, self.e_version);
+        endian.write_u32(buf, self.e_entry);
+        endian.write_u32(buf, self.e_phoff);
+        endian.write_u32(buf, self.e_shoff);
+        endian.write_u32(buf, self.e_flags);
+        endian.write_u16(buf, self.e_ehsize);
+        endian.write_u16(buf, self.e_phentsize);
+        endian.write_u16(buf, self.e_phnum);
+        endian.write_u16(buf, self.e_shentsize);
+        endian.write_u16(buf, self.e_shnum);
+        endian.write_u16(buf, self.e_shstrndx);
+    }
 }
 
 #[derive(Debug, Default, Clone)]
@@ -79,16 +137,112 @@ pub struct SectionHeader {
     pub sh_entsize: u32,
 }
 
+impl From<&elf64::SectionHeader> for SectionHeader {
+    fn from(shdr: &elf64::SectionHeader) -> SectionHeader {
+        SectionHeader {
+            sh_name: shdr.sh_name,
+            sh_type: shdr.sh_type,
+            sh_flags: shdr.sh_flags as u32,
+            sh_addr: shdr.sh_addr as u32,
+            sh_offset: shdr.sh_offset as u32,
+            sh_size: shdr.sh_size as u32,
+            sh_link: shdr.sh_link,
+            sh_info: shdr.sh_info,
+            sh_addralign: shdr.sh_addralign as u32,
+            sh_entsize: shdr.sh_entsize as u32,
+        }
+    }
+}
+
+impl SectionHeader {
+    // data holds at least one entry
+    pub fn parse(data: &[u8], endian: Endian) -> SectionHeader {
+        let mut cursor = data;
+        let sh_name: u32 = endian.read_u32(&mut cursor);
+        let sh_type: u32 = endian.read_u32(&mut cursor);
+        let sh_flags: u32 = endian.read_u32(&mut cursor);
+        let sh_addr: u32 = endian.read_u32(&mut cursor);
+        let sh_offset: u32 = endian.read_u32(&mut cursor);
+        let sh_size: u32 = endian.read_u32(&mut cursor);
+        let sh_link: u32 = endian.read_u32(&mut cursor);
+        let sh_info: u32 = endian.read_u32(&mut cursor);
+        let sh_addralign: u32 = endian.read_u32(&mut cursor);
+        let sh_entsize: u32 = endian.read_u32(&mut cursor);
+        SectionHeader {
+            sh_name, sh_type, sh_flags, sh_addr,
+            sh_offset, sh_size, sh_link, sh_info,
+            sh_addralign, sh_entsize,
+        }
+    }
+
+    pub fn write_to(&self, endian: Endian, buf: &mut Vec<u8>) {
+        endian.write_u32(buf, self.sh_name);
+        endian.write_u32(buf, self.sh_type);
+        endian.write_u32(buf, self.sh_flags);
+        endian.write_u32(buf, self.sh_addr);
+        endian.write_u32(buf, self.sh_offset);
+        endian.write_u32(buf, self.sh_size);
+        endian.write_u32(buf, self.sh_link);
+        endian.write_u32(buf, self.sh_info);
+        endian.write_u32(buf, self.sh_addralign);
+        endian.write_u32(buf, self.sh_entsize);
+    }
+
+    // processor-specific types and flags are told apart by e_machine
+    pub fn section_type(&self, machine: u16) -> SectionType {
+        SectionType::new(self.sh_type, machine)
+    }
+
+    pub fn flags(&self, machine: u16) -> SectionFlags {
+        SectionFlags::new(self.sh_flags as u64, machine)
+    }
+}
+
+// header at the start of SHF_COMPRESSED section data, without ch_reserved of elf64
+#[derive(Debug, Default, Clone)]
+pub struct CompressionHeader {
+    pub ch_type: u32,
+    pub ch_size: u32,
+    pub ch_addralign: u32,
+}
+
+impl From<&elf64::CompressionHeader> for CompressionHeader {
+    fn from(chdr: &elf64::CompressionHeader) -> CompressionHeader {
+        CompressionHeader {
+            ch_type: chdr.ch_type,
+            ch_size: chdr.ch_size as u32,
+            ch_addralign: chdr.ch_addralign as u32,
+        }
+    }
+}
+
+impl CompressionHeader {
+    // data holds at least one header
+    pub fn parse(data: &[u8], endian: Endian) -> CompressionHeader {
+        let mut cursor = data;
+        let ch_type = endian.read_u32(&mut cursor);
+        let ch_size = endian.read_u32(&mut cursor);
+        let ch_addralign = endian.read_u32(&mut cursor);
+        CompressionHeader { ch_type, ch_size, ch_addralign }
+    }
+
+    pub fn write_to(&self, endian: Endian, buf: &mut Vec<u8>) {
+        endian.write_u32(buf, self.ch_type);
+        endian.write_u32(buf, self.ch_size);
+        endian.write_u32(buf, self.ch_addralign);
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct SectionTable {
     // ELFHeader.e_shoff
     pub offset: u32,
     // ELFHeader.e_shentsize
     pub size: u16,
-    // ELFHeader.e_shnum
-    pub num: u16,
-    // ELFHeader.e_shstrndx
-    pub name: u16,
+    // ELFHeader.e_shnum, sh_size of section 0 with extended numbering
+    pub num: u32,
+    // ELFHeader.e_shstrndx, sh_link of section 0 with extended numbering
+    pub name: u32,
     pub data: Vec<SectionHeader>,
 }
 
@@ -97,59 +251,68 @@ impl SectionTable {
         Self::default()
     }
 
-    pub fn load<T>(elf: &mut T, ehdr: &ELFHeader) -> Result<SectionTable, String>
+    pub fn load<T>(elf: &mut T, ehdr: &ELFHeader) -> Result<SectionTable>
         where T: Read + Seek
     {
-        // check e_shentsize, min size is 0x28
-        if ehdr.e_shentsize < 0x28 {
-            return Err(String::from("invalid e_shentsize"));
-        }
-        let num = ehdr.e_shnum as u64;
+        let endian = ehdr.endian();
         let size = ehdr.e_shentsize as u64;
         let offset = ehdr.e_shoff as u64;
+        // no section header table
+        if ehdr.e_shoff == 0 {
+            return Ok(SectionTable { size: ehdr.e_shentsize, ..SectionTable::new() });
+        }
+
+        // section 0 holds what does not fit the 16-bit fields, extended numbering
+        let first = match ehdr.e_shnum == 0 || ehdr.e_shstrndx as usize == value::SHN_XINDEX {
+            true => Some(first_section(elf, ehdr)?),
+            false => None,
+        };
+        let num = match (ehdr.e_shnum, &first) {
+            (0, Some(first)) => first.sh_size,
+            (n, _) => n as u32,
+        };
+        let name = match (ehdr.e_shstrndx as usize, &first) {
+            (value::SHN_XINDEX, Some(first)) => first.sh_link,
+            (n, _) => n as u32,
+        };
+        // check e_shentsize, min size is 0x28
+        if num != 0 && ehdr.e_shentsize < 0x28 {
+            return Err(Error::invalid("e_shentsize", format!("{:#x}", ehdr.e_shentsize)));
+        }
 
         let mut data: [u8; 0x28] = [0; 0x28];
-        let mut sections: Vec<SectionHeader> = Vec::with_capacity(num as usize);
+        // a count taken from section 0 is only trusted as far as reading goes
+        let mut sections: Vec<SectionHeader> = Vec::with_capacity(num.min(value::SHN_LORESERVE as u32) as usize);
 
-        for i in 0..num {
+        for i in 0..num as u64 {
             // read data
             let pos: u64 = offset + i * size;
-            if let Err(e) = elf.seek(std::io::SeekFrom::Start(pos)) {
-                return Err(e.to_string());
-            }
-            match elf.read(&mut data) {
-                Ok(n) => if n != 0x28 { return Err(String::from("invalid length")) },
-                Err(e) => return Err(e.to_string()),
-            };
-            // parse section header
-            let mut cursor = &data[..];
-            let sh_name: u32 = cursor.get_u32_le();
-            let sh_type: u32 = cursor.get_u32_le();
-            let sh_flags: u32 = cursor.get_u32_le();
-            let sh_addr: u32 = cursor.get_u32_le();
-            let sh_offset: u32 = cursor.get_u32_le();
-            let sh_size: u32 = cursor.get_u32_le();
-            let sh_link: u32 = cursor.get_u32_le();
-            let sh_info: u32 = cursor.get_u32_le();
-            let sh_addralign: u32 = cursor.get_u32_le();
-            let sh_entsize: u32 = cursor.get_u32_le();
-            sections.push(SectionHeader {
-                sh_name, sh_type, sh_flags, sh_addr,
-                sh_offset, sh_size, sh_link, sh_info,
-                sh_addralign, sh_entsize,
-            });
+            read_at(elf, pos, &mut data, || format!("section header {}", i))?;
+            sections.push(SectionHeader::parse(&data, endian));
         }
 
         Ok(SectionTable {
             offset: ehdr.e_shoff,
             size: ehdr.e_shentsize,
-            num: ehdr.e_shnum,
-            name: ehdr.e_shstrndx,
+            num, name,
             data: sections,
         })
     }
 }
 
+// section 0 at e_shoff, which holds the counts of extended numbering
+fn first_section<T>(elf: &mut T, ehdr: &ELFHeader) -> Result<SectionHeader>
+    where T: Read + Seek
+{
+    // check e_shentsize, min size is 0x28
+    if ehdr.e_shentsize < 0x28 {
+        return Err(Error::invalid("e_shentsize", format!("{:#x}", ehdr.e_shentsize)));
+    }
+    let mut data: [u8; 0x28] = [0; 0x28];
+    read_at(elf, ehdr.e_shoff as u64, &mut data, || String::from("section header 0"))?;
+    Ok(SectionHeader::parse(&data, ehdr.endian()))
+}
+
 #[derive(Debug, Default)]
 pub struct StringTable {
     pub section: SectionHeader,
@@ -161,25 +324,14 @@ impl StringTable {
         Self::default()
     }
 
-    pub fn load<T>(elf: &mut T, section: &SectionHeader) -> Result<StringTable, String>
+    pub fn load<T>(elf: &mut T, section: &SectionHeader) -> Result<StringTable>
         where T: Read + Seek
     {
         // check section type
         if section.sh_type != value::SHT_STRTAB {
-            return Err(String::from("section type is not SHT_STRTAB"));
-        }
-        // read data
-        let offset = section.sh_offset;
-        if let Err(e) = elf.seek(SeekFrom::Start(offset as u64)) {
-            return Err(e.to_string());
-        }
-        let size = section.sh_size;
-        let mut data: Vec<u8> = Vec::new();
-        data.resize(size as usize, 0);
-        match elf.read(&mut data) {
-            Ok(n) => if n != size as usize { return Err(String::from("invalid length")); },
-            Err(e) => return Err(e.to_string()),
-        };
+            return Err(Error::invalid("sh_type", format!("{:#x} is not SHT_STRTAB", section.sh_type)));
+        }
+        let data = read_vec_at(elf, section.sh_offset as u64, section.sh_size as u64, || String::from("string table"))?;
 
         Ok(StringTable {
             section: section.clone(),
@@ -187,23 +339,14 @@ impl StringTable {
         })
     }
 
+    // string at pos, empty if pos is out of range and lossy if not UTF-8
     pub fn get(&self, pos: usize) -> String {
-        if pos >= self.data.len() {
-            panic!("index of StringTable out of range");
-        }
-        let mut s: Vec<u8> = Vec::new();
-        for n in self.data.iter().skip(pos) {
-            if *n != b'\0' {
-                s.push(*n);
-            }
-            else {
-                break;
-            }
-        }
-        match String::from_utf8(s) {
-            Ok(s) => return s,
-            Err(_) => panic!("invalid StringTable"),
+        let rest = match self.data.get(pos..) {
+            Some(rest) => rest,
+            None => return String::new(),
         };
+        let end = rest.iter().position(|c| *c == b'\0').unwrap_or(rest.len());
+        String::from_utf8_lossy(&rest[..end]).into_owned()
     }
 }
 
@@ -217,9 +360,62 @@ pub struct SymbolEntry {
     pub st_shndx: u16,
 }
 
+impl From<&elf64::SymbolEntry> for SymbolEntry {
+    fn from(sym: &elf64::SymbolEntry) -> SymbolEntry {
+        SymbolEntry {
+            st_name: sym.st_name,
+            st_value: sym.st_value as u32,
+            st_size: sym.st_size as u32,
+            st_info: sym.st_info,
+            st_other: sym.st_other,
+            st_shndx: sym.st_shndx,
+        }
+    }
+}
+
+impl SymbolEntry {
+    // data holds at least one entry
+    pub fn parse(data: &[u8], endian: Endian) -> SymbolEntry {
+        let mut cursor = data;
+        let st_name = endian.read_u32(&mut cursor);
+        let st_value = endian.read_u32(&mut cursor);
+        let st_size = endian.read_u32(&mut cursor);
+        let st_info = cursor.get_u8();
+        let st_other = cursor.get_u8();
+        let st_shndx = endian.read_u16(&mut cursor);
+        SymbolEntry {
+            st_name, st_value, st_size,
+            st_info, st_other, st_shndx,
+        }
+    }
+
+    pub fn write_to(&self, endian: Endian, buf: &mut Vec<u8>) {
+        endian.write_u32(buf, self.st_name);
+        endian.write_u32(buf, self.st_value);
+        endian.write_u32(buf, self.st_size);
+        buf.push(self.st_info);
+        buf.push(self.st_other);
+        endian.write_u16(buf, self.st_shndx);
+    }
+
+    pub fn bind(&self) -> SymbolBind {
+        SymbolBind::from(value::symbol_bind(self.st_info))
+    }
+
+    pub fn symbol_type(&self) -> SymbolType {
+        SymbolType::from(value::symbol_type(self.st_info))
+    }
+
+    pub fn visibility(&self) -> SymbolVisibility {
+        SymbolVisibility::from(self.st_other)
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct SymbolTable {
     pub section: SectionHeader,
+    // string table of section.sh_link, empty unless loaded by load_linked
+    pub strtab: StringTable,
     pub data: Vec<SymbolEntry>,
 }
 
@@ -228,55 +424,64 @@ impl SymbolTable {
         Self::default()
     }
 
-    pub fn load<T>(elf: &mut T, section: &SectionHeader) -> Result<SymbolTable, String>
+    pub fn load<T>(elf: &mut T, ehdr: &ELFHeader, section: &SectionHeader) -> Result<SymbolTable>
         where T: Read + Seek
     {
-        // check section type
-        if section.sh_type != value::SHT_SYMTAB {
-            return Err(String::from("invalid section type"));
+        // check section type, .symtab or .dynsym
+        if section.sh_type != value::SHT_SYMTAB && section.sh_type != value::SHT_DYNSYM {
+            return Err(Error::invalid("sh_type", format!("{:#x} is not SHT_SYMTAB or SHT_DYNSYM", section.sh_type)));
         }
         // sizeof(SymbolEntry) = 0x10
         if section.sh_entsize < 0x10 {
-            return Err(String::from("invalid entry size"));
+            return Err(Error::invalid("sh_entsize", format!("{:#x}", section.sh_entsize)));
         }
         if section.sh_size % section.sh_entsize != 0 {
-            return Err(String::from("invalid section size"));
+            return Err(Error::invalid("sh_size", format!("{:#x} is not a multiple of sh_entsize", section.sh_size)));
         }
 
+        let endian = ehdr.endian();
         let offset = section.sh_offset as u64;
         let entry_size = section.sh_entsize as u64;
         let num = (section.sh_size as u64) / entry_size;
         let mut data: [u8; 0x10] = [0; 0x10];
-        let mut symbols: Vec<SymbolEntry> = Vec::with_capacity(num as usize);
+        // entries are kept as they are read, sh_size is not trusted for allocation
+        let mut symbols: Vec<SymbolEntry> = Vec::new();
 
         for n in 0..num {
             let pos = offset + n * entry_size;
-            if let Err(e) = elf.seek(SeekFrom::Start(pos)) {
-                return Err(e.to_string());
-            }
-            match elf.read(&mut data) {
-                Ok(n) => if n != 0x10 { return Err(String::from("invalid length")); },
-                Err(e) => return Err(e.to_string()),
-            };
-            // parse data
-            let mut cursor = &data[..];
-            let st_name = cursor.get_u32_le();
-            let st_value = cursor.get_u32_le();
-            let st_size = cursor.get_u32_le();
-            let st_info = cursor.get_u8();
-            let st_other = cursor.get_u8();
-            let st_shndx = cursor.get_u16_le();
-            symbols.push(SymbolEntry {
-                st_name, st_value, st_size,
-                st_info, st_other, st_shndx,
-            });
+            read_at(elf, pos, &mut data, || format!("symbol {}", n))?;
+            symbols.push(SymbolEntry::parse(&data, endian));
         }
 
         Ok(SymbolTable {
             section: section.clone(),
+            strtab: StringTable::new(),
             data: symbols,
         })
     }
+
+    // load symbol table with names, .symtab and .dynsym link to different string tables
+    pub fn load_linked<T>(elf: &mut T, ehdr: &ELFHeader, table: &SectionTable, section: &SectionHeader)
+        -> Result<SymbolTable>
+        where T: Read + Seek
+    {
+        let mut symbols = SymbolTable::load(elf, ehdr, section)?;
+        let strtab = match table.data.get(section.sh_link as usize) {
+            Some(s) => s,
+            None => return Err(Error::out_of_range("sh_link of symbol table", section.sh_link as u64, table.data.len() as u64)),
+        };
+        symbols.strtab = StringTable::load(elf, strtab)?;
+        Ok(symbols)
+    }
+
+    pub fn name(&self, symbol: &SymbolEntry) -> String {
+        if (symbol.st_name as usize) < self.strtab.data.len() {
+            self.strtab.get(symbol.st_name as usize)
+        }
+        else {
+            String::new()
+        }
+    }
 }
 
 #[derive(Debug, Default)]
@@ -285,6 +490,39 @@ pub struct RelEntry {
     pub r_info: u32,
 }
 
+// r_info of elf64 holds the symbol in the high 32 bits, ELF32 keeps 24 of them
+impl From<&elf64::RelEntry> for RelEntry {
+    fn from(rel: &elf64::RelEntry) -> RelEntry {
+        RelEntry {
+            r_offset: rel.r_offset as u32,
+            r_info: value::relocation_info(rel.symbol(), rel.rel_type()),
+        }
+    }
+}
+
+impl RelEntry {
+    // data holds at least one entry
+    pub fn parse(data: &[u8], endian: Endian) -> RelEntry {
+        let mut cursor = data;
+        let r_offset = endian.read_u32(&mut cursor);
+        let r_info = endian.read_u32(&mut cursor);
+        RelEntry { r_offset, r_info }
+    }
+
+    pub fn write_to(&self, endian: Endian, buf: &mut Vec<u8>) {
+        endian.write_u32(buf, self.r_offset);
+        endian.write_u32(buf, self.r_info);
+    }
+
+    pub fn symbol(&self) -> u32 {
+        value::relocation_symbol(self.r_info)
+    }
+
+    pub fn rel_type(&self) -> u32 {
+        value::relocation_type(self.r_info)
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct RelTable {
     pub section: SectionHeader,
@@ -296,41 +534,32 @@ impl RelTable {
         Self::default()
     }
 
-    pub fn load<T>(elf: &mut T, section: &SectionHeader) -> Result<RelTable, String>
+    pub fn load<T>(elf: &mut T, ehdr: &ELFHeader, section: &SectionHeader) -> Result<RelTable>
         where T: Read + Seek
     {
         // check section type
         if section.sh_type != value::SHT_REL {
-            return Err(String::from("invalid section type"));
+            return Err(Error::invalid("sh_type", format!("{:#x} is not SHT_REL", section.sh_type)));
         }
         // sizeof(RelEntry) = 0x8
         if section.sh_entsize < 0x8 {
-            return Err(String::from("invalid entry size"));
+            return Err(Error::invalid("sh_entsize", format!("{:#x}", section.sh_entsize)));
         }
         if section.sh_size % section.sh_entsize != 0 {
-            return Err(String::from("invalid section size"));
+            return Err(Error::invalid("sh_size", format!("{:#x} is not a multiple of sh_entsize", section.sh_size)));
         }
 
+        let endian = ehdr.endian();
         let offset = section.sh_offset as u64;
         let entry_size = section.sh_entsize as u64;
         let num = (section.sh_size as u64) / entry_size;
         let mut data: [u8; 0x8] = [0; 0x8];
-        let mut entries: Vec<RelEntry> = Vec::with_capacity(num as usize);
+        let mut entries: Vec<RelEntry> = Vec::new();
 
         for n in 0..num {
             let pos = offset + n * entry_size;
-            if let Err(e) = elf.seek(SeekFrom::Start(pos)) {
-                return Err(e.to_string());
-            }
-            match elf.read(&mut data) {
-                Ok(n) => if n != 0x8 { return Err(String::from("invalid length")); },
-                Err(e) => return Err(e.to_string()),
-            };
-            // parse data
-            let mut cursor = &data[..];
-            let r_offset = cursor.get_u32_le();
-            let r_info = cursor.get_u32_le();
-            entries.push(RelEntry { r_offset, r_info });
+            read_at(elf, pos, &mut data, || format!("relocation {}", n))?;
+            entries.push(RelEntry::parse(&data, endian));
         }
 
         Ok(RelTable {
@@ -347,6 +576,41 @@ pub struct RelaEntry {
     pub r_addend: i32,
 }
 
+impl From<&elf64::RelaEntry> for RelaEntry {
+    fn from(rela: &elf64::RelaEntry) -> RelaEntry {
+        RelaEntry {
+            r_offset: rela.r_offset as u32,
+            r_info: value::relocation_info(rela.symbol(), rela.rel_type()),
+            r_addend: rela.r_addend as i32,
+        }
+    }
+}
+
+impl RelaEntry {
+    // data holds at least one entry
+    pub fn parse(data: &[u8], endian: Endian) -> RelaEntry {
+        let mut cursor = data;
+        let r_offset = endian.read_u32(&mut cursor);
+        let r_info = endian.read_u32(&mut cursor);
+        let r_addend = endian.read_i32(&mut cursor);
+        RelaEntry { r_offset, r_info, r_addend }
+    }
+
+    pub fn write_to(&self, endian: Endian, buf: &mut Vec<u8>) {
+        endian.write_u32(buf, self.r_offset);
+        endian.write_u32(buf, self.r_info);
+        endian.write_i32(buf, self.r_addend);
+    }
+
+    pub fn symbol(&self) -> u32 {
+        value::relocation_symbol(self.r_info)
+    }
+
+    pub fn rel_type(&self) -> u32 {
+        value::relocation_type(self.r_info)
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct RelaTable {
     pub section: SectionHeader,
@@ -358,42 +622,32 @@ impl RelaTable {
         Self::default()
     }
 
-    pub fn load<T>(elf: &mut T, section: &SectionHeader) -> Result<RelaTable, String>
+    pub fn load<T>(elf: &mut T, ehdr: &ELFHeader, section: &SectionHeader) -> Result<RelaTable>
         where T: Read + Seek
     {
         // check section type
         if section.sh_type != value::SHT_RELA {
-            return Err(String::from("invalid section type"));
+            return Err(Error::invalid("sh_type", format!("{:#x} is not SHT_RELA", section.sh_type)));
         }
         // sizeof(RelEntry) = 0xC
         if section.sh_entsize < 0xC {
-            return Err(String::from("invalid entry size"));
+            return Err(Error::invalid("sh_entsize", format!("{:#x}", section.sh_entsize)));
         }
         if section.sh_size % section.sh_entsize != 0 {
-            return Err(String::from("invalid section size"));
+            return Err(Error::invalid("sh_size", format!("{:#x} is not a multiple of sh_entsize", section.sh_size)));
         }
 
+        let endian = ehdr.endian();
         let offset = section.sh_offset as u64;
         let entry_size = section.sh_entsize as u64;
         let num = (section.sh_size as u64) / entry_size;
         let mut data: [u8; 0xC] = [0; 0xC];
-        let mut entries: Vec<RelaEntry> = Vec::with_capacity(num as usize);
+        let mut entries: Vec<RelaEntry> = Vec::new();
 
         for n in 0..num {
             let pos = offset + n * entry_size;
-            if let Err(e) = elf.seek(SeekFrom::Start(pos)) {
-                return Err(e.to_string());
-            }
-            match elf.read(&mut data) {
-                Ok(n) => if n != 0xC { return Err(String::from("invalid length")); },
-                Err(e) => return Err(e.to_string()),
-            };
-            // parse data
-            let mut cursor = &data[..];
-            let r_offset = cursor.get_u32_le();
-            let r_info = cursor.get_u32_le();
-            let r_addend = cursor.get_i32_le();
-            entries.push(RelaEntry { r_offset, r_info, r_addend });
+            read_at(elf, pos, &mut data, || format!("relocation {}", n))?;
+            entries.push(RelaEntry::parse(&data, endian));
         }
 
         Ok(RelaTable {
@@ -403,6 +657,88 @@ impl RelaTable {
     }
 }
 
+#[derive(Debug, Default, Clone)]
+pub struct DynamicEntry {
+    pub d_tag: i32,
+    pub d_val: u32,
+}
+
+impl From<&elf64::DynamicEntry> for DynamicEntry {
+    fn from(dyn_: &elf64::DynamicEntry) -> DynamicEntry {
+        DynamicEntry {
+            d_tag: dyn_.d_tag as i32,
+            d_val: dyn_.d_val as u32,
+        }
+    }
+}
+
+impl DynamicEntry {
+    // data holds at least one entry
+    pub fn parse(data: &[u8], endian: Endian) -> DynamicEntry {
+        let mut cursor = data;
+        let d_tag = endian.read_i32(&mut cursor);
+        let d_val = endian.read_u32(&mut cursor);
+        DynamicEntry { d_tag, d_val }
+    }
+
+    pub fn write_to(&self, endian: Endian, buf: &mut Vec<u8>) {
+        endian.write_i32(buf, self.d_tag);
+        endian.write_u32(buf, self.d_val);
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct DynamicTable {
+    pub section: SectionHeader,
+    pub data: Vec<DynamicEntry>,
+}
+
+impl DynamicTable {
+    pub fn new() -> DynamicTable {
+        Self::default()
+    }
+
+    pub fn load<T>(elf: &mut T, ehdr: &ELFHeader, section: &SectionHeader) -> Result<DynamicTable>
+        where T: Read + Seek
+    {
+        // check section type
+        if section.sh_type != value::SHT_DYNAMIC {
+            return Err(Error::invalid("sh_type", format!("{:#x} is not SHT_DYNAMIC", section.sh_type)));
+        }
+        // sizeof(DynamicEntry) = 0x8
+        if section.sh_entsize < 0x8 {
+            return Err(Error::invalid("sh_entsize", format!("{:#x}", section.sh_entsize)));
+        }
+        if !section.sh_size.is_multiple_of(section.sh_entsize) {
+            return Err(Error::invalid("sh_size", format!("{:#x} is not a multiple of sh_entsize", section.sh_size)));
+        }
+
+        let endian = ehdr.endian();
+        let offset = section.sh_offset as u64;
+        let entry_size = section.sh_entsize as u64;
+        let num = (section.sh_size as u64) / entry_size;
+        let mut data: [u8; 0x8] = [0; 0x8];
+        let mut entries: Vec<DynamicEntry> = Vec::new();
+
+        for n in 0..num {
+            let pos = offset + n * entry_size;
+            read_at(elf, pos, &mut data, || format!("dynamic entry {}", n))?;
+            let entry = DynamicEntry::parse(&data, endian);
+            let d_tag = entry.d_tag;
+            entries.push(entry);
+            // DT_NULL marks the end of table, the rest is padding
+            if d_tag as i64 == value::DT_NULL {
+                break;
+            }
+        }
+
+        Ok(DynamicTable {
+            section: section.clone(),
+            data: entries,
+        })
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct ProgramHeader {
     pub p_type: u32,
@@ -415,14 +751,92 @@ pub struct ProgramHeader {
     pub p_align: u32,
 }
 
+impl From<&elf64::ProgramHeader> for ProgramHeader {
+    fn from(phdr: &elf64::ProgramHeader) -> ProgramHeader {
+        ProgramHeader {
+            p_type: phdr.p_type,
+            p_offset: phdr.p_offset as u32,
+            p_vaddr: phdr.p_vaddr as u32,
+            p_paddr: phdr.p_paddr as u32,
+            p_filesz: phdr.p_filesz as u32,
+            p_memsz: phdr.p_memsz as u32,
+            p_flags: phdr.p_flags,
+            p_align: phdr.p_align as u32,
+        }
+    }
+}
+
+impl ProgramHeader {
+    // data holds at least one entry
+    pub fn parse(data: &[u8], endian: Endian) -> ProgramHeader {
+        let mut cursor = data;
+        let p_type: u32 = endian.read_u32(&mut cursor);
+        let p_offset: u32 = endian.read_u32(&mut cursor);
+        let p_vaddr: u32 = endian.read_u32(&mut cursor);
+        let p_paddr: u32 = endian.read_u32(&mut cursor);
+        let p_filesz: u32 = endian.read_u32(&mut cursor);
+        let p_memsz: u32 = endian.read_u32(&mut cursor);
+        let p_flags: u32 = endian.read_u32(&mut cursor);
+        let p_align: u32 = endian.read_u32(&mut cursor);
+        ProgramHeader {
+            p_type, p_offset, p_vaddr, p_paddr,
+            p_filesz, p_memsz, p_flags, p_align,
+        }
+    }
+
+    pub fn write_to(&self, endian: Endian, buf: &mut Vec<u8>) {
+        endian.write_u32(buf, self.p_type);
+        endian.write_u32(buf, self.p_offset);
+        endian.write_u32(buf, self.p_vaddr);
+        endian.write_u32(buf, self.p_paddr);
+        endian.write_u32(buf, self.p_filesz);
+        endian.write_u32(buf, self.p_memsz);
+        endian.write_u32(buf, self.p_flags);
+        endian.write_u32(buf, self.p_align);
+    }
+
+    // processor-specific types are told apart by e_machine
+    pub fn segment_type(&self, machine: u16) -> SegmentType {
+        SegmentType::new(self.p_type, machine)
+    }
+
+    pub fn flags(&self) -> SegmentFlags {
+        SegmentFlags(self.p_flags)
+    }
+}
+
+// entry of .ARM.exidx, only found in ELF32 files of ARM
+#[derive(Debug, Default, Clone)]
+pub struct ExidxEntry {
+    // prel31 offset to the function start
+    pub fnoffset: u32,
+    // EXIDX_CANTUNWIND, inline unwind instructions or prel31 offset into .ARM.extab
+    pub insn: u32,
+}
+
+impl ExidxEntry {
+    // data holds at least one entry
+    pub fn parse(data: &[u8], endian: Endian) -> ExidxEntry {
+        let mut cursor = data;
+        let fnoffset = endian.read_u32(&mut cursor);
+        let insn = endian.read_u32(&mut cursor);
+        ExidxEntry { fnoffset, insn }
+    }
+
+    pub fn write_to(&self, endian: Endian, buf: &mut Vec<u8>) {
+        endian.write_u32(buf, self.fnoffset);
+        endian.write_u32(buf, self.insn);
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct ProgramTable {
     // ELFHeader.e_phoff
     pub offset: u32,
     // ELFHeader.e_phentsize
     pub size: u16,
-    // ELFHeader.e_phnum
-    pub num: u16,
+    // ELFHeader.e_phnum, sh_info of section 0 with extended numbering
+    pub num: u32,
     pub programs: Vec<ProgramHeader>,
 }
 
@@ -431,51 +845,36 @@ impl ProgramTable {
         Self::default()
     }
 
-    pub fn load<T>(elf: &mut T, ehdr: &ELFHeader) -> Result<ProgramTable, String>
+    pub fn load<T>(elf: &mut T, ehdr: &ELFHeader) -> Result<ProgramTable>
         where T: Read + Seek
     {
         // check e_phentsize, min size is 0x20
         if ehdr.e_phentsize < 0x20 {
-            return Err(String::from("invalid e_shentsize"));
+            return Err(Error::invalid("e_phentsize", format!("{:#x}", ehdr.e_phentsize)));
         }
-        let num = ehdr.e_phnum as u64;
+        let endian = ehdr.endian();
+        let num = match ehdr.e_phnum {
+            value::PN_XNUM if ehdr.e_shoff != 0 => first_section(elf, ehdr)?.sh_info,
+            n => n as u32,
+        };
         let size = ehdr.e_phentsize as u64;
         let offset = ehdr.e_phoff as u64;
 
         let mut data: [u8; 0x20] = [0; 0x20];
-        let mut programs: Vec<ProgramHeader> = Vec::with_capacity(num as usize);
+        let mut programs: Vec<ProgramHeader> = Vec::with_capacity(num.min(value::PN_XNUM as u32) as usize);
 
-        for i in 0..num {
+        for i in 0..num as u64 {
             // read data
             let pos: u64 = offset + i * size;
-            if let Err(e) = elf.seek(std::io::SeekFrom::Start(pos)) {
-                return Err(e.to_string());
-            }
-            match elf.read(&mut data) {
-                Ok(n) => if n != 0x20 { return Err(String::from("invalid length")) },
-                Err(e) => return Err(e.to_string()),
-            };
-            // parse section header
-            let mut cursor = &data[..];
-            let p_type: u32 = cursor.get_u32_le();
-            let p_offset: u32 = cursor.get_u32_le();
-            let p_vaddr: u32 = cursor.get_u32_le();
-            let p_paddr: u32 = cursor.get_u32_le();
-            let p_filesz: u32 = cursor.get_u32_le();
-            let p_memsz: u32 = cursor.get_u32_le();
-            let p_flags: u32 = cursor.get_u32_le();
-            let p_align: u32 = cursor.get_u32_le();
-            programs.push(ProgramHeader {
-                p_type, p_offset, p_vaddr, p_paddr,
-                p_filesz, p_memsz, p_flags, p_align,
-            });
+            read_at(elf, pos, &mut data, || format!("program header {}", i))?;
+            programs.push(ProgramHeader::parse(&data, endian));
         }
 
         Ok(ProgramTable {
             programs,
             offset: ehdr.e_phoff,
             size: ehdr.e_phentsize,
-            num: ehdr.e_phnum,
+            num,
         })
     }
 }
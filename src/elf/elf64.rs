@@ -0,0 +1,861 @@
+use bytes::Buf;
+use std::io::prelude::*;
+use crate::{
+    elf::{elf32, endian::Endian, section::{SectionFlags, SectionType}, segment::{SegmentFlags, SegmentType}, symbol::{SymbolBind, SymbolType, SymbolVisibility}, value},
+    error::{read_at, read_vec_at, Error, Result},
+};
+
+
+#[derive(Debug, Default, Clone)]
+pub struct ELFHeader {
+    pub e_ident: [u8; 16],
+    pub e_type: u16,
+    pub e_machine: u16,
+    pub e_version: u32,
+    pub e_entry: u64,
+    pub e_phoff: u64,
+    pub e_shoff: u64,
+    pub e_flags: u32,
+    pub e_ehsize: u16,
+    pub e_phentsize: u16,
+    pub e_phnum: u16,
+    pub e_shentsize: u16,
+    pub e_shnum: u16,
+    pub e_shstrndx: u16,
+}
+
+impl ELFHeader {
+    pub fn new() -> ELFHeader {
+        Self::default()
+    }
+
+    pub fn load<T>(elf: &mut T) -> Result<ELFHeader>
+        where T: Read
+    {
+        // sizeof(ELFHeader) == 0x40
+        let mut data: [u8; 0x40] = [0; 0x40];
+        if let Err(e) = elf.read_exact(&mut data) {
+            return Err(match e.kind() {
+                std::io::ErrorKind::UnexpectedEof => Error::truncated("ELF header", 0, 0x40),
+                _ => Error::from(e),
+            });
+        }
+        ELFHeader::parse(&data)
+    }
+
+    // data holds at least 0x40 bytes of header
+    pub fn parse(data: &[u8]) -> Result<ELFHeader> {
+        if data.len() < 0x40 {
+            return Err(Error::truncated("ELF header", 0, 0x40));
+        }
+        let mut cursor = data;
+        let mut e_ident: [u8; 16] = [0; 16];
+        cursor.copy_to_slice(&mut e_ident);
+        // the rest of header follows EI_DATA
+        let endian = Endian::from_ident(&e_ident)?;
+        let e_type: u16 = endian.read_u16(&mut cursor);
+        let e_machine: u16 = endian.read_u16(&mut cursor);
+        let e_version: u32 = endian.read_u32(&mut cursor);
+        let e_entry: u64 = endian.read_u64(&mut cursor);
+        let e_phoff: u64 = endian.read_u64(&mut cursor);
+        let e_shoff: u64 = endian.read_u64(&mut cursor);
+        let e_flags: u32 = endian.read_u32(&mut cursor);
+        let e_ehsize: u16 = endian.read_u16(&mut cursor);
+        let e_phentsize: u16 = endian.read_u16(&mut cursor);
+        let e_phnum: u16 = endian.read_u16(&mut cursor);
+        let e_shentsize: u16 = endian.read_u16(&mut cursor);
+        let e_shnum: u16 = endian.read_u16(&mut cursor);
+        let e_shstrndx: u16 = endian.read_u16(&mut cursor);
+
+        Ok(ELFHeader {
+            e_ident, e_type, e_machine, e_version,
+            e_entry, e_phoff, e_shoff, e_flags,
+            e_ehsize, e_phentsize, e_phnum,
+            e_shentsize, e_shnum, e_shstrndx,
+        })
+    }
+
+    pub fn endian(&self) -> Endian {
+        match self.e_ident[value::EI_DATA] {
+            value::ELFDATA2MSB => Endian::Big,
+            _ => Endian::Little,
+        }
+    }
+
+    pub fn write_to(&self, buf: &mut Vec<u8>) {
+        let endian = self.endian();
+        buf.extend_from_slice(&self.e_ident);
+        endian.write_u16(buf, self.e_type);
+        endian.write_u16(buf, self.e_machine);
+        endian.write_u32(buf, self.e_version);
+        endian.write_u64(buf, self.e_entry);
+        endian.write_u64(buf, self.e_phoff);
+        endian.write_u64(buf, self.e_shoff);
+        endian.write_u32(buf, self.e_flags);
+        endian.write_u16(buf, self.e_ehsize);
+        endian.write_u16(buf, self.e_phentsize);
+        endian.write_u16(buf, self.e_phnum);
+        endian.write_u16(buf, self.e_shentsize);
+        endian.write_u16(buf, self.e_shnum);
+        endian.write_u16(buf, self.e_shstrndx);
+    }
+}
+
+impl From<&elf32::ELFHeader> for ELFHeader {
+    fn from(ehdr: &elf32::ELFHeader) -> ELFHeader {
+        ELFHeader {
+            e_ident: ehdr.e_ident,
+            e_type: ehdr.e_type,
+            e_machine: ehdr.e_machine,
+            e_version: ehdr.e_version,
+            e_entry: ehdr.e_entry as u64,
+            e_phoff: ehdr.e_phoff as u64,
+            e_shoff: ehdr.e_shoff as u64,
+            e_flags: ehdr.e_flags,
+            e_ehsize: ehdr.e_ehsize,
+            e_phentsize: ehdr.e_phentsize,
+            e_phnum: ehdr.e_phnum,
+            e_shentsize: ehdr.e_shentsize,
+            e_shnum: ehdr.e_shnum,
+            e_shstrndx: ehdr.e_shstrndx,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct SectionHeader {
+    pub sh_name: u32,
+    pub sh_type: u32,
+    pub sh_flags: u64,
+    pub sh_addr: u64,
+    pub sh_offset: u64,
+    pub sh_size: u64,
+    pub sh_link: u32,
+    pub sh_info: u32,
+    pub sh_addralign: u64,
+    pub sh_entsize: u64,
+}
+
+impl SectionHeader {
+    // data holds at least one entry
+    pub fn parse(data: &[u8], endian: Endian) -> SectionHeader {
+        let mut cursor = data;
+        let sh_name: u32 = endian.read_u32(&mut cursor);
+        let sh_type: u32 = endian.read_u32(&mut cursor);
+        let sh_flags: u64 = endian.read_u64(&mut cursor);
+        let sh_addr: u64 = endian.read_u64(&mut cursor);
+        let sh_offset: u64 = endian.read_u64(&mut cursor);
+        let sh_size: u64 = endian.read_u64(&mut cursor);
+        let sh_link: u32 = endian.read_u32(&mut cursor);
+        let sh_info: u32 = endian.read_u32(&mut cursor);
+        let sh_addralign: u64 = endian.read_u64(&mut cursor);
+        let sh_entsize: u64 = endian.read_u64(&mut cursor);
+        SectionHeader {
+            sh_name, sh_type, sh_flags, sh_addr,
+            sh_offset, sh_size, sh_link, sh_info,
+            sh_addralign, sh_entsize,
+        }
+    }
+
+    pub fn write_to(&self, endian: Endian, buf: &mut Vec<u8>) {
+        endian.write_u32(buf, self.sh_name);
+        endian.write_u32(buf, self.sh_type);
+        endian.write_u64(buf, self.sh_flags);
+        endian.write_u64(buf, self.sh_addr);
+        endian.write_u64(buf, self.sh_offset);
+        endian.write_u64(buf, self.sh_size);
+        endian.write_u32(buf, self.sh_link);
+        endian.write_u32(buf, self.sh_info);
+        endian.write_u64(buf, self.sh_addralign);
+        endian.write_u64(buf, self.sh_entsize);
+    }
+
+    // processor-specific types and flags are told apart by e_machine
+    pub fn section_type(&self, machine: u16) -> SectionType {
+        SectionType::new(self.sh_type, machine)
+    }
+
+    pub fn flags(&self, machine: u16) -> SectionFlags {
+        SectionFlags::new(self.sh_flags, machine)
+    }
+}
+
+impl From<&elf32::SectionHeader> for SectionHeader {
+    fn from(shdr: &elf32::SectionHeader) -> SectionHeader {
+        SectionHeader {
+            sh_name: shdr.sh_name,
+            sh_type: shdr.sh_type,
+            sh_flags: shdr.sh_flags as u64,
+            sh_addr: shdr.sh_addr as u64,
+            sh_offset: shdr.sh_offset as u64,
+            sh_size: shdr.sh_size as u64,
+            sh_link: shdr.sh_link,
+            sh_info: shdr.sh_info,
+            sh_addralign: shdr.sh_addralign as u64,
+            sh_entsize: shdr.sh_entsize as u64,
+        }
+    }
+}
+
+// header at the start of SHF_COMPRESSED section data
+#[derive(Debug, Default, Clone)]
+pub struct CompressionHeader {
+    pub ch_type: u32,
+    pub ch_reserved: u32,
+    // size and alignment of the uncompressed data
+    pub ch_size: u64,
+    pub ch_addralign: u64,
+}
+
+impl CompressionHeader {
+    // data holds at least one header
+    pub fn parse(data: &[u8], endian: Endian) -> CompressionHeader {
+        let mut cursor = data;
+        let ch_type = endian.read_u32(&mut cursor);
+        let ch_reserved = endian.read_u32(&mut cursor);
+        let ch_size = endian.read_u64(&mut cursor);
+        let ch_addralign = endian.read_u64(&mut cursor);
+        CompressionHeader { ch_type, ch_reserved, ch_size, ch_addralign }
+    }
+
+    pub fn write_to(&self, endian: Endian, buf: &mut Vec<u8>) {
+        endian.write_u32(buf, self.ch_type);
+        endian.write_u32(buf, self.ch_reserved);
+        endian.write_u64(buf, self.ch_size);
+        endian.write_u64(buf, self.ch_addralign);
+    }
+}
+
+impl From<&elf32::CompressionHeader> for CompressionHeader {
+    fn from(chdr: &elf32::CompressionHeader) -> CompressionHeader {
+        CompressionHeader {
+            ch_type: chdr.ch_type,
+            ch_reserved: 0,
+            ch_size: chdr.ch_size as u64,
+            ch_addralign: chdr.ch_addralign as u64,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct SectionTable {
+    // ELFHeader.e_shoff
+    pub offset: u64,
+    // ELFHeader.e_shentsize
+    pub size: u16,
+    // ELFHeader.e_shnum, sh_size of section 0 with extended numbering
+    pub num: u64,
+    // ELFHeader.e_shstrndx, sh_link of section 0 with extended numbering
+    pub name: u32,
+    pub data: Vec<SectionHeader>,
+}
+
+impl SectionTable {
+    pub fn new() -> SectionTable {
+        Self::default()
+    }
+
+    pub fn load<T>(elf: &mut T, ehdr: &ELFHeader) -> Result<SectionTable>
+        where T: Read + Seek
+    {
+        let endian = ehdr.endian();
+        let size = ehdr.e_shentsize as u64;
+        let offset = ehdr.e_shoff;
+        // no section header table
+        if ehdr.e_shoff == 0 {
+            return Ok(SectionTable { size: ehdr.e_shentsize, ..SectionTable::new() });
+        }
+
+        // section 0 holds what does not fit the 16-bit fields, extended numbering
+        let first = match ehdr.e_shnum == 0 || ehdr.e_shstrndx as usize == value::SHN_XINDEX {
+            true => Some(first_section(elf, ehdr)?),
+            false => None,
+        };
+        let num = match (ehdr.e_shnum, &first) {
+            (0, Some(first)) => first.sh_size,
+            (n, _) => n as u64,
+        };
+        let name = match (ehdr.e_shstrndx as usize, &first) {
+            (value::SHN_XINDEX, Some(first)) => first.sh_link,
+            (n, _) => n as u32,
+        };
+        // check e_shentsize, min size is 0x40
+        if num != 0 && ehdr.e_shentsize < 0x40 {
+            return Err(Error::invalid("e_shentsize", format!("{:#x}", ehdr.e_shentsize)));
+        }
+
+        let mut data: [u8; 0x40] = [0; 0x40];
+        // a count taken from section 0 is only trusted as far as reading goes
+        let mut sections: Vec<SectionHeader> = Vec::with_capacity(num.min(value::SHN_LORESERVE as u64) as usize);
+
+        for i in 0..num {
+            // read data
+            let pos: u64 = offset.saturating_add(i * size);
+            read_at(elf, pos, &mut data, || format!("section header {}", i))?;
+            sections.push(SectionHeader::parse(&data, endian));
+        }
+
+        Ok(SectionTable {
+            offset: ehdr.e_shoff,
+            size: ehdr.e_shentsize,
+            num, name,
+            data: sections,
+        })
+    }
+}
+
+// section 0 at e_shoff, which holds the counts of extended numbering
+fn first_section<T>(elf: &mut T, ehdr: &ELFHeader) -> Result<SectionHeader>
+    where T: Read + Seek
+{
+    // check e_shentsize, min size is 0x40
+    if ehdr.e_shentsize < 0x40 {
+        return Err(Error::invalid("e_shentsize", format!("{:#x}", ehdr.e_shentsize)));
+    }
+    let mut data: [u8; 0x40] = [0; 0x40];
+    read_at(elf, ehdr.e_shoff, &mut data, || String::from("section header 0"))?;
+    Ok(SectionHeader::parse(&data, ehdr.endian()))
+}
+
+#[derive(Debug, Default)]
+pub struct StringTable {
+    pub section: SectionHeader,
+    pub data: Vec<u8>,
+}
+
+impl StringTable {
+    pub fn new() -> StringTable {
+        Self::default()
+    }
+
+    pub fn load<T>(elf: &mut T, section: &SectionHeader) -> Result<StringTable>
+        where T: Read + Seek
+    {
+        // check section type
+        if section.sh_type != value::SHT_STRTAB {
+            return Err(Error::invalid("sh_type", format!("{:#x} is not SHT_STRTAB", section.sh_type)));
+        }
+        let data = read_vec_at(elf, section.sh_offset, section.sh_size, || String::from("string table"))?;
+
+        Ok(StringTable {
+            section: section.clone(),
+            data
+        })
+    }
+
+    // string at pos, empty if pos is out of range and lossy if not UTF-8
+    pub fn get(&self, pos: usize) -> String {
+        let rest = match self.data.get(pos..) {
+            Some(rest) => rest,
+            None => return String::new(),
+        };
+        let end = rest.iter().position(|c| *c == b'\0').unwrap_or(rest.len());
+        String::from_utf8_lossy(&rest[..end]).into_owned()
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct SymbolEntry {
+    pub st_name: u32,
+    pub st_info: u8,
+    pub st_other: u8,
+    pub st_shndx: u16,
+    pub st_value: u64,
+    pub st_size: u64,
+}
+
+impl From<&elf32::SymbolEntry> for SymbolEntry {
+    fn from(sym: &elf32::SymbolEntry) -> SymbolEntry {
+        SymbolEntry {
+            st_name: sym.st_name,
+            st_info: sym.st_info,
+            st_other: sym.st_other,
+            st_shndx: sym.st_shndx,
+            st_value: sym.st_value as u64,
+            st_size: sym.st_size as u64,
+        }
+    }
+}
+
+impl SymbolEntry {
+    // Elf64_Sym moves st_value and st_size to the end
+    pub fn parse(data: &[u8], endian: Endian) -> SymbolEntry {
+        let mut cursor = data;
+        let st_name = endian.read_u32(&mut cursor);
+        let st_info = cursor.get_u8();
+        let st_other = cursor.get_u8();
+        let st_shndx = endian.read_u16(&mut cursor);
+        let st_value = endian.read_u64(&mut cursor);
+        let st_size = endian.read_u64(&mut cursor);
+        SymbolEntry {
+            st_name, st_info, st_other,
+            st_shndx, st_value, st_size,
+        }
+    }
+
+    pub fn write_to(&self, endian: Endian, buf: &mut Vec<u8>) {
+        endian.write_u32(buf, self.st_name);
+        buf.push(self.st_info);
+        buf.push(self.st_other);
+        endian.write_u16(buf, self.st_shndx);
+        endian.write_u64(buf, self.st_value);
+        endian.write_u64(buf, self.st_size);
+    }
+
+    pub fn bind(&self) -> SymbolBind {
+        SymbolBind::from(value::symbol_bind(self.st_info))
+    }
+
+    pub fn symbol_type(&self) -> SymbolType {
+        SymbolType::from(value::symbol_type(self.st_info))
+    }
+
+    pub fn visibility(&self) -> SymbolVisibility {
+        SymbolVisibility::from(self.st_other)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct SymbolTable {
+    pub section: SectionHeader,
+    // string table of section.sh_link, empty unless loaded by load_linked
+    pub strtab: StringTable,
+    pub data: Vec<SymbolEntry>,
+}
+
+impl SymbolTable {
+    pub fn new() -> SymbolTable {
+        Self::default()
+    }
+
+    pub fn load<T>(elf: &mut T, ehdr: &ELFHeader, section: &SectionHeader) -> Result<SymbolTable>
+        where T: Read + Seek
+    {
+        // check section type, .symtab or .dynsym
+        if section.sh_type != value::SHT_SYMTAB && section.sh_type != value::SHT_DYNSYM {
+            return Err(Error::invalid("sh_type", format!("{:#x} is not SHT_SYMTAB or SHT_DYNSYM", section.sh_type)));
+        }
+        // sizeof(SymbolEntry) = 0x18
+        if section.sh_entsize < 0x18 {
+            return Err(Error::invalid("sh_entsize", format!("{:#x}", section.sh_entsize)));
+        }
+        if !section.sh_size.is_multiple_of(section.sh_entsize) {
+            return Err(Error::invalid("sh_size", format!("{:#x} is not a multiple of sh_entsize", section.sh_size)));
+        }
+
+        let endian = ehdr.endian();
+        let offset = section.sh_offset;
+        let entry_size = section.sh_entsize;
+        let num = section.sh_size / entry_size;
+        let mut data: [u8; 0x18] = [0; 0x18];
+        // entries are kept as they are read, sh_size is not trusted for allocation
+        let mut symbols: Vec<SymbolEntry> = Vec::new();
+
+        for n in 0..num {
+            let pos = offset.saturating_add(n * entry_size);
+            read_at(elf, pos, &mut data, || format!("symbol {}", n))?;
+            symbols.push(SymbolEntry::parse(&data, endian));
+        }
+
+        Ok(SymbolTable {
+            section: section.clone(),
+            strtab: StringTable::new(),
+            data: symbols,
+        })
+    }
+
+    // load symbol table with names, .symtab and .dynsym link to different string tables
+    pub fn load_linked<T>(elf: &mut T, ehdr: &ELFHeader, table: &SectionTable, section: &SectionHeader)
+        -> Result<SymbolTable>
+        where T: Read + Seek
+    {
+        let mut symbols = SymbolTable::load(elf, ehdr, section)?;
+        let strtab = match table.data.get(section.sh_link as usize) {
+            Some(s) => s,
+            None => return Err(Error::out_of_range("sh_link of symbol table", section.sh_link as u64, table.data.len() as u64)),
+        };
+        symbols.strtab = StringTable::load(elf, strtab)?;
+        Ok(symbols)
+    }
+
+    pub fn name(&self, symbol: &SymbolEntry) -> String {
+        if (symbol.st_name as usize) < self.strtab.data.len() {
+            self.strtab.get(symbol.st_name as usize)
+        }
+        else {
+            String::new()
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct RelEntry {
+    pub r_offset: u64,
+    pub r_info: u64,
+}
+
+impl From<&elf32::RelEntry> for RelEntry {
+    fn from(rel: &elf32::RelEntry) -> RelEntry {
+        // ELF32 r_info keeps the type in the low 8 bits
+        RelEntry {
+            r_offset: rel.r_offset as u64,
+            r_info: value::relocation64_info(rel.r_info >> 8, rel.r_info & 0xFF),
+        }
+    }
+}
+
+impl RelEntry {
+    // data holds at least one entry
+    pub fn parse(data: &[u8], endian: Endian) -> RelEntry {
+        let mut cursor = data;
+        let r_offset = endian.read_u64(&mut cursor);
+        let r_info = endian.read_u64(&mut cursor);
+        RelEntry { r_offset, r_info }
+    }
+
+    pub fn write_to(&self, endian: Endian, buf: &mut Vec<u8>) {
+        endian.write_u64(buf, self.r_offset);
+        endian.write_u64(buf, self.r_info);
+    }
+
+    pub fn symbol(&self) -> u32 {
+        value::relocation64_symbol(self.r_info)
+    }
+
+    pub fn rel_type(&self) -> u32 {
+        value::relocation64_type(self.r_info)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct RelTable {
+    pub section: SectionHeader,
+    pub data: Vec<RelEntry>,
+}
+
+impl RelTable {
+    pub fn new() -> RelTable {
+        Self::default()
+    }
+
+    pub fn load<T>(elf: &mut T, ehdr: &ELFHeader, section: &SectionHeader) -> Result<RelTable>
+        where T: Read + Seek
+    {
+        // check section type
+        if section.sh_type != value::SHT_REL {
+            return Err(Error::invalid("sh_type", format!("{:#x} is not SHT_REL", section.sh_type)));
+        }
+        // sizeof(RelEntry) = 0x10
+        if section.sh_entsize < 0x10 {
+            return Err(Error::invalid("sh_entsize", format!("{:#x}", section.sh_entsize)));
+        }
+        if !section.sh_size.is_multiple_of(section.sh_entsize) {
+            return Err(Error::invalid("sh_size", format!("{:#x} is not a multiple of sh_entsize", section.sh_size)));
+        }
+
+        let endian = ehdr.endian();
+        let offset = section.sh_offset;
+        let entry_size = section.sh_entsize;
+        let num = section.sh_size / entry_size;
+        let mut data: [u8; 0x10] = [0; 0x10];
+        let mut entries: Vec<RelEntry> = Vec::new();
+
+        for n in 0..num {
+            let pos = offset.saturating_add(n * entry_size);
+            read_at(elf, pos, &mut data, || format!("relocation {}", n))?;
+            entries.push(RelEntry::parse(&data, endian));
+        }
+
+        Ok(RelTable {
+            section: section.clone(),
+            data: entries,
+        })
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct RelaEntry {
+    pub r_offset: u64,
+    pub r_info: u64,
+    pub r_addend: i64,
+}
+
+impl From<&elf32::RelaEntry> for RelaEntry {
+    fn from(rela: &elf32::RelaEntry) -> RelaEntry {
+        // ELF32 r_info keeps the type in the low 8 bits
+        RelaEntry {
+            r_offset: rela.r_offset as u64,
+            r_info: value::relocation64_info(rela.r_info >> 8, rela.r_info & 0xFF),
+            r_addend: rela.r_addend as i64,
+        }
+    }
+}
+
+impl RelaEntry {
+    // data holds at least one entry
+    pub fn parse(data: &[u8], endian: Endian) -> RelaEntry {
+        let mut cursor = data;
+        let r_offset = endian.read_u64(&mut cursor);
+        let r_info = endian.read_u64(&mut cursor);
+        let r_addend = endian.read_i64(&mut cursor);
+        RelaEntry { r_offset, r_info, r_addend }
+    }
+
+    pub fn write_to(&self, endian: Endian, buf: &mut Vec<u8>) {
+        endian.write_u64(buf, self.r_offset);
+        endian.write_u64(buf, self.r_info);
+        endian.write_i64(buf, self.r_addend);
+    }
+
+    pub fn symbol(&self) -> u32 {
+        value::relocation64_symbol(self.r_info)
+    }
+
+    pub fn rel_type(&self) -> u32 {
+        value::relocation64_type(self.r_info)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct RelaTable {
+    pub section: SectionHeader,
+    pub data: Vec<RelaEntry>,
+}
+
+impl RelaTable {
+    pub fn new() -> RelaTable {
+        Self::default()
+    }
+
+    pub fn load<T>(elf: &mut T, ehdr: &ELFHeader, section: &SectionHeader) -> Result<RelaTable>
+        where T: Read + Seek
+    {
+        // check section type
+        if section.sh_type != value::SHT_RELA {
+            return Err(Error::invalid("sh_type", format!("{:#x} is not SHT_RELA", section.sh_type)));
+        }
+        // sizeof(RelaEntry) = 0x18
+        if section.sh_entsize < 0x18 {
+            return Err(Error::invalid("sh_entsize", format!("{:#x}", section.sh_entsize)));
+        }
+        if !section.sh_size.is_multiple_of(section.sh_entsize) {
+            return Err(Error::invalid("sh_size", format!("{:#x} is not a multiple of sh_entsize", section.sh_size)));
+        }
+
+        let endian = ehdr.endian();
+        let offset = section.sh_offset;
+        let entry_size = section.sh_entsize;
+        let num = section.sh_size / entry_size;
+        let mut data: [u8; 0x18] = [0; 0x18];
+        let mut entries: Vec<RelaEntry> = Vec::new();
+
+        for n in 0..num {
+            let pos = offset.saturating_add(n * entry_size);
+            read_at(elf, pos, &mut data, || format!("relocation {}", n))?;
+            entries.push(RelaEntry::parse(&data, endian));
+        }
+
+        Ok(RelaTable {
+            section: section.clone(),
+            data: entries,
+        })
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct DynamicEntry {
+    pub d_tag: i64,
+    pub d_val: u64,
+}
+
+impl DynamicEntry {
+    // data holds at least one entry
+    pub fn parse(data: &[u8], endian: Endian) -> DynamicEntry {
+        let mut cursor = data;
+        let d_tag = endian.read_i64(&mut cursor);
+        let d_val = endian.read_u64(&mut cursor);
+        DynamicEntry { d_tag, d_val }
+    }
+
+    pub fn write_to(&self, endian: Endian, buf: &mut Vec<u8>) {
+        endian.write_i64(buf, self.d_tag);
+        endian.write_u64(buf, self.d_val);
+    }
+}
+
+impl From<&elf32::DynamicEntry> for DynamicEntry {
+    fn from(dyn_: &elf32::DynamicEntry) -> DynamicEntry {
+        DynamicEntry {
+            d_tag: dyn_.d_tag as i64,
+            d_val: dyn_.d_val as u64,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct DynamicTable {
+    pub section: SectionHeader,
+    pub data: Vec<DynamicEntry>,
+}
+
+impl DynamicTable {
+    pub fn new() -> DynamicTable {
+        Self::default()
+    }
+
+    pub fn load<T>(elf: &mut T, ehdr: &ELFHeader, section: &SectionHeader) -> Result<DynamicTable>
+        where T: Read + Seek
+    {
+        // check section type
+        if section.sh_type != value::SHT_DYNAMIC {
+            return Err(Error::invalid("sh_type", format!("{:#x} is not SHT_DYNAMIC", section.sh_type)));
+        }
+        // sizeof(DynamicEntry) = 0x10
+        if section.sh_entsize < 0x10 {
+            return Err(Error::invalid("sh_entsize", format!("{:#x}", section.sh_entsize)));
+        }
+        if !section.sh_size.is_multiple_of(section.sh_entsize) {
+            return Err(Error::invalid("sh_size", format!("{:#x} is not a multiple of sh_entsize", section.sh_size)));
+        }
+
+        let endian = ehdr.endian();
+        let offset = section.sh_offset;
+        let entry_size = section.sh_entsize;
+        let num = section.sh_size / entry_size;
+        let mut data: [u8; 0x10] = [0; 0x10];
+        let mut entries: Vec<DynamicEntry> = Vec::new();
+
+        for n in 0..num {
+            let pos = offset.saturating_add(n * entry_size);
+            read_at(elf, pos, &mut data, || format!("dynamic entry {}", n))?;
+            let entry = DynamicEntry::parse(&data, endian);
+            let d_tag = entry.d_tag;
+            entries.push(entry);
+            // DT_NULL marks the end of table, the rest is padding
+            if d_tag == value::DT_NULL {
+                break;
+            }
+        }
+
+        Ok(DynamicTable {
+            section: section.clone(),
+            data: entries,
+        })
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct ProgramHeader {
+    pub p_type: u32,
+    pub p_flags: u32,
+    pub p_offset: u64,
+    pub p_vaddr: u64,
+    pub p_paddr: u64,
+    pub p_filesz: u64,
+    pub p_memsz: u64,
+    pub p_align: u64,
+}
+
+impl ProgramHeader {
+    // Elf64_Phdr moves p_flags after p_type
+    pub fn parse(data: &[u8], endian: Endian) -> ProgramHeader {
+        let mut cursor = data;
+        let p_type: u32 = endian.read_u32(&mut cursor);
+        let p_flags: u32 = endian.read_u32(&mut cursor);
+        let p_offset: u64 = endian.read_u64(&mut cursor);
+        let p_vaddr: u64 = endian.read_u64(&mut cursor);
+        let p_paddr: u64 = endian.read_u64(&mut cursor);
+        let p_filesz: u64 = endian.read_u64(&mut cursor);
+        let p_memsz: u64 = endian.read_u64(&mut cursor);
+        let p_align: u64 = endian.read_u64(&mut cursor);
+        ProgramHeader {
+            p_type, p_flags, p_offset, p_vaddr,
+            p_paddr, p_filesz, p_memsz, p_align,
+        }
+    }
+
+    pub fn write_to(&self, endian: Endian, buf: &mut Vec<u8>) {
+        endian.write_u32(buf, self.p_type);
+        endian.write_u32(buf, self.p_flags);
+        endian.write_u64(buf, self.p_offset);
+        endian.write_u64(buf, self.p_vaddr);
+        endian.write_u64(buf, self.p_paddr);
+        endian.write_u64(buf, self.p_filesz);
+        endian.write_u64(buf, self.p_memsz);
+        endian.write_u64(buf, self.p_align);
+    }
+
+    // processor-specific types are told apart by e_machine
+    pub fn segment_type(&self, machine: u16) -> SegmentType {
+        SegmentType::new(self.p_type, machine)
+    }
+
+    pub fn flags(&self) -> SegmentFlags {
+        SegmentFlags(self.p_flags)
+    }
+}
+
+impl From<&elf32::ProgramHeader> for ProgramHeader {
+    fn from(phdr: &elf32::ProgramHeader) -> ProgramHeader {
+        ProgramHeader {
+            p_type: phdr.p_type,
+            p_flags: phdr.p_flags,
+            p_offset: phdr.p_offset as u64,
+            p_vaddr: phdr.p_vaddr as u64,
+            p_paddr: phdr.p_paddr as u64,
+            p_filesz: phdr.p_filesz as u64,
+            p_memsz: phdr.p_memsz as u64,
+            p_align: phdr.p_align as u64,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ProgramTable {
+    // ELFHeader.e_phoff
+    pub offset: u64,
+    // ELFHeader.e_phentsize
+    pub size: u16,
+    // ELFHeader.e_phnum, sh_info of section 0 with extended numbering
+    pub num: u32,
+    pub programs: Vec<ProgramHeader>,
+}
+
+impl ProgramTable {
+    pub fn new() -> ProgramTable {
+        Self::default()
+    }
+
+    pub fn load<T>(elf: &mut T, ehdr: &ELFHeader) -> Result<ProgramTable>
+        where T: Read + Seek
+    {
+        // check e_phentsize, min size is 0x38
+        if ehdr.e_phentsize < 0x38 {
+            return Err(Error::invalid("e_phentsize", format!("{:#x}", ehdr.e_phentsize)));
+        }
+        let endian = ehdr.endian();
+        let num = match ehdr.e_phnum {
+            value::PN_XNUM if ehdr.e_shoff != 0 => first_section(elf, ehdr)?.sh_info,
+            n => n as u32,
+        };
+        let size = ehdr.e_phentsize as u64;
+        let offset = ehdr.e_phoff;
+
+        let mut data: [u8; 0x38] = [0; 0x38];
+        let mut programs: Vec<ProgramHeader> = Vec::with_capacity(num.min(value::PN_XNUM as u32) as usize);
+
+        for i in 0..num as u64 {
+            // read data
+            let pos: u64 = offset.saturating_add(i * size);
+            read_at(elf, pos, &mut data, || format!("program header {}", i))?;
+            programs.push(ProgramHeader::parse(&data, endian));
+        }
+
+        Ok(ProgramTable {
+            programs,
+            offset: ehdr.e_phoff,
+            size: ehdr.e_phentsize,
+            num,
+        })
+    }
+}
@@ -0,0 +1,91 @@
+use bytes::{Buf, BufMut};
+use crate::{elf::value, error::{Error, Result}};
+
+
+// byte order of an ELF file, from ELFHeader.e_ident[EI_DATA]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Endian {
+    #[default]
+    Little,
+    Big,
+}
+
+impl Endian {
+    pub fn from_ident(e_ident: &[u8; 16]) -> Result<Endian> {
+        match e_ident[value::EI_DATA] {
+            value::ELFDATA2LSB => Ok(Endian::Little),
+            value::ELFDATA2MSB => Ok(Endian::Big),
+            n => Err(Error::UnsupportedEncoding(n)),
+        }
+    }
+
+    pub fn read_u16(self, cursor: &mut &[u8]) -> u16 {
+        match self {
+            Endian::Little => cursor.get_u16_le(),
+            Endian::Big => cursor.get_u16(),
+        }
+    }
+
+    pub fn read_u32(self, cursor: &mut &[u8]) -> u32 {
+        match self {
+            Endian::Little => cursor.get_u32_le(),
+            Endian::Big => cursor.get_u32(),
+        }
+    }
+
+    pub fn read_u64(self, cursor: &mut &[u8]) -> u64 {
+        match self {
+            Endian::Little => cursor.get_u64_le(),
+            Endian::Big => cursor.get_u64(),
+        }
+    }
+
+    pub fn read_i32(self, cursor: &mut &[u8]) -> i32 {
+        match self {
+            Endian::Little => cursor.get_i32_le(),
+            Endian::Big => cursor.get_i32(),
+        }
+    }
+
+    pub fn read_i64(self, cursor: &mut &[u8]) -> i64 {
+        match self {
+            Endian::Little => cursor.get_i64_le(),
+            Endian::Big => cursor.get_i64(),
+        }
+    }
+
+    pub fn write_u16(self, buf: &mut Vec<u8>, n: u16) {
+        match self {
+            Endian::Little => buf.put_u16_le(n),
+            Endian::Big => buf.put_u16(n),
+        }
+    }
+
+    pub fn write_u32(self, buf: &mut Vec<u8>, n: u32) {
+        match self {
+            Endian::Little => buf.put_u32_le(n),
+            Endian::Big => buf.put_u32(n),
+        }
+    }
+
+    pub fn write_u64(self, buf: &mut Vec<u8>, n: u64) {
+        match self {
+            Endian::Little => buf.put_u64_le(n),
+            Endian::Big => buf.put_u64(n),
+        }
+    }
+
+    pub fn write_i32(self, buf: &mut Vec<u8>, n: i32) {
+        match self {
+            Endian::Little => buf.put_i32_le(n),
+            Endian::Big => buf.put_i32(n),
+        }
+    }
+
+    pub fn write_i64(self, buf: &mut Vec<u8>, n: i64) {
+        match self {
+            Endian::Little => buf.put_i64_le(n),
+            Endian::Big => buf.put_i64(n),
+        }
+    }
+}
@@ -0,0 +1,1151 @@
+use std::{
+    fs::File,
+    io::BufReader,
+    io::prelude::*,
+    io::SeekFrom,
+    path::Path,
+};
+use crate::elf::{address::{self, Location}, arm_exidx::ArmExidx, compress::Compression, demangle, dwarf::{self, LineProgram, LineSections, SourceLocation}, dynamic,
+    eh_frame::{EhFrame, EhFrameHdr}, dynamic::{Dynamic, DynamicInfo}, elf32, elf64, endian::Endian, note::NoteTable, section::Group, segment, symbol::SymbolType, value};
+use crate::elf::hash::{self, GnuHash, HashTable, SysvHash};
+use crate::elf::relocation::{self, Relocation, RelocationSection};
+use crate::elf::version::{SymbolVersion, VerdauxEntry, VerdefEntry, VernauxEntry, VerneedEntry,
+    VersionDef, VersionNeed, VersionRequirement, Versions};
+use crate::error::{self, Error, Result};
+
+
+// section header with resolved name, widened to ELF64 layout
+#[derive(Debug, Clone)]
+pub struct Section {
+    pub index: usize,
+    pub name: String,
+    pub header: elf64::SectionHeader,
+}
+
+// symbol entry with resolved name, widened to ELF64 layout
+#[derive(Debug, Clone)]
+pub struct Symbol {
+    pub name: String,
+    pub entry: elf64::SymbolEntry,
+    // st_shndx, the SHT_SYMTAB_SHNDX entry when it is SHN_XINDEX
+    pub shndx: u32,
+    // only dynamic symbols carry versions
+    pub version: Option<SymbolVersion>,
+}
+
+impl Symbol {
+    // name with version suffix, as "memcpy@@GLIBC_2.14"
+    pub fn versioned_name(&self) -> String {
+        match &self.version {
+            Some(version) => format!("{}{}", self.name, version),
+            None => self.name.clone(),
+        }
+    }
+
+    // C++ or Rust name in readable form, None if name is not mangled
+    pub fn demangled(&self) -> Option<String> {
+        demangle::demangle(&self.name)
+    }
+
+    // readable name falling back to name, which stays the mangled one
+    pub fn demangled_name(&self) -> String {
+        demangle::demangle_or_raw(&self.name)
+    }
+
+    // demangled name with version suffix, as "std::__1::cout@@VER"
+    pub fn versioned_demangled_name(&self) -> String {
+        match &self.version {
+            Some(version) => format!("{}{}", self.demangled_name(), version),
+            None => self.demangled_name(),
+        }
+    }
+}
+
+// ELF file of any class and byte order.
+// All headers are widened to their ELF64 layout, 32-bit files are
+// narrowed back only when a table has to be read from the file.
+pub struct Elf<T> {
+    reader: T,
+    pub class: u8,
+    pub endian: Endian,
+    pub header: elf64::ELFHeader,
+    sections: Vec<Section>,
+    segments: Vec<elf64::ProgramHeader>,
+    // section headers or names load had to leave out, the rest comes from PT_DYNAMIC
+    pub warnings: Vec<Error>,
+    // hash table and dynamic symbols of lookup, read on first use
+    lookup_table: Option<(Option<HashTable>, Vec<Symbol>)>,
+}
+
+impl Elf<BufReader<File>> {
+    pub fn open<P>(path: P) -> Result<Elf<BufReader<File>>>
+        where P: AsRef<Path>
+    {
+        let file = match File::open(path.as_ref()) {
+            Ok(f) => BufReader::new(f),
+            Err(e) => return Err(Error::io(path.as_ref(), e)),
+        };
+        Elf::load(file)
+    }
+}
+
+impl<T> Elf<T>
+    where T: Read + Seek
+{
+    pub fn load(mut reader: T) -> Result<Elf<T>> {
+        // read e_ident, detect class and byte order
+        let mut e_ident: [u8; value::EI_NIDENT] = [0; value::EI_NIDENT];
+        error::read_at(&mut reader, 0, &mut e_ident, || String::from("e_ident"))?;
+        if e_ident[value::EI_MAG0] != value::ELFMAG0 || e_ident[value::EI_MAG1] != value::ELFMAG1 ||
+            e_ident[value::EI_MAG2] != value::ELFMAG2 || e_ident[value::EI_MAG3] != value::ELFMAG3 {
+            return Err(Error::BadMagic);
+        }
+        let class = e_ident[value::EI_CLASS];
+        let endian = Endian::from_ident(&e_ident)?;
+        reader.seek(SeekFrom::Start(0))?;
+
+        // load headers by class, relocatable files have no program header
+        // and stripped files may have no section header. With extended
+        // numbering e_shnum is 0 and the count is in section 0.
+        // Section headers are not needed to load, a dump or a packed file
+        // may have anything there, so failing to read them is only a warning.
+        let mut warnings: Vec<Error> = Vec::new();
+        let (header, section_table, name_index, segments) = match class {
+            value::ELFCLASS32 => {
+                let ehdr = elf32::ELFHeader::load(&mut reader)?;
+                let mut sections: Vec<elf64::SectionHeader> = Vec::new();
+                let mut name_index = 0;
+                if ehdr.e_shoff == 0 && ehdr.e_shnum != 0 {
+                    warnings.push(Error::invalid("e_shoff", format!("0 with e_shnum {}", ehdr.e_shnum)));
+                } else if ehdr.e_shoff != 0 {
+                    match elf32::SectionTable::load(&mut reader, &ehdr) {
+                        Ok(table) => {
+                            sections = table.data.iter().map(elf64::SectionHeader::from).collect();
+                            name_index = table.name;
+                        },
+                        Err(e) => warnings.push(e),
+                    }
+                }
+                let mut segments: Vec<elf64::ProgramHeader> = Vec::new();
+                if ehdr.e_phnum != 0 {
+                    let table = elf32::ProgramTable::load(&mut reader, &ehdr)?;
+                    segments = table.programs.iter().map(elf64::ProgramHeader::from).collect();
+                }
+                (elf64::ELFHeader::from(&ehdr), sections, name_index, segments)
+            },
+            value::ELFCLASS64 => {
+                let ehdr = elf64::ELFHeader::load(&mut reader)?;
+                let mut sections: Vec<elf64::SectionHeader> = Vec::new();
+                let mut name_index = 0;
+                if ehdr.e_shoff == 0 && ehdr.e_shnum != 0 {
+                    warnings.push(Error::invalid("e_shoff", format!("0 with e_shnum {}", ehdr.e_shnum)));
+                } else if ehdr.e_shoff != 0 {
+                    match elf64::SectionTable::load(&mut reader, &ehdr) {
+                        Ok(table) => {
+                            sections = table.data;
+                            name_index = table.name;
+                        },
+                        Err(e) => warnings.push(e),
+                    }
+                }
+                let mut segments: Vec<elf64::ProgramHeader> = Vec::new();
+                if ehdr.e_phnum != 0 {
+                    segments = elf64::ProgramTable::load(&mut reader, &ehdr)?.programs;
+                }
+                (ehdr, sections, name_index, segments)
+            },
+            n => return Err(Error::UnsupportedClass(n)),
+        };
+
+        let mut elf = Elf {
+            reader, class, endian, header,
+            sections: Vec::new(),
+            segments,
+            warnings,
+            lookup_table: None,
+        };
+
+        // resolve section names by e_shstrndx, sections stay unnamed if it is not a string table
+        let name_index = name_index as usize;
+        let names = match section_table.get(name_index) {
+            Some(shdr) if name_index != value::SHN_UNDEF => match elf.load_strings(shdr) {
+                Ok(names) => Some(names),
+                Err(e) => {
+                    elf.warnings.push(e);
+                    None
+                },
+            },
+            Some(_) => None,
+            None => {
+                if name_index != value::SHN_UNDEF {
+                    elf.warnings.push(Error::out_of_range("e_shstrndx", name_index as u64, section_table.len() as u64));
+                }
+                None
+            },
+        };
+        elf.sections = section_table.into_iter()
+            .enumerate()
+            .map(|(index, header)| {
+                let name = match &names {
+                    Some(names) => string_at(names, header.sh_name as usize),
+                    None => String::new(),
+                };
+                Section { index, name, header }
+            })
+            .collect();
+
+        Ok(elf)
+    }
+
+    pub fn sections(&self) -> &[Section] {
+        &self.sections
+    }
+
+    pub fn section_by_name(&self, name: &str) -> Option<&Section> {
+        self.sections.iter().find(|s| s.name == name)
+    }
+
+    pub fn segments(&self) -> &[elf64::ProgramHeader] {
+        &self.segments
+    }
+
+    // file location of virtual address as the loader maps it
+    pub fn vaddr_to_offset(&self, vaddr: u64) -> Option<Location> {
+        address::vaddr_to_offset(&self.segments, vaddr)
+    }
+
+    pub fn offset_to_vaddr(&self, offset: u64) -> Option<u64> {
+        address::offset_to_vaddr(&self.segments, offset)
+    }
+
+    pub fn segment_for_vaddr(&self, vaddr: u64) -> Option<&elf64::ProgramHeader> {
+        address::segment_for_vaddr(&self.segments, vaddr).map(|i| &self.segments[i])
+    }
+
+    pub fn section_for_vaddr(&self, vaddr: u64) -> Option<&Section> {
+        address::section_for_vaddr(self.sections.iter().map(|s| &s.header), vaddr).map(|i| &self.sections[i])
+    }
+
+    // indices of sections in each segment, as readelf -l maps them
+    pub fn section_mapping(&self) -> Vec<Vec<usize>> {
+        let headers: Vec<elf64::SectionHeader> = self.sections.iter().map(|s| s.header.clone()).collect();
+        segment::section_mapping(&self.segments, &headers)
+    }
+
+    // SHT_NOTE sections, PT_NOTE segments when there is none as in dumps
+    pub fn notes(&mut self) -> Result<Vec<NoteTable>> {
+        let mut places: Vec<(Option<String>, u64, u64, u64)> = self.sections.iter()
+            .filter(|s| s.header.sh_type == value::SHT_NOTE)
+            .map(|s| (Some(s.name.clone()), s.header.sh_offset, s.header.sh_size, s.header.sh_addralign))
+            .collect();
+        if places.is_empty() {
+            places = self.segments.iter()
+                .filter(|p| p.p_type == value::PT_NOTE)
+                .map(|p| (None, p.p_offset, p.p_filesz, p.p_align))
+                .collect();
+        }
+        let mut tables: Vec<NoteTable> = Vec::new();
+        for (section, offset, size, align) in places {
+            tables.push(NoteTable {
+                section, offset, align,
+                data: self.read_at(offset, size)?,
+                class: self.class,
+                endian: self.endian,
+            });
+        }
+        Ok(tables)
+    }
+
+    // descriptor of NT_GNU_BUILD_ID
+    pub fn build_id(&mut self) -> Result<Option<Vec<u8>>> {
+        for table in self.notes()? {
+            if let Some(id) = table.notes().find_map(|n| n.build_id()) {
+                return Ok(Some(id.to_vec()));
+            }
+        }
+        Ok(None)
+    }
+
+    // line number programs of .debug_line, empty without debug info
+    pub fn line_programs(&mut self) -> Result<Vec<LineProgram>> {
+        let debug_line = self.named_section_data(".debug_line")?;
+        let debug_line_str = self.named_section_data(".debug_line_str")?;
+        let debug_str = self.named_section_data(".debug_str")?;
+        let sections = LineSections {
+            debug_line: &debug_line,
+            debug_line_str: &debug_line_str,
+            debug_str: &debug_str,
+        };
+        let address_size = if self.class == value::ELFCLASS32 { 4 } else { 8 };
+        dwarf::parse_line_programs(&sections, self.endian, address_size)
+    }
+
+    // Source file and line of vaddr, as addr2line prints it. Addresses of
+    // relocatable files are section offsets, .rela.debug_line is not applied.
+    pub fn addr2line(&mut self, vaddr: u64) -> Result<Option<SourceLocation>> {
+        Ok(dwarf::find_location(&self.line_programs()?, vaddr))
+    }
+
+    // .eh_frame_hdr section, or PT_GNU_EH_FRAME segment without sections
+    pub fn eh_frame_hdr(&mut self) -> Result<Option<EhFrameHdr>> {
+        let place = match self.sections.iter().find(|s| s.name == ".eh_frame_hdr") {
+            Some(s) => Some((s.header.sh_offset, s.header.sh_size, s.header.sh_addr)),
+            None => self.segments.iter()
+                .find(|p| p.p_type == value::PT_GNU_EH_FRAME)
+                .map(|p| (p.p_offset, p.p_filesz, p.p_vaddr)),
+        };
+        let (offset, size, vaddr) = match place {
+            Some(place) => place,
+            None => return Ok(None),
+        };
+        let data = self.read_at(offset, size)?;
+        EhFrameHdr::parse(&data, vaddr, self.class, self.endian).map(Some)
+    }
+
+    // .eh_frame section, or where .eh_frame_hdr points without sections.
+    // Its size is only known from sections, else it runs up to its zero
+    // terminator or the end of the PT_LOAD holding it.
+    pub fn eh_frame(&mut self) -> Result<Option<EhFrame>> {
+        if let Some(section) = self.sections.iter().find(|s| s.name == ".eh_frame" && s.header.sh_type != value::SHT_NOBITS) {
+            let (offset, size, vaddr) = (section.header.sh_offset, section.header.sh_size, section.header.sh_addr);
+            let data = self.read_at(offset, size)?;
+            return EhFrame::parse(data, vaddr, self.class, self.endian).map(Some);
+        }
+        let vaddr = match self.eh_frame_hdr()? {
+            Some(hdr) => hdr.eh_frame_ptr,
+            None => return Ok(None),
+        };
+        let (offset, phdr) = match (self.vaddr_to_offset(vaddr), self.segment_for_vaddr(vaddr)) {
+            (Some(Location::File(offset)), Some(phdr)) => (offset, phdr),
+            _ => return Err(Error::invalid("eh_frame_ptr", format!("{:#x} is not in file", vaddr))),
+        };
+        // rest of the segment, p_offset + p_filesz of a crafted PT_LOAD may overflow
+        let size = match phdr.p_offset.checked_add(phdr.p_filesz).and_then(|end| end.checked_sub(offset)) {
+            Some(size) => size,
+            None => return Err(Error::invalid("PT_LOAD", format!("{:#x} + {:#x} does not hold offset {:#x}",
+                phdr.p_offset, phdr.p_filesz, offset))),
+        };
+        let data = self.read_at(offset, size)?;
+        EhFrame::parse(data, vaddr, self.class, self.endian).map(Some)
+    }
+
+    // .ARM.exidx section, or PT_ARM_EXIDX segment without sections. Entries
+    // out of line are read from .ARM.extab, else from the PT_LOAD holding it.
+    pub fn arm_exidx(&mut self) -> Result<Option<ArmExidx>> {
+        if self.header.e_machine != value::EM_ARM {
+            return Ok(None);
+        }
+        let place = match self.sections.iter().find(|s| s.header.sh_type == value::SHT_ARM_EXIDX) {
+            Some(s) => Some((s.header.sh_offset, s.header.sh_size, s.header.sh_addr)),
+            None => self.segments.iter()
+                .find(|p| p.p_type == value::PT_ARM_EXIDX)
+                .map(|p| (p.p_offset, p.p_filesz, p.p_vaddr)),
+        };
+        let (offset, size, vaddr) = match place {
+            Some(place) => place,
+            None => return Ok(None),
+        };
+        let data = self.read_at(offset, size)?;
+        let extab = match self.sections.iter().find(|s| s.name == ".ARM.extab" && s.header.sh_type != value::SHT_NOBITS) {
+            Some(s) => Some((s.header.sh_offset, s.header.sh_size, s.header.sh_addr)),
+            None => self.segment_for_vaddr(vaddr).map(|p| (p.p_offset, p.p_filesz, p.p_vaddr)),
+        };
+        let (extab, extab_vaddr) = match extab {
+            Some((offset, size, vaddr)) => (self.read_at(offset, size)?, vaddr),
+            None => (Vec::new(), 0),
+        };
+        ArmExidx::parse(&data, vaddr, &extab, extab_vaddr, self.endian).map(Some)
+    }
+
+    // SHT_GROUP sections with signature and members, as COMDAT groups of relocatable files
+    pub fn groups(&mut self) -> Result<Vec<Group>> {
+        let indices: Vec<usize> = self.sections.iter()
+            .filter(|s| s.header.sh_type == value::SHT_GROUP)
+            .map(|s| s.index)
+            .collect();
+        let mut groups: Vec<Group> = Vec::with_capacity(indices.len());
+        // groups of a file share one symbol table
+        let mut symbols: Option<(usize, Vec<Symbol>)> = None;
+        for index in indices {
+            let header = self.sections[index].header.clone();
+            let table = header.sh_link as usize;
+            if self.sections.get(table).map(|s| s.header.sh_type) != Some(value::SHT_SYMTAB) {
+                return Err(Error::invalid("sh_link of SHT_GROUP", format!("section {} is not SHT_SYMTAB", table)));
+            }
+            if symbols.as_ref().map(|(i, _)| *i) != Some(table) {
+                symbols = Some((table, self.load_table_symbols(table)?));
+            }
+            let table_symbols = &symbols.as_ref().unwrap().1;
+            let symbol = match table_symbols.get(header.sh_info as usize) {
+                Some(symbol) => symbol,
+                None => return Err(Error::out_of_range("sh_info of SHT_GROUP", header.sh_info as u64, table_symbols.len() as u64)),
+            };
+            // signature of a section symbol is the name of its section
+            let signature = match symbol.entry.symbol_type() {
+                SymbolType::Section => self.sections.get(symbol.shndx as usize).map(|s| s.name.clone()).unwrap_or_default(),
+                _ => symbol.name.clone(),
+            };
+            let data = self.section_data(index)?;
+            groups.push(Group::parse(index, signature, &data, self.endian)?);
+        }
+        Ok(groups)
+    }
+
+    // program interpreter path of PT_INTERP
+    pub fn interpreter(&mut self) -> Result<Option<String>> {
+        let (offset, size) = match self.segments.iter().find(|p| p.p_type == value::PT_INTERP) {
+            Some(phdr) => (phdr.p_offset, phdr.p_filesz),
+            None => return Ok(None),
+        };
+        let data = self.read_at(offset, size)?;
+        let end = data.iter().position(|c| *c == b'\0').unwrap_or(data.len());
+        Ok(Some(String::from_utf8_lossy(&data[..end]).into_owned()))
+    }
+
+    // symbols of .symtab
+    pub fn symbols(&mut self) -> Result<Vec<Symbol>> {
+        self.load_symbols(value::SHT_SYMTAB)
+    }
+
+    // symbols of .dynsym, or of DT_SYMTAB if there is no .dynsym section
+    pub fn dynamic_symbols(&mut self) -> Result<Vec<Symbol>> {
+        let mut symbols = if self.sections.iter().any(|s| s.header.sh_type == value::SHT_DYNSYM) {
+            self.load_symbols(value::SHT_DYNSYM)?
+        } else {
+            match self.dynamic()? {
+                Some(dynamic) => self.load_dynamic_symbols(&dynamic)?,
+                None => return Ok(Vec::new()),
+            }
+        };
+        if let Some(versions) = self.versions()? {
+            set_versions(&mut symbols, &versions);
+        }
+        Ok(symbols)
+    }
+
+    // relocations of every SHT_REL and SHT_RELA section, with symbols of sh_link
+    pub fn relocations(&mut self) -> Result<Vec<RelocationSection>> {
+        let tables: Vec<Section> = self.sections.iter()
+            .filter(|s| matches!(s.header.sh_type, value::SHT_REL | value::SHT_RELA | value::SHT_RELR |
+                value::SHT_ANDROID_REL | value::SHT_ANDROID_RELA | value::SHT_ANDROID_RELR))
+            .cloned()
+            .collect();
+        let mut linked: Vec<(u32, Vec<Symbol>)> = Vec::new();
+        let mut result: Vec<RelocationSection> = Vec::new();
+        for table in &tables {
+            let link = table.header.sh_link;
+            if !linked.iter().any(|(l, _)| *l == link) {
+                let symbols = match self.sections.get(link as usize).map(|s| s.header.sh_type) {
+                    Some(value::SHT_DYNSYM) => self.dynamic_symbols()?,
+                    Some(value::SHT_SYMTAB) => self.symbols()?,
+                    _ => Vec::new(),
+                };
+                linked.push((link, symbols));
+            }
+            let symbols = &linked.iter().find(|(l, _)| *l == link).unwrap().1;
+            let (rel, rela) = match table.header.sh_type {
+                value::SHT_REL => (self.load_rel_entries(&table.header)?, Vec::new()),
+                value::SHT_RELA => (Vec::new(), self.load_rela_entries(&table.header)?),
+                value::SHT_ANDROID_REL => {
+                    let data = self.section_data(table.index)?;
+                    (rela_to_rel(relocation::decode_aps2(&data, self.class)?), Vec::new())
+                },
+                value::SHT_ANDROID_RELA => {
+                    let data = self.section_data(table.index)?;
+                    (Vec::new(), relocation::decode_aps2(&data, self.class)?)
+                },
+                _ => {
+                    let data = self.section_data(table.index)?;
+                    (self.decode_relr(&data), Vec::new())
+                },
+            };
+            let mut section = RelocationSection::new();
+            section.name = table.name.clone();
+            section.offset = table.header.sh_offset;
+            section.relocations = self.join_relocations(&rel, &rela, symbols);
+            result.push(section);
+        }
+        Ok(result)
+    }
+
+    // relocations of PT_DYNAMIC tables, section headers are never used
+    pub fn dynamic_relocations(&mut self) -> Result<Vec<RelocationSection>> {
+        let dynamic = match self.dynamic()? {
+            Some(d) => d,
+            None => return Ok(Vec::new()),
+        };
+        let info = match self.dynamic_info()? {
+            Some(info) => info,
+            None => return Ok(Vec::new()),
+        };
+        let mut result: Vec<RelocationSection> = Vec::new();
+        let relr_tag = if dynamic.get(value::DT_RELR).is_some() { value::DT_RELR } else { value::DT_ANDROID_RELR };
+        let tables: [(&str, i64, &[elf64::RelEntry], &[elf64::RelaEntry]); 6] = [
+            ("DT_REL", value::DT_REL, &info.rel, &[]),
+            ("DT_RELA", value::DT_RELA, &[], &info.rela),
+            ("DT_ANDROID_REL", value::DT_ANDROID_REL, &info.android_rel, &[]),
+            ("DT_ANDROID_RELA", value::DT_ANDROID_RELA, &[], &info.android_rela),
+            (dynamic::tag_name(relr_tag), relr_tag, &info.relr, &[]),
+            ("DT_JMPREL", value::DT_JMPREL, &info.plt_rel, &info.plt_rela),
+        ];
+        for (name, tag, rel, rela) in tables.iter() {
+            if rel.is_empty() && rela.is_empty() {
+                continue;
+            }
+            let mut section = RelocationSection::new();
+            section.name = name.to_string();
+            section.offset = dynamic.get(*tag).and_then(|a| self.file_offset(a)).unwrap_or(0);
+            section.relocations = self.join_relocations(rel, rela, &info.symbols);
+            result.push(section);
+        }
+        Ok(result)
+    }
+
+    // symbol versions from .gnu.version sections, or from PT_DYNAMIC
+    pub fn versions(&mut self) -> Result<Option<Versions>> {
+        let versym = match self.sections.iter().find(|s| s.header.sh_type == value::SHT_GNU_VERSYM) {
+            Some(s) => s.header.clone(),
+            None => return match self.dynamic()? {
+                Some(dynamic) => self.dynamic_versions(&dynamic),
+                None => Ok(None),
+            },
+        };
+        let mut versions = Versions::new();
+        versions.versym = self.read_halfs(versym.sh_offset, versym.sh_size / 2)?;
+
+        // sh_info is the number of entries, sh_link the string table
+        for sh_type in &[value::SHT_GNU_VERDEF, value::SHT_GNU_VERNEED] {
+            let section = match self.sections.iter().find(|s| s.header.sh_type == *sh_type) {
+                Some(s) => s.header.clone(),
+                None => continue,
+            };
+            let strtab = match self.sections.get(section.sh_link as usize) {
+                Some(s) => s.header.clone(),
+                None => return Err(Error::out_of_range("sh_link of version section", section.sh_link as u64,
+                    self.sections.len() as u64)),
+            };
+            let strings = self.load_strings(&strtab)?;
+            if *sh_type == value::SHT_GNU_VERDEF {
+                versions.defs = self.load_verdef(section.sh_offset, section.sh_info as u64, &strings)?;
+            } else {
+                versions.needs = self.load_verneed(section.sh_offset, section.sh_info as u64, &strings)?;
+            }
+        }
+        Ok(Some(versions))
+    }
+
+    // hash table of dynamic symbols, .gnu.hash is preferred as the dynamic linker does
+    pub fn hash_table(&mut self) -> Result<Option<HashTable>> {
+        for sh_type in &[value::SHT_GNU_HASH, value::SHT_HASH] {
+            let index = match self.sections.iter().position(|s| s.header.sh_type == *sh_type) {
+                Some(n) => n,
+                None => continue,
+            };
+            let data = self.section_data(index)?;
+            return match *sh_type {
+                value::SHT_GNU_HASH => Ok(Some(HashTable::Gnu(GnuHash::parse(&data, self.endian, self.class)?))),
+                _ => Ok(Some(HashTable::Sysv(SysvHash::parse(&data, self.endian)?))),
+            };
+        }
+
+        // no section headers, size of table comes from its own header
+        let dynamic = match self.dynamic()? {
+            Some(d) => d,
+            None => return Ok(None),
+        };
+        if let Some(offset) = dynamic.get(value::DT_GNU_HASH).and_then(|a| self.file_offset(a)) {
+            let words = self.read_words(offset, 4)?;
+            let (nbuckets, symoffset, bloom_size) = (words[0] as u64, words[1] as u64, words[2] as u64);
+            let word_size: u64 = if self.class == value::ELFCLASS32 { 4 } else { 8 };
+            let count = self.dynamic_symbol_count(&dynamic)?;
+            let size = (16 + bloom_size * word_size + nbuckets * 4)
+                .saturating_add(count.saturating_sub(symoffset).saturating_mul(4));
+            let data = self.read_at(offset, size)?;
+            return Ok(Some(HashTable::Gnu(GnuHash::parse(&data, self.endian, self.class)?)));
+        }
+        if let Some(offset) = dynamic.get(value::DT_HASH).and_then(|a| self.file_offset(a)) {
+            let words = self.read_words(offset, 2)?;
+            let data = self.read_at(offset, (2 + words[0] as u64 + words[1] as u64) * 4)?;
+            return Ok(Some(HashTable::Sysv(SysvHash::parse(&data, self.endian)?)));
+        }
+        Ok(None)
+    }
+
+    // defined dynamic symbol by name, through hash table if there is one
+    pub fn lookup(&mut self, name: &str) -> Result<Option<Symbol>> {
+        self.lookup_version(name, None)
+    }
+
+    // as lookup, None asks for the default version
+    pub fn lookup_version(&mut self, name: &str, version: Option<&str>) -> Result<Option<Symbol>> {
+        let (table, symbols) = match self.lookup_table.take() {
+            Some(t) => t,
+            None => (self.hash_table()?, self.dynamic_symbols()?),
+        };
+        let index = match &table {
+            Some(table) => table.lookup_version(name, version, &symbols),
+            None => symbols.iter().position(|s| hash::is_match(s, name, version)),
+        };
+        let symbol = index.map(|i| symbols[i].clone());
+        self.lookup_table = Some((table, symbols));
+        Ok(symbol)
+    }
+
+    // content of section, decompressed when SHF_COMPRESSED or .zdebug
+    pub fn section_data(&mut self, index: usize) -> Result<Vec<u8>> {
+        let data = self.raw_section_data(index)?;
+        let section = &self.sections[index];
+        match Compression::detect(&section.name, &section.header, &data, self.class, self.endian)? {
+            Some(compression) => compression.decompress(&data),
+            None => Ok(data),
+        }
+    }
+
+    // content of section as stored in file, SHT_NOBITS has no data in file
+    pub fn raw_section_data(&mut self, index: usize) -> Result<Vec<u8>> {
+        let header = match self.sections.get(index) {
+            Some(s) => s.header.clone(),
+            None => return Err(Error::out_of_range("section", index as u64, self.sections.len() as u64)),
+        };
+        if header.sh_type == value::SHT_NOBITS {
+            return Ok(Vec::new());
+        }
+        self.read_at(header.sh_offset, header.sh_size)
+    }
+
+    // compression of section with its stored and uncompressed sizes, None if stored as is
+    pub fn section_compression(&mut self, index: usize) -> Result<Option<Compression>> {
+        let section = match self.sections.get(index) {
+            Some(s) => s.clone(),
+            None => return Err(Error::out_of_range("section", index as u64, self.sections.len() as u64)),
+        };
+        if section.header.sh_type == value::SHT_NOBITS {
+            return Ok(None);
+        }
+        // the largest header, that of ELF64
+        let data = self.read_at(section.header.sh_offset, section.header.sh_size.min(24))?;
+        Compression::detect(&section.name, &section.header, &data, self.class, self.endian)
+    }
+
+    // dynamic section from PT_DYNAMIC, or SHT_DYNAMIC if there is no program header
+    pub fn dynamic(&mut self) -> Result<Option<Dynamic>> {
+        let entry_size: u64 = if self.class == value::ELFCLASS32 { 0x8 } else { 0x10 };
+        let section = match self.segments.iter().find(|p| p.p_type == value::PT_DYNAMIC) {
+            Some(phdr) => elf64::SectionHeader {
+                sh_type: value::SHT_DYNAMIC,
+                sh_addr: phdr.p_vaddr,
+                sh_offset: phdr.p_offset,
+                sh_size: phdr.p_filesz - phdr.p_filesz % entry_size,
+                sh_entsize: entry_size,
+                ..Default::default()
+            },
+            None => match self.sections.iter().find(|s| s.header.sh_type == value::SHT_DYNAMIC) {
+                Some(s) => s.header.clone(),
+                None => return Ok(None),
+            },
+        };
+
+        let entries: Vec<elf64::DynamicEntry> = match self.class {
+            value::ELFCLASS32 => {
+                let ehdr = elf32::ELFHeader::from(&self.header);
+                let table = elf32::DynamicTable::load(&mut self.reader, &ehdr, &elf32::SectionHeader::from(&section))?;
+                table.data.iter().map(elf64::DynamicEntry::from).collect()
+            },
+            _ => elf64::DynamicTable::load(&mut self.reader, &self.header, &section)?.data,
+        };
+        let mut dynamic = Dynamic { entries, strtab: Vec::new() };
+
+        // string-valued tags refer to DT_STRTAB
+        if let (Some(addr), Some(size)) = (dynamic.get(value::DT_STRTAB), dynamic.get(value::DT_STRSZ)) {
+            if let Some(offset) = self.file_offset(addr) {
+                dynamic.strtab = self.read_at(offset, size)?;
+            }
+        }
+
+        Ok(Some(dynamic))
+    }
+
+    // rebuild dynamic tables from PT_DYNAMIC, section headers are never used
+    pub fn dynamic_info(&mut self) -> Result<Option<DynamicInfo>> {
+        let dynamic = match self.dynamic()? {
+            Some(d) => d,
+            None => return Ok(None),
+        };
+        let mut info = DynamicInfo::new();
+        info.symbols = self.load_dynamic_symbols(&dynamic)?;
+        if let Some(versions) = self.dynamic_versions(&dynamic)? {
+            set_versions(&mut info.symbols, &versions);
+        }
+
+        // relocation tables
+        let (rel_size, rela_size): (u64, u64) = match self.class {
+            value::ELFCLASS32 => (0x8, 0xC),
+            _ => (0x10, 0x18),
+        };
+        if let Some(shdr) = self.dynamic_table(&dynamic, value::SHT_REL,
+            value::DT_REL, value::DT_RELSZ, value::DT_RELENT, rel_size)? {
+            info.rel = self.load_rel_entries(&shdr)?;
+        }
+        if let Some(shdr) = self.dynamic_table(&dynamic, value::SHT_RELA,
+            value::DT_RELA, value::DT_RELASZ, value::DT_RELAENT, rela_size)? {
+            info.rela = self.load_rela_entries(&shdr)?;
+        }
+        // DT_PLTREL tells whether DT_JMPREL holds REL or RELA entries
+        match dynamic.get(value::DT_PLTREL).map(|v| v as i64) {
+            Some(value::DT_REL) => {
+                if let Some(shdr) = self.dynamic_table(&dynamic, value::SHT_REL,
+                    value::DT_JMPREL, value::DT_PLTRELSZ, value::DT_RELENT, rel_size)? {
+                    info.plt_rel = self.load_rel_entries(&shdr)?;
+                }
+            },
+            Some(value::DT_RELA) => {
+                if let Some(shdr) = self.dynamic_table(&dynamic, value::SHT_RELA,
+                    value::DT_JMPREL, value::DT_PLTRELSZ, value::DT_RELAENT, rela_size)? {
+                    info.plt_rela = self.load_rela_entries(&shdr)?;
+                }
+            },
+            _ => { },
+        };
+
+        // packed tables of Android and RELR
+        if let Some(data) = self.dynamic_bytes(&dynamic, value::DT_ANDROID_REL, value::DT_ANDROID_RELSZ)? {
+            info.android_rel = rela_to_rel(relocation::decode_aps2(&data, self.class)?);
+        }
+        if let Some(data) = self.dynamic_bytes(&dynamic, value::DT_ANDROID_RELA, value::DT_ANDROID_RELASZ)? {
+            info.android_rela = relocation::decode_aps2(&data, self.class)?;
+        }
+        for (addr_tag, size_tag) in &[(value::DT_RELR, value::DT_RELRSZ),
+            (value::DT_ANDROID_RELR, value::DT_ANDROID_RELRSZ)] {
+            if let Some(data) = self.dynamic_bytes(&dynamic, *addr_tag, *size_tag)? {
+                let entries = self.decode_relr(&data);
+                info.relr.extend(entries);
+            }
+        }
+
+        // init and fini functions
+        info.init = dynamic.get(value::DT_INIT);
+        info.fini = dynamic.get(value::DT_FINI);
+        info.init_array = self.load_dynamic_array(&dynamic, value::DT_INIT_ARRAY, value::DT_INIT_ARRAYSZ)?;
+        info.fini_array = self.load_dynamic_array(&dynamic, value::DT_FINI_ARRAY, value::DT_FINI_ARRAYSZ)?;
+        info.preinit_array = self.load_dynamic_array(&dynamic, value::DT_PREINIT_ARRAY,
+            value::DT_PREINIT_ARRAYSZ)?;
+
+        Ok(Some(info))
+    }
+
+    // number of DT_SYMTAB entries, the dynamic table does not record it
+    pub fn dynamic_symbol_count(&mut self, dynamic: &Dynamic) -> Result<u64> {
+        // DT_HASH: nchain equals the number of symbols
+        if let Some(offset) = dynamic.get(value::DT_HASH).and_then(|a| self.file_offset(a)) {
+            let words = self.read_words(offset, 2)?;
+            return Ok(words[1] as u64);
+        }
+        // DT_GNU_HASH: follow the chain of the highest bucket to its end
+        if let Some(offset) = dynamic.get(value::DT_GNU_HASH).and_then(|a| self.file_offset(a)) {
+            let words = self.read_words(offset, 4)?;
+            let (nbuckets, symoffset, bloom_size) = (words[0] as u64, words[1] as u64, words[2] as u64);
+            let word_size: u64 = if self.class == value::ELFCLASS32 { 4 } else { 8 };
+            let buckets_offset = offset + 16 + bloom_size * word_size;
+            let buckets = self.read_words(buckets_offset, nbuckets)?;
+            let last = match buckets.iter().max() {
+                Some(n) if (*n as u64) >= symoffset => *n as u64,
+                _ => return Ok(symoffset),
+            };
+            // bit 0 of chain value marks the last symbol of a chain
+            let chains_offset = buckets_offset + nbuckets * 4;
+            let mut index = last;
+            loop {
+                let chain = self.read_words(chains_offset + (index - symoffset) * 4, 1)?;
+                if (chain[0] & 1) != 0 {
+                    return Ok(index + 1);
+                }
+                index += 1;
+            }
+        }
+        // no hash table, assume DT_STRTAB follows DT_SYMTAB as linkers lay them out
+        let syment = dynamic.get(value::DT_SYMENT).unwrap_or(0);
+        match (dynamic.get(value::DT_SYMTAB), dynamic.get(value::DT_STRTAB)) {
+            (Some(symtab), Some(strtab)) if strtab > symtab && syment != 0 => Ok((strtab - symtab) / syment),
+            _ => Err(Error::NotFound(String::from("number of dynamic symbols"))),
+        }
+    }
+
+    // DT_VERSYM has one entry per DT_SYMTAB symbol
+    fn dynamic_versions(&mut self, dynamic: &Dynamic) -> Result<Option<Versions>> {
+        let offset = match dynamic.get(value::DT_VERSYM).and_then(|a| self.file_offset(a)) {
+            Some(n) => n,
+            None => return Ok(None),
+        };
+        let mut versions = Versions::new();
+        let count = self.dynamic_symbol_count(dynamic)?;
+        versions.versym = self.read_halfs(offset, count)?;
+        if let Some(offset) = dynamic.get(value::DT_VERDEF).and_then(|a| self.file_offset(a)) {
+            let count = dynamic.get(value::DT_VERDEFNUM).unwrap_or(0);
+            versions.defs = self.load_verdef(offset, count, &dynamic.strtab)?;
+        }
+        if let Some(offset) = dynamic.get(value::DT_VERNEED).and_then(|a| self.file_offset(a)) {
+            let count = dynamic.get(value::DT_VERNEEDNUM).unwrap_or(0);
+            versions.needs = self.load_verneed(offset, count, &dynamic.strtab)?;
+        }
+        Ok(Some(versions))
+    }
+
+    // verdef entries are chained by vd_next, their names by vda_next
+    fn load_verdef(&mut self, offset: u64, count: u64, strings: &[u8]) -> Result<Vec<VersionDef>> {
+        let mut defs: Vec<VersionDef> = Vec::new();
+        let mut pos = offset;
+        for _ in 0..count {
+            let data = self.read_at(pos, VerdefEntry::SIZE as u64)?;
+            let entry = VerdefEntry::parse(&data, self.endian);
+            let mut def = VersionDef {
+                index: entry.vd_ndx,
+                flags: entry.vd_flags,
+                hash: entry.vd_hash,
+                names: Vec::new(),
+            };
+            let mut aux = pos + entry.vd_aux as u64;
+            for _ in 0..entry.vd_cnt {
+                let data = self.read_at(aux, VerdauxEntry::SIZE as u64)?;
+                let entry = VerdauxEntry::parse(&data, self.endian);
+                def.names.push(string_at(strings, entry.vda_name as usize));
+                if entry.vda_next == 0 {
+                    break;
+                }
+                aux += entry.vda_next as u64;
+            }
+            defs.push(def);
+            if entry.vd_next == 0 {
+                break;
+            }
+            pos += entry.vd_next as u64;
+        }
+        Ok(defs)
+    }
+
+    // verneed entries are chained by vn_next, their versions by vna_next
+    fn load_verneed(&mut self, offset: u64, count: u64, strings: &[u8]) -> Result<Vec<VersionNeed>> {
+        let mut needs: Vec<VersionNeed> = Vec::new();
+        let mut pos = offset;
+        for _ in 0..count {
+            let data = self.read_at(pos, VerneedEntry::SIZE as u64)?;
+            let entry = VerneedEntry::parse(&data, self.endian);
+            let mut need = VersionNeed {
+                file: string_at(strings, entry.vn_file as usize),
+                versions: Vec::new(),
+            };
+            let mut aux = pos + entry.vn_aux as u64;
+            for _ in 0..entry.vn_cnt {
+                let data = self.read_at(aux, VernauxEntry::SIZE as u64)?;
+                let entry = VernauxEntry::parse(&data, self.endian);
+                need.versions.push(VersionRequirement {
+                    index: entry.vna_other,
+                    flags: entry.vna_flags,
+                    hash: entry.vna_hash,
+                    name: string_at(strings, entry.vna_name as usize),
+                });
+                if entry.vna_next == 0 {
+                    break;
+                }
+                aux += entry.vna_next as u64;
+            }
+            needs.push(need);
+            if entry.vn_next == 0 {
+                break;
+            }
+            pos += entry.vn_next as u64;
+        }
+        Ok(needs)
+    }
+
+    fn load_dynamic_symbols(&mut self, dynamic: &Dynamic) -> Result<Vec<Symbol>> {
+        let syment = match self.class {
+            value::ELFCLASS32 => 0x10,
+            _ => 0x18,
+        };
+        let count = match dynamic.get(value::DT_SYMTAB) {
+            Some(_) => self.dynamic_symbol_count(dynamic)?,
+            None => return Ok(Vec::new()),
+        };
+        let syment = dynamic.get(value::DT_SYMENT).unwrap_or(syment);
+        let addr = dynamic.get(value::DT_SYMTAB).unwrap_or(0);
+        let offset = match self.file_offset(addr) {
+            Some(n) => n,
+            None => return Err(Error::invalid("DT_SYMTAB", format!("{:#x} is not in file", addr))),
+        };
+        let section = elf64::SectionHeader {
+            sh_type: value::SHT_DYNSYM,
+            sh_addr: addr,
+            sh_offset: offset,
+            sh_size: count.saturating_mul(syment),
+            sh_entsize: syment,
+            ..Default::default()
+        };
+        let entries = self.load_symbol_entries(&section)?;
+
+        Ok(entries.into_iter()
+            .map(|entry| Symbol {
+                name: dynamic.string(entry.st_name as u64).unwrap_or_default(),
+                shndx: entry.st_shndx as u32,
+                entry,
+                version: None,
+            })
+            .collect())
+    }
+
+    // section header of table given by address, size and entry size tags
+    fn dynamic_table(&self, dynamic: &Dynamic, sh_type: u32, addr_tag: i64, size_tag: i64,
+        entsize_tag: i64, entsize: u64) -> Result<Option<elf64::SectionHeader>>
+    {
+        let (addr, size) = match (dynamic.get(addr_tag), dynamic.get(size_tag)) {
+            (Some(addr), Some(size)) if size != 0 => (addr, size),
+            _ => return Ok(None),
+        };
+        let offset = match self.file_offset(addr) {
+            Some(n) => n,
+            None => return Err(Error::invalid(dynamic::tag_name(addr_tag), format!("{:#x} is not in file", addr))),
+        };
+        Ok(Some(elf64::SectionHeader {
+            sh_type,
+            sh_addr: addr,
+            sh_offset: offset,
+            sh_size: size,
+            sh_entsize: dynamic.get(entsize_tag).unwrap_or(entsize),
+            ..Default::default()
+        }))
+    }
+
+    // array of pointers given by address and size tags
+    fn load_dynamic_array(&mut self, dynamic: &Dynamic, addr_tag: i64, size_tag: i64) -> Result<Vec<u64>> {
+        let (addr, size) = match (dynamic.get(addr_tag), dynamic.get(size_tag)) {
+            (Some(addr), Some(size)) => (addr, size),
+            _ => return Ok(Vec::new()),
+        };
+        let offset = match self.file_offset(addr) {
+            Some(n) => n,
+            None => return Ok(Vec::new()),
+        };
+        let data = self.read_at(offset, size)?;
+        let mut cursor = &data[..];
+        let mut pointers: Vec<u64> = Vec::new();
+        if self.class == value::ELFCLASS32 {
+            while cursor.len() >= 4 {
+                pointers.push(self.endian.read_u32(&mut cursor) as u64);
+            }
+        }
+        else {
+            while cursor.len() >= 8 {
+                pointers.push(self.endian.read_u64(&mut cursor));
+            }
+        }
+        Ok(pointers)
+    }
+
+    // 32-bit words at virtual address, None if address is not in file
+    pub(crate) fn words_at_vaddr(&mut self, vaddr: u64, count: u64) -> Result<Option<Vec<u32>>> {
+        match self.file_offset(vaddr) {
+            Some(offset) => Ok(Some(self.read_words(offset, count)?)),
+            None => Ok(None),
+        }
+    }
+
+    // file offset of virtual address, by PT_LOAD or by section address
+    fn file_offset(&self, vaddr: u64) -> Option<u64> {
+        if let Some(location) = address::vaddr_to_offset(&self.segments, vaddr) {
+            return location.offset();
+        }
+        for section in &self.sections {
+            let shdr = &section.header;
+            if shdr.sh_addr != 0 && shdr.sh_type != value::SHT_NOBITS &&
+                vaddr >= shdr.sh_addr && vaddr - shdr.sh_addr < shdr.sh_size {
+                return shdr.sh_offset.checked_add(vaddr - shdr.sh_addr);
+            }
+        }
+        None
+    }
+
+    // content of table located by address and size tags
+    fn dynamic_bytes(&mut self, dynamic: &Dynamic, addr_tag: i64, size_tag: i64) -> Result<Option<Vec<u8>>> {
+        let (addr, size) = match (dynamic.get(addr_tag), dynamic.get(size_tag)) {
+            (Some(a), Some(s)) => (a, s),
+            _ => return Ok(None),
+        };
+        match self.file_offset(addr) {
+            Some(offset) => Ok(Some(self.read_at(offset, size)?)),
+            None => Err(Error::invalid(dynamic::tag_name(addr_tag), format!("{:#x} is not in file", addr))),
+        }
+    }
+
+    fn decode_relr(&self, data: &[u8]) -> Vec<elf64::RelEntry> {
+        let rel_type = relocation::relative_type(self.header.e_machine).unwrap_or(0);
+        relocation::decode_relr(data, self.endian, self.class, rel_type)
+    }
+
+    fn join_relocations(&self, rel: &[elf64::RelEntry], rela: &[elf64::RelaEntry], symbols: &[Symbol])
+        -> Vec<Relocation>
+    {
+        rel.iter()
+            .map(|r| self.relocation(r.r_offset, r.r_info, None, symbols))
+            .chain(rela.iter().map(|r| self.relocation(r.r_offset, r.r_info, Some(r.r_addend), symbols)))
+            .collect()
+    }
+
+    fn relocation(&self, offset: u64, info: u64, addend: Option<i64>, symbols: &[Symbol]) -> Relocation {
+        let machine = self.header.e_machine;
+        let (symbol_index, rel_type) = relocation::split_info(machine, self.class, self.endian, info);
+        // r_info of ELF32 was widened, report it as stored in file
+        let info = match self.class {
+            value::ELFCLASS32 => value::relocation_info(symbol_index, rel_type) as u64,
+            _ => info,
+        };
+        Relocation {
+            offset, info, symbol_index, rel_type, addend,
+            symbol: match symbol_index {
+                0 => None,
+                n => symbols.get(n as usize).cloned(),
+            },
+            machine,
+        }
+    }
+
+    fn load_symbols(&mut self, sh_type: u32) -> Result<Vec<Symbol>> {
+        match self.sections.iter().position(|s| s.header.sh_type == sh_type) {
+            Some(index) => self.load_table_symbols(index),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    // symbols of the table at section index
+    fn load_table_symbols(&mut self, index: usize) -> Result<Vec<Symbol>> {
+        let section = self.sections[index].header.clone();
+        // symbol names live in the string table of sh_link
+        let strtab = match self.sections.get(section.sh_link as usize) {
+            Some(s) => s.header.clone(),
+            None => return Err(Error::out_of_range("sh_link of symbol table", section.sh_link as u64,
+                self.sections.len() as u64)),
+        };
+        let names = self.load_strings(&strtab)?;
+
+        let entries = self.load_symbol_entries(&section)?;
+
+        // section indices past SHN_LORESERVE are in the SHT_SYMTAB_SHNDX linked to table
+        let shndx = match self.sections.iter().find(|s| s.header.sh_type == value::SHT_SYMTAB_SHNDX && s.header.sh_link as usize == index) {
+            Some(s) => {
+                let (offset, size) = (s.header.sh_offset, s.header.sh_size);
+                self.read_words(offset, size / 4)?
+            },
+            None => Vec::new(),
+        };
+
+        Ok(entries.into_iter()
+            .enumerate()
+            .map(|(i, entry)| Symbol {
+                name: string_at(&names, entry.st_name as usize),
+                shndx: match entry.st_shndx as usize {
+                    value::SHN_XINDEX => shndx.get(i).copied().unwrap_or(value::SHN_XINDEX as u32),
+                    n => n as u32,
+                },
+                entry,
+                version: None,
+            })
+            .collect())
+    }
+
+    fn load_symbol_entries(&mut self, section: &elf64::SectionHeader) -> Result<Vec<elf64::SymbolEntry>> {
+        match self.class {
+            value::ELFCLASS32 => {
+                let ehdr = elf32::ELFHeader::from(&self.header);
+                let table = elf32::SymbolTable::load(&mut self.reader, &ehdr, &elf32::SectionHeader::from(section))?;
+                Ok(table.data.iter().map(elf64::SymbolEntry::from).collect())
+            },
+            _ => Ok(elf64::SymbolTable::load(&mut self.reader, &self.header, section)?.data),
+        }
+    }
+
+    fn load_rel_entries(&mut self, section: &elf64::SectionHeader) -> Result<Vec<elf64::RelEntry>> {
+        match self.class {
+            value::ELFCLASS32 => {
+                let ehdr = elf32::ELFHeader::from(&self.header);
+                let table = elf32::RelTable::load(&mut self.reader, &ehdr, &elf32::SectionHeader::from(section))?;
+                Ok(table.data.iter().map(elf64::RelEntry::from).collect())
+            },
+            _ => Ok(elf64::RelTable::load(&mut self.reader, &self.header, section)?.data),
+        }
+    }
+
+    fn load_rela_entries(&mut self, section: &elf64::SectionHeader) -> Result<Vec<elf64::RelaEntry>> {
+        match self.class {
+            value::ELFCLASS32 => {
+                let ehdr = elf32::ELFHeader::from(&self.header);
+                let table = elf32::RelaTable::load(&mut self.reader, &ehdr, &elf32::SectionHeader::from(section))?;
+                Ok(table.data.iter().map(elf64::RelaEntry::from).collect())
+            },
+            _ => Ok(elf64::RelaTable::load(&mut self.reader, &self.header, section)?.data),
+        }
+    }
+
+    // 32-bit words in file byte order, hash tables use them in both classes
+    fn read_words(&mut self, offset: u64, count: u64) -> Result<Vec<u32>> {
+        let data = self.read_at(offset, count.saturating_mul(4))?;
+        let mut cursor = &data[..];
+        let mut words: Vec<u32> = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            words.push(self.endian.read_u32(&mut cursor));
+        }
+        Ok(words)
+    }
+
+    // 16-bit words in file byte order, as .gnu.version entries
+    fn read_halfs(&mut self, offset: u64, count: u64) -> Result<Vec<u16>> {
+        let data = self.read_at(offset, count.saturating_mul(2))?;
+        let mut cursor = &data[..];
+        let mut halfs: Vec<u16> = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            halfs.push(self.endian.read_u16(&mut cursor));
+        }
+        Ok(halfs)
+    }
+
+    // content of the section named name or its .zdebug twin, empty if there is none
+    fn named_section_data(&mut self, name: &str) -> Result<Vec<u8>> {
+        let legacy = format!(".z{}", name.trim_start_matches('.'));
+        match self.sections.iter().position(|s| s.name == name).or_else(|| self.sections.iter().position(|s| s.name == legacy)) {
+            Some(index) => self.section_data(index),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    fn load_strings(&mut self, section: &elf64::SectionHeader) -> Result<Vec<u8>> {
+        if section.sh_type != value::SHT_STRTAB {
+            return Err(Error::invalid("sh_type", format!("{:#x} is not SHT_STRTAB", section.sh_type)));
+        }
+        self.read_at(section.sh_offset, section.sh_size)
+    }
+
+    fn read_at(&mut self, offset: u64, size: u64) -> Result<Vec<u8>> {
+        error::read_vec_at(&mut self.reader, offset, size, || String::from("file data"))
+    }
+}
+
+// APS2 of DT_ANDROID_REL carries no addends
+fn rela_to_rel(entries: Vec<elf64::RelaEntry>) -> Vec<elf64::RelEntry> {
+    entries.into_iter()
+        .map(|r| elf64::RelEntry { r_offset: r.r_offset, r_info: r.r_info })
+        .collect()
+}
+
+fn set_versions(symbols: &mut [Symbol], versions: &Versions) {
+    for (index, symbol) in symbols.iter_mut().enumerate() {
+        symbol.version = versions.symbol_version(index);
+    }
+}
+
+// null-terminated string at pos, empty if out of range
+fn string_at(table: &[u8], pos: usize) -> String {
+    if pos >= table.len() {
+        return String::new();
+    }
+    let end = table[pos..].iter()
+        .position(|c| *c == b'\0')
+        .map_or(table.len(), |n| pos + n);
+    String::from_utf8_lossy(&table[pos..end]).into_owned()
+}
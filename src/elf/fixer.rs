@@ -0,0 +1,307 @@
+use std::{convert::TryFrom, io::Cursor};
+use crate::{
+    elf::{dynamic::Dynamic, elf32, elf64, file::Elf, value, writer},
+    error::{Error, Result},
+};
+
+
+const PAGE_SIZE: u64 = 0x1000;
+
+// pointer-valued tags, glibc relocates them in memory while bionic keeps them as is
+const POINTER_TAGS: [i64; 20] = [
+    value::DT_PLTGOT, value::DT_HASH, value::DT_STRTAB, value::DT_SYMTAB,
+    value::DT_RELA, value::DT_INIT, value::DT_FINI, value::DT_REL,
+    value::DT_JMPREL, value::DT_INIT_ARRAY, value::DT_FINI_ARRAY, value::DT_PREINIT_ARRAY,
+    value::DT_GNU_HASH, value::DT_VERSYM, value::DT_VERDEF, value::DT_VERNEED,
+    value::DT_ANDROID_REL, value::DT_ANDROID_RELA, value::DT_RELR, value::DT_ANDROID_RELR,
+];
+
+// section to rebuild, sh_link is resolved by name after sorting
+struct FixSection {
+    name: &'static str,
+    header: elf64::SectionHeader,
+    link: Option<&'static str>,
+    info: Option<&'static str>,
+}
+
+// Rebuild a loadable ELF from a library image dumped from memory.
+// base is the address the first byte of image was mapped at, e.g. the
+// begin of first page of the library in /proc/<pid>/maps.
+pub fn fix_dump(image: &[u8], base: u64) -> Result<Vec<u8>> {
+    let mut out = image.to_vec();
+
+    // section headers are not mapped, whatever e_shoff points at is garbage
+    let mut header = load_header(&out)?;
+    header.e_shoff = 0;
+    header.e_shnum = 0;
+    header.e_shstrndx = 0;
+    store_header(&mut out, &header);
+
+    // file offsets follow memory layout now, bss is in image as well
+    let elf = Elf::load(Cursor::new(&out[..]))?;
+    let (class, endian) = (elf.class, elf.endian);
+    let mut segments = elf.segments().to_vec();
+    let first = match segments.iter().filter(|p| p.p_type == value::PT_LOAD).map(|p| p.p_vaddr).min() {
+        Some(vaddr) => vaddr & !(PAGE_SIZE - 1),
+        None => return Err(Error::NotFound(String::from("PT_LOAD segment in image"))),
+    };
+    let bias = base.wrapping_sub(first);
+    let size = out.len() as u64;
+    for phdr in segments.iter_mut() {
+        if phdr.p_vaddr < first {
+            continue;
+        }
+        phdr.p_offset = phdr.p_vaddr - first;
+        if phdr.p_type == value::PT_LOAD {
+            phdr.p_filesz = phdr.p_memsz.min(size.saturating_sub(phdr.p_offset));
+        }
+    }
+    let mut phdrs: Vec<u8> = Vec::new();
+    for phdr in &segments {
+        writer::write_program(&mut phdrs, class, endian, phdr);
+    }
+    store_at(&mut out, header.e_phoff, &phdrs)?;
+
+    // undo the load bias of pointers in dynamic section
+    let mut elf = Elf::load(Cursor::new(&out[..]))?;
+    let dynamic = match elf.dynamic()? {
+        Some(d) => d,
+        None => return Err(Error::NotFound(String::from("dynamic section in image"))),
+    };
+    let dynamic_phdr = match segments.iter().find(|p| p.p_type == value::PT_DYNAMIC) {
+        Some(phdr) => phdr.clone(),
+        None => return Err(Error::NotFound(String::from("PT_DYNAMIC segment in image"))),
+    };
+    let mut entries: Vec<u8> = Vec::new();
+    for entry in &dynamic.entries {
+        let mut entry = entry.clone();
+        if bias != 0 && POINTER_TAGS.contains(&entry.d_tag) && entry.d_val >= bias {
+            entry.d_val -= bias;
+        }
+        writer::write_dynamic(&mut entries, class, endian, &entry);
+    }
+    store_at(&mut out, dynamic_phdr.p_offset, &entries)?;
+
+    // rebuild sections from the fixed dynamic section
+    let mut elf = Elf::load(Cursor::new(&out[..]))?;
+    let dynamic = match elf.dynamic()? {
+        Some(d) => d,
+        None => return Err(Error::NotFound(String::from("dynamic section in image"))),
+    };
+    let mut sections = dynamic_sections(&mut elf, &dynamic, &dynamic_phdr, first)?;
+    sections.retain(|s| s.header.sh_offset.checked_add(s.header.sh_size).is_some_and(|end| end <= size));
+    sections.sort_by_key(|s| s.header.sh_addr);
+
+    // names and links, index 0 is SHT_NULL and .shstrtab is the last one
+    let mut shstrtab: Vec<u8> = vec![0];
+    let mut headers: Vec<elf64::SectionHeader> = vec![elf64::SectionHeader::default()];
+    for section in &sections {
+        let mut shdr = section.header.clone();
+        shdr.sh_name = shstrtab.len() as u32;
+        shstrtab.extend_from_slice(section.name.as_bytes());
+        shstrtab.push(0);
+        let index_of = |name: Option<&str>| name
+            .and_then(|n| sections.iter().position(|s| s.name == n))
+            .map_or(0, |i| i as u32 + 1);
+        shdr.sh_link = index_of(section.link);
+        if section.info.is_some() {
+            shdr.sh_info = index_of(section.info);
+        }
+        // only the null symbol is known to be local in dynsym
+        if shdr.sh_type == value::SHT_DYNSYM {
+            shdr.sh_info = 1;
+        }
+        headers.push(shdr);
+    }
+    let name = shstrtab.len() as u32;
+    shstrtab.extend_from_slice(b".shstrtab\0");
+    headers.push(elf64::SectionHeader {
+        sh_name: name,
+        sh_type: value::SHT_STRTAB,
+        sh_offset: out.len() as u64,
+        sh_size: shstrtab.len() as u64,
+        sh_addralign: 1,
+        ..Default::default()
+    });
+    out.extend_from_slice(&shstrtab);
+
+    // section header table at the end of file
+    let align: usize = if class == value::ELFCLASS32 { 4 } else { 8 };
+    out.resize(out.len().div_ceil(align) * align, 0);
+    header.e_shoff = out.len() as u64;
+    header.e_shentsize = if class == value::ELFCLASS32 { 0x28 } else { 0x40 };
+    header.e_shnum = headers.len() as u16;
+    header.e_shstrndx = headers.len() as u16 - 1;
+    for shdr in &headers {
+        writer::write_section(&mut out, class, endian, shdr);
+    }
+    store_header(&mut out, &header);
+
+    Ok(out)
+}
+
+// sections described by dynamic section, addresses are relative to vaddr 0
+fn dynamic_sections<T>(elf: &mut Elf<T>, dynamic: &Dynamic, dynamic_phdr: &elf64::ProgramHeader, first: u64)
+    -> Result<Vec<FixSection>>
+    where T: std::io::Read + std::io::Seek
+{
+    let is_32 = elf.class == value::ELFCLASS32;
+    let pointer: u64 = if is_32 { 4 } else { 8 };
+    let alloc = value::SHF_ALLOC as u64;
+    let write = value::SHF_WRITE as u64;
+    let mut sections: Vec<FixSection> = Vec::new();
+    let mut add = |name: &'static str, sh_type: u32, sh_flags: u64, addr: u64, size: u64, sh_entsize: u64,
+        link: Option<&'static str>, info: Option<&'static str>| {
+        sections.push(FixSection {
+            name,
+            header: elf64::SectionHeader {
+                sh_type, sh_flags,
+                sh_addr: addr,
+                sh_offset: addr.wrapping_sub(first),
+                sh_size: size,
+                sh_addralign: if sh_entsize != 0 { sh_entsize.min(pointer) } else { pointer },
+                sh_entsize,
+                ..Default::default()
+            },
+            link, info,
+        });
+    };
+
+    // symbols and strings
+    let syment = dynamic.get(value::DT_SYMENT).unwrap_or(if is_32 { 0x10 } else { 0x18 });
+    let count = elf.dynamic_symbol_count(dynamic)?;
+    if let Some(addr) = dynamic.get(value::DT_SYMTAB) {
+        add(".dynsym", value::SHT_DYNSYM, alloc, addr, count.saturating_mul(syment), syment, Some(".dynstr"), None);
+    }
+    if let (Some(addr), Some(size)) = (dynamic.get(value::DT_STRTAB), dynamic.get(value::DT_STRSZ)) {
+        add(".dynstr", value::SHT_STRTAB, alloc, addr, size, 0, None, None);
+    }
+
+    // hash tables, sizes come from their headers
+    if let Some(addr) = dynamic.get(value::DT_HASH) {
+        if let Some(words) = elf.words_at_vaddr(addr, 2)? {
+            let size = (2 + words[0] as u64 + words[1] as u64) * 4;
+            add(".hash", value::SHT_HASH, alloc, addr, size, 4, Some(".dynsym"), None);
+        }
+    }
+    if let Some(addr) = dynamic.get(value::DT_GNU_HASH) {
+        if let Some(words) = elf.words_at_vaddr(addr, 4)? {
+            let (nbuckets, symoffset, bloom_size) = (words[0] as u64, words[1] as u64, words[2] as u64);
+            let size = (16 + bloom_size * pointer + nbuckets * 4)
+                .saturating_add(count.saturating_sub(symoffset).saturating_mul(4));
+            add(".gnu.hash", value::SHT_GNU_HASH, alloc, addr, size, 0, Some(".dynsym"), None);
+        }
+    }
+
+    // relocations
+    if let (Some(addr), Some(size)) = (dynamic.get(value::DT_REL), dynamic.get(value::DT_RELSZ)) {
+        let entsize = dynamic.get(value::DT_RELENT).unwrap_or(pointer * 2);
+        add(".rel.dyn", value::SHT_REL, alloc, addr, size, entsize, Some(".dynsym"), None);
+    }
+    if let (Some(addr), Some(size)) = (dynamic.get(value::DT_RELA), dynamic.get(value::DT_RELASZ)) {
+        let entsize = dynamic.get(value::DT_RELAENT).unwrap_or(pointer * 3);
+        add(".rela.dyn", value::SHT_RELA, alloc, addr, size, entsize, Some(".dynsym"), None);
+    }
+    let plt_flags = alloc | value::SHF_INFO_LINK as u64;
+    let plt_count = match (dynamic.get(value::DT_JMPREL), dynamic.get(value::DT_PLTRELSZ),
+        dynamic.get(value::DT_PLTREL)) {
+        (Some(addr), Some(size), Some(kind)) if kind as i64 == value::DT_RELA => {
+            let entsize = dynamic.get(value::DT_RELAENT).unwrap_or(pointer * 3);
+            add(".rela.plt", value::SHT_RELA, plt_flags, addr, size, entsize, Some(".dynsym"), Some(".got"));
+            size / entsize.max(1)
+        },
+        (Some(addr), Some(size), _) => {
+            let entsize = dynamic.get(value::DT_RELENT).unwrap_or(pointer * 2);
+            add(".rel.plt", value::SHT_REL, plt_flags, addr, size, entsize, Some(".dynsym"), Some(".got"));
+            size / entsize.max(1)
+        },
+        _ => 0,
+    };
+
+    // init and fini arrays, dynamic and GOT live in writable segment
+    for (name, sh_type, addr_tag, size_tag) in [
+        (".preinit_array", value::SHT_PREINIT_ARRAY, value::DT_PREINIT_ARRAY, value::DT_PREINIT_ARRAYSZ),
+        (".init_array", value::SHT_INIT_ARRAY, value::DT_INIT_ARRAY, value::DT_INIT_ARRAYSZ),
+        (".fini_array", value::SHT_FINI_ARRAY, value::DT_FINI_ARRAY, value::DT_FINI_ARRAYSZ),
+    ] {
+        if let (Some(addr), Some(size)) = (dynamic.get(addr_tag), dynamic.get(size_tag)) {
+            add(name, sh_type, alloc | write, addr, size, pointer, None, None);
+        }
+    }
+    let dyn_size = if is_32 { 0x8 } else { 0x10 };
+    add(".dynamic", value::SHT_DYNAMIC, alloc | write, dynamic_phdr.p_vaddr, dynamic_phdr.p_memsz,
+        dyn_size, Some(".dynstr"), None);
+    // GOT starts with 3 reserved entries, then one slot per PLT relocation
+    if let Some(addr) = dynamic.get(value::DT_PLTGOT) {
+        add(".got", value::SHT_PROGBITS, alloc | write, addr, plt_count.saturating_add(3).saturating_mul(pointer), pointer, None, None);
+    }
+
+    // .text and .data fill the rest of executable and last writable segment
+    let loads: Vec<elf64::ProgramHeader> = elf.segments().iter()
+        .filter(|p| p.p_type == value::PT_LOAD)
+        .cloned()
+        .collect();
+    let known_end = |sections: &Vec<FixSection>, phdr: &elf64::ProgramHeader| sections.iter()
+        .map(|s| &s.header)
+        .filter(|h| h.sh_addr >= phdr.p_vaddr && h.sh_addr < phdr.p_vaddr.saturating_add(phdr.p_memsz))
+        .map(|h| h.sh_addr.saturating_add(h.sh_size))
+        .max()
+        .unwrap_or(phdr.p_vaddr);
+    let text = loads.iter().find(|p| (p.p_flags & value::PF_X) != 0);
+    let data = loads.iter().rev().find(|p| (p.p_flags & value::PF_W) != 0);
+    let mut ranges: Vec<(&'static str, u64, u64, u64)> = Vec::new();
+    if let Some(phdr) = text {
+        let start = known_end(&sections, phdr);
+        ranges.push((".text", start, phdr.p_vaddr.saturating_add(phdr.p_filesz), alloc | value::SHF_EXECINSTR as u64));
+    }
+    if let Some(phdr) = data {
+        let start = known_end(&sections, phdr);
+        ranges.push((".data", start, phdr.p_vaddr.saturating_add(phdr.p_memsz), alloc | write));
+    }
+    for (name, start, end, flags) in ranges {
+        if start < end {
+            sections.push(FixSection {
+                name,
+                header: elf64::SectionHeader {
+                    sh_type: value::SHT_PROGBITS,
+                    sh_flags: flags,
+                    sh_addr: start,
+                    sh_offset: start - first,
+                    sh_size: end - start,
+                    sh_addralign: pointer,
+                    ..Default::default()
+                },
+                link: None,
+                info: None,
+            });
+        }
+    }
+
+    Ok(sections)
+}
+
+fn load_header(image: &[u8]) -> Result<elf64::ELFHeader> {
+    if image.len() <= value::EI_CLASS {
+        return Err(Error::truncated("ELF header", 0, value::EI_NIDENT as u64));
+    }
+    match image[value::EI_CLASS] {
+        value::ELFCLASS32 => Ok(elf64::ELFHeader::from(&elf32::ELFHeader::load(&mut &image[..])?)),
+        value::ELFCLASS64 => elf64::ELFHeader::load(&mut &image[..]),
+        n => Err(Error::UnsupportedClass(n)),
+    }
+}
+
+fn store_header(out: &mut [u8], header: &elf64::ELFHeader) {
+    let mut buf: Vec<u8> = Vec::new();
+    writer::write_header(&mut buf, header.e_ident[value::EI_CLASS], header);
+    out[..buf.len()].copy_from_slice(&buf);
+}
+
+fn store_at(out: &mut [u8], offset: u64, data: &[u8]) -> Result<()> {
+    let begin = usize::try_from(offset).unwrap_or(usize::MAX);
+    match out.get_mut(begin..begin.saturating_add(data.len())) {
+        Some(dst) => dst.copy_from_slice(data),
+        None => return Err(Error::truncated("image", offset, data.len() as u64)),
+    };
+    Ok(())
+}
@@ -0,0 +1,238 @@
+use crate::{
+    elf::{endian::Endian, file::Symbol, value},
+    error::{Error, Result},
+};
+
+
+// SHT_HASH content
+#[derive(Debug, Default, Clone)]
+pub struct SysvHash {
+    pub buckets: Vec<u32>,
+    // one chain entry per symbol, nchain equals the number of symbols
+    pub chains: Vec<u32>,
+}
+
+// SHT_GNU_HASH content
+#[derive(Debug, Default, Clone)]
+pub struct GnuHash {
+    // index of first symbol reachable by hash
+    pub symoffset: u32,
+    pub bloom_shift: u32,
+    // bloom words are 32 or 64 bits by class
+    pub bloom: Vec<u64>,
+    pub bloom_bits: u32,
+    pub buckets: Vec<u32>,
+    // hash values of symbols from symoffset, bit 0 ends a chain
+    pub chains: Vec<u32>,
+}
+
+#[derive(Debug, Clone)]
+pub enum HashTable {
+    Sysv(SysvHash),
+    Gnu(GnuHash),
+}
+
+impl SysvHash {
+    pub fn new() -> SysvHash {
+        Self::default()
+    }
+
+    pub fn parse(data: &[u8], endian: Endian) -> Result<SysvHash> {
+        if data.len() < 8 {
+            return Err(Error::truncated("hash table", 0, 8));
+        }
+        let mut cursor = data;
+        let nbucket = endian.read_u32(&mut cursor) as usize;
+        let nchain = endian.read_u32(&mut cursor) as usize;
+        // sizes in u64, counts of a hostile table may overflow usize
+        let size = (2 + nbucket as u64 + nchain as u64) * 4;
+        if size > data.len() as u64 {
+            return Err(Error::truncated("hash table", 0, size));
+        }
+        let mut table = SysvHash::new();
+        table.buckets = (0..nbucket).map(|_| endian.read_u32(&mut cursor)).collect();
+        table.chains = (0..nchain).map(|_| endian.read_u32(&mut cursor)).collect();
+        Ok(table)
+    }
+
+    // index of defined symbol with name, at its default version
+    pub fn lookup(&self, name: &str, symbols: &[Symbol]) -> Option<usize> {
+        self.lookup_version(name, None, symbols)
+    }
+
+    // index of defined symbol with name and version
+    pub fn lookup_version(&self, name: &str, version: Option<&str>, symbols: &[Symbol]) -> Option<usize> {
+        if self.buckets.is_empty() {
+            return None;
+        }
+        let hash = elf_hash(name.as_bytes());
+        let mut index = self.buckets[hash as usize % self.buckets.len()] as usize;
+        // chain length is bounded by nchain, broken tables may loop
+        for _ in 0..self.chains.len() {
+            if index == 0 || index >= self.chains.len() {
+                break;
+            }
+            if let Some(symbol) = symbols.get(index) {
+                if is_match(symbol, name, version) {
+                    return Some(index);
+                }
+            }
+            index = self.chains[index] as usize;
+        }
+        None
+    }
+}
+
+impl GnuHash {
+    pub fn new() -> GnuHash {
+        Self::default()
+    }
+
+    // chains run to the end of data
+    pub fn parse(data: &[u8], endian: Endian, class: u8) -> Result<GnuHash> {
+        if data.len() < 16 {
+            return Err(Error::truncated("gnu hash table", 0, 16));
+        }
+        let word_size: usize = if class == value::ELFCLASS32 { 4 } else { 8 };
+        let mut cursor = data;
+        let nbuckets = endian.read_u32(&mut cursor) as usize;
+        let mut table = GnuHash::new();
+        table.symoffset = endian.read_u32(&mut cursor);
+        let bloom_size = endian.read_u32(&mut cursor) as usize;
+        table.bloom_shift = endian.read_u32(&mut cursor);
+        let size = 16 + bloom_size as u64 * word_size as u64 + nbuckets as u64 * 4;
+        if size > data.len() as u64 {
+            return Err(Error::truncated("gnu hash table", 0, size));
+        }
+        table.bloom_bits = word_size as u32 * 8;
+        table.bloom = (0..bloom_size)
+            .map(|_| match word_size {
+                4 => endian.read_u32(&mut cursor) as u64,
+                _ => endian.read_u64(&mut cursor),
+            })
+            .collect();
+        table.buckets = (0..nbuckets).map(|_| endian.read_u32(&mut cursor)).collect();
+        while cursor.len() >= 4 {
+            table.chains.push(endian.read_u32(&mut cursor));
+        }
+        Ok(table)
+    }
+
+    // number of symbols covered by table, symbols below symoffset included
+    pub fn symbol_count(&self) -> u64 {
+        let last = match self.buckets.iter().max() {
+            Some(n) if *n >= self.symoffset => *n,
+            _ => return self.symoffset as u64,
+        };
+        let mut index = (last - self.symoffset) as usize;
+        while index < self.chains.len() {
+            if (self.chains[index] & 1) != 0 {
+                return self.symoffset as u64 + index as u64 + 1;
+            }
+            index += 1;
+        }
+        self.symoffset as u64 + self.chains.len() as u64
+    }
+
+    // bloom filter test, false means name is surely absent
+    pub fn may_contain(&self, hash: u32) -> bool {
+        if self.bloom.is_empty() || self.bloom_bits == 0 {
+            return true;
+        }
+        let bits = self.bloom_bits;
+        let word = self.bloom[(hash / bits) as usize % self.bloom.len()];
+        let mask = (1u64 << (hash % bits)) | (1u64 << (hash.checked_shr(self.bloom_shift).unwrap_or(0) % bits));
+        (word & mask) == mask
+    }
+
+    // index of defined symbol with name, at its default version
+    pub fn lookup(&self, name: &str, symbols: &[Symbol]) -> Option<usize> {
+        self.lookup_version(name, None, symbols)
+    }
+
+    // index of defined symbol with name and version
+    pub fn lookup_version(&self, name: &str, version: Option<&str>, symbols: &[Symbol]) -> Option<usize> {
+        if self.buckets.is_empty() {
+            return None;
+        }
+        let hash = gnu_hash(name.as_bytes());
+        if !self.may_contain(hash) {
+            return None;
+        }
+        let mut index = self.buckets[hash as usize % self.buckets.len()] as usize;
+        if index < self.symoffset as usize {
+            return None;
+        }
+        // hashes of a chain are compared without their end bit
+        while let Some(chain) = self.chains.get(index - self.symoffset as usize) {
+            if (chain | 1) == (hash | 1) {
+                if let Some(symbol) = symbols.get(index) {
+                    if is_match(symbol, name, version) {
+                        return Some(index);
+                    }
+                }
+            }
+            if (chain & 1) != 0 {
+                break;
+            }
+            index += 1;
+        }
+        None
+    }
+}
+
+impl HashTable {
+    // index into the symbol table the hash table is linked to
+    pub fn lookup(&self, name: &str, symbols: &[Symbol]) -> Option<usize> {
+        match self {
+            HashTable::Sysv(table) => table.lookup(name, symbols),
+            HashTable::Gnu(table) => table.lookup(name, symbols),
+        }
+    }
+
+    pub fn lookup_version(&self, name: &str, version: Option<&str>, symbols: &[Symbol]) -> Option<usize> {
+        match self {
+            HashTable::Sysv(table) => table.lookup_version(name, version, symbols),
+            HashTable::Gnu(table) => table.lookup_version(name, version, symbols),
+        }
+    }
+}
+
+// hash function of SHT_HASH
+pub fn elf_hash(name: &[u8]) -> u32 {
+    let mut h: u32 = 0;
+    for c in name {
+        h = (h << 4).wrapping_add(*c as u32);
+        let g = h & 0xF000_0000;
+        if g != 0 {
+            h ^= g >> 24;
+        }
+        h &= !g;
+    }
+    h
+}
+
+// hash function of SHT_GNU_HASH, djb2
+pub fn gnu_hash(name: &[u8]) -> u32 {
+    let mut h: u32 = 5381;
+    for c in name {
+        h = h.wrapping_mul(33).wrapping_add(*c as u32);
+    }
+    h
+}
+
+// the dynamic linker never binds to undefined symbols,
+// nor to hidden versions unless the version is asked for.
+// The first defined version is the exception, objects linked
+// before the library had versions refer to it unversioned.
+pub(crate) fn is_match(symbol: &Symbol, name: &str, version: Option<&str>) -> bool {
+    if symbol.entry.st_shndx as usize == value::SHN_UNDEF || symbol.name != name {
+        return false;
+    }
+    match (version, &symbol.version) {
+        (Some(want), Some(have)) => have.name == want,
+        (Some(_), None) => false,
+        (None, Some(have)) => !have.hidden || have.index <= value::VER_NDX_GLOBAL + 1,
+        (None, None) => true,
+    }
+}
@@ -0,0 +1,39 @@
+// LEB128 numbers, None if data ends inside a number or it overflows 64 bits
+
+pub fn read_uleb128(cursor: &mut &[u8]) -> Option<u64> {
+    let mut result: u64 = 0;
+    let mut shift = 0;
+    loop {
+        let (byte, rest) = cursor.split_first()?;
+        *cursor = rest;
+        if shift >= 64 {
+            return None;
+        }
+        result |= ((byte & 0x7F) as u64) << shift;
+        shift += 7;
+        if (byte & 0x80) == 0 {
+            return Some(result);
+        }
+    }
+}
+
+pub fn read_sleb128(cursor: &mut &[u8]) -> Option<i64> {
+    let mut result: i64 = 0;
+    let mut shift = 0;
+    loop {
+        let (byte, rest) = cursor.split_first()?;
+        *cursor = rest;
+        if shift >= 64 {
+            return None;
+        }
+        result |= ((byte & 0x7F) as i64) << shift;
+        shift += 7;
+        if (byte & 0x80) == 0 {
+            // sign extend from last bit read
+            if shift < 64 && (byte & 0x40) != 0 {
+                result |= -1i64 << shift;
+            }
+            return Some(result);
+        }
+    }
+}
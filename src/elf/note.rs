@@ -0,0 +1,269 @@
+use std::{fmt, str};
+use crate::elf::{endian::Endian, value, view::cstr};
+
+
+// entry of a note section or segment, name and descriptor borrowed from its data
+#[derive(Debug, Clone)]
+pub struct Note<'a> {
+    pub name: &'a str,
+    pub n_type: u32,
+    pub desc: &'a [u8],
+    class: u8,
+    endian: Endian,
+}
+
+// notes of one SHT_NOTE section or PT_NOTE segment, decoded while iterating
+pub struct NoteIter<'a> {
+    data: &'a [u8],
+    align: usize,
+    class: u8,
+    endian: Endian,
+}
+
+// content of a SHT_NOTE section or PT_NOTE segment read from file
+#[derive(Debug, Clone)]
+pub struct NoteTable {
+    // name of section, None for a segment
+    pub section: Option<String>,
+    pub offset: u64,
+    pub align: u64,
+    pub data: Vec<u8>,
+    pub class: u8,
+    pub endian: Endian,
+}
+
+// descriptor decoded by owner and type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NoteDesc<'a> {
+    BuildId(&'a [u8]),
+    AbiTag { os: u32, major: u32, minor: u32, patch: u32 },
+    Properties(Vec<Property<'a>>),
+    // NDK version and build number are only written by NDK r14 and later
+    AndroidIdent { api_level: u32, ndk_version: Option<&'a str>, ndk_build: Option<&'a str> },
+    Other,
+}
+
+// entry of NT_GNU_PROPERTY_TYPE_0
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Property<'a> {
+    StackSize(u64),
+    NoCopyOnProtected,
+    Aarch64Features(u32),
+    X86Features(u32),
+    X86IsaNeeded(u32),
+    X86IsaUsed(u32),
+    Other { pr_type: u32, data: &'a [u8] },
+}
+
+impl<'a> NoteIter<'a> {
+    // align is sh_addralign or p_align, 8 only for GNU properties of ELF64
+    pub fn new(data: &'a [u8], class: u8, endian: Endian, align: u64) -> NoteIter<'a> {
+        NoteIter { data, align: if align == 8 { 8 } else { 4 }, class, endian }
+    }
+}
+
+impl<'a> Iterator for NoteIter<'a> {
+    type Item = Note<'a>;
+
+    // a truncated entry ends the iteration
+    fn next(&mut self) -> Option<Note<'a>> {
+        if self.data.len() < 12 {
+            return None;
+        }
+        let mut cursor = self.data;
+        let namesz = self.endian.read_u32(&mut cursor) as usize;
+        let descsz = self.endian.read_u32(&mut cursor) as usize;
+        let n_type = self.endian.read_u32(&mut cursor);
+        let desc_start = align_up(namesz.checked_add(12)?, self.align)?;
+        let desc_end = desc_start.checked_add(descsz)?;
+        let (name, desc) = match (self.data.get(12..12 + namesz), self.data.get(desc_start..desc_end)) {
+            (Some(name), Some(desc)) => (name, desc),
+            _ => {
+                self.data = &[];
+                return None;
+            }
+        };
+        let next = align_up(desc_end, self.align).unwrap_or(usize::MAX);
+        self.data = self.data.get(next..).unwrap_or(&[]);
+        Some(Note {
+            // owners are ASCII, anything else matches none
+            name: cstr(name, 0).and_then(|s| str::from_utf8(s).ok()).unwrap_or(""),
+            n_type, desc,
+            class: self.class,
+            endian: self.endian,
+        })
+    }
+}
+
+impl NoteTable {
+    pub fn notes(&self) -> NoteIter<'_> {
+        NoteIter::new(&self.data, self.class, self.endian, self.align)
+    }
+}
+
+impl<'a> Note<'a> {
+    // pr_type of processor-specific properties depends on machine
+    pub fn decode(&self, machine: u16) -> NoteDesc<'a> {
+        let mut cursor = self.desc;
+        match (self.name, self.n_type) {
+            ("GNU", value::NT_GNU_BUILD_ID) => NoteDesc::BuildId(self.desc),
+            ("GNU", value::NT_GNU_ABI_TAG) if self.desc.len() >= 16 => NoteDesc::AbiTag {
+                os: self.endian.read_u32(&mut cursor),
+                major: self.endian.read_u32(&mut cursor),
+                minor: self.endian.read_u32(&mut cursor),
+                patch: self.endian.read_u32(&mut cursor),
+            },
+            ("GNU", value::NT_GNU_PROPERTY_TYPE_0) => NoteDesc::Properties(self.properties(machine)),
+            ("Android", value::NT_ANDROID_TYPE_IDENT) if self.desc.len() >= 4 => {
+                let text = |range: std::ops::Range<usize>| self.desc.get(range).and_then(|s| cstr(s, 0)).and_then(|s| str::from_utf8(s).ok());
+                NoteDesc::AndroidIdent {
+                    api_level: self.endian.read_u32(&mut cursor),
+                    ndk_version: text(4..68),
+                    ndk_build: text(68..132),
+                }
+            },
+            _ => NoteDesc::Other,
+        }
+    }
+
+    pub fn build_id(&self) -> Option<&'a [u8]> {
+        match (self.name, self.n_type) {
+            ("GNU", value::NT_GNU_BUILD_ID) => Some(self.desc),
+            _ => None,
+        }
+    }
+
+    // entries are padded to 8 bytes in ELF64 and to 4 in ELF32
+    fn properties(&self, machine: u16) -> Vec<Property<'a>> {
+        let align = if self.class == value::ELFCLASS32 { 4 } else { 8 };
+        let mut properties: Vec<Property<'a>> = Vec::new();
+        let mut rest = self.desc;
+        while rest.len() >= 8 {
+            let mut cursor = rest;
+            let pr_type = self.endian.read_u32(&mut cursor);
+            let size = self.endian.read_u32(&mut cursor) as usize;
+            let data = match cursor.get(..size) {
+                Some(data) => data,
+                None => break,
+            };
+            // features and ISA levels are 32-bit masks, stack size is a pointer
+            let mut value_data = data;
+            let number = match size {
+                4 => Some(self.endian.read_u32(&mut value_data) as u64),
+                8 => Some(self.endian.read_u64(&mut value_data)),
+                _ => None,
+            };
+            let x86 = machine == value::EM_386 || machine == value::EM_X86_64;
+            let property = match (pr_type, number) {
+                (value::GNU_PROPERTY_STACK_SIZE, Some(n)) => Property::StackSize(n),
+                (value::GNU_PROPERTY_NO_COPY_ON_PROTECTED, _) => Property::NoCopyOnProtected,
+                (value::GNU_PROPERTY_AARCH64_FEATURE_1_AND, Some(n)) if machine == value::EM_AARCH64 && size == 4 =>
+                    Property::Aarch64Features(n as u32),
+                (value::GNU_PROPERTY_X86_FEATURE_1_AND, Some(n)) if x86 && size == 4 => Property::X86Features(n as u32),
+                (value::GNU_PROPERTY_X86_ISA_1_NEEDED, Some(n)) if x86 && size == 4 => Property::X86IsaNeeded(n as u32),
+                (value::GNU_PROPERTY_X86_ISA_1_USED, Some(n)) if x86 && size == 4 => Property::X86IsaUsed(n as u32),
+                _ => Property::Other { pr_type, data },
+            };
+            properties.push(property);
+            let next = align_up(8 + size, align).unwrap_or(usize::MAX);
+            rest = rest.get(next..).unwrap_or(&[]);
+        }
+        properties
+    }
+}
+
+// note type as readelf names it, by owner
+pub fn type_name(name: &str, n_type: u32) -> &'static str {
+    match (name, n_type) {
+        ("GNU", value::NT_GNU_ABI_TAG) => "NT_GNU_ABI_TAG",
+        ("GNU", value::NT_GNU_HWCAP) => "NT_GNU_HWCAP",
+        ("GNU", value::NT_GNU_BUILD_ID) => "NT_GNU_BUILD_ID",
+        ("GNU", value::NT_GNU_GOLD_VERSION) => "NT_GNU_GOLD_VERSION",
+        ("GNU", value::NT_GNU_PROPERTY_TYPE_0) => "NT_GNU_PROPERTY_TYPE_0",
+        ("Android", value::NT_ANDROID_TYPE_IDENT) => "NT_ANDROID_TYPE_IDENT",
+        _ => "UNKNOWN",
+    }
+}
+
+// lowercase hex, as build-ids are written in symbol file paths
+pub fn hex(data: &[u8]) -> String {
+    data.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+impl<'a> fmt::Display for NoteDesc<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NoteDesc::BuildId(id) => write!(f, "Build ID: {}", hex(id)),
+            NoteDesc::AbiTag { os, major, minor, patch } => {
+                let os = match *os {
+                    value::ELF_NOTE_OS_LINUX => "Linux",
+                    value::ELF_NOTE_OS_GNU => "Hurd",
+                    value::ELF_NOTE_OS_SOLARIS2 => "Solaris 2",
+                    value::ELF_NOTE_OS_FREEBSD => "FreeBSD",
+                    _ => "Unknown",
+                };
+                write!(f, "OS: {}, ABI: {}.{}.{}", os, major, minor, patch)
+            },
+            NoteDesc::Properties(properties) => {
+                let list: Vec<String> = properties.iter().map(|p| p.to_string()).collect();
+                write!(f, "Properties: {}", list.join("; "))
+            },
+            NoteDesc::AndroidIdent { api_level, ndk_version, ndk_build } => {
+                write!(f, "Android API level: {}", api_level)?;
+                if let Some(version) = ndk_version {
+                    write!(f, ", NDK: {}", version)?;
+                }
+                match ndk_build {
+                    Some(build) => write!(f, " ({})", build),
+                    None => Ok(()),
+                }
+            },
+            NoteDesc::Other => Ok(()),
+        }
+    }
+}
+
+impl<'a> fmt::Display for Property<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let isa = [
+            (value::GNU_PROPERTY_X86_ISA_1_BASELINE, "x86-64-baseline"),
+            (value::GNU_PROPERTY_X86_ISA_1_V2, "x86-64-v2"),
+            (value::GNU_PROPERTY_X86_ISA_1_V3, "x86-64-v3"),
+            (value::GNU_PROPERTY_X86_ISA_1_V4, "x86-64-v4"),
+        ];
+        match self {
+            Property::StackSize(size) => write!(f, "stack size: {:#x}", size),
+            Property::NoCopyOnProtected => write!(f, "no copy on protected"),
+            Property::Aarch64Features(bits) => write!(f, "AArch64 feature: {}", bit_names(*bits, &[
+                (value::GNU_PROPERTY_AARCH64_FEATURE_1_BTI, "BTI"),
+                (value::GNU_PROPERTY_AARCH64_FEATURE_1_PAC, "PAC"),
+            ])),
+            Property::X86Features(bits) => write!(f, "x86 feature: {}", bit_names(*bits, &[
+                (value::GNU_PROPERTY_X86_FEATURE_1_IBT, "IBT"),
+                (value::GNU_PROPERTY_X86_FEATURE_1_SHSTK, "SHSTK"),
+            ])),
+            Property::X86IsaNeeded(bits) => write!(f, "x86 ISA needed: {}", bit_names(*bits, &isa)),
+            Property::X86IsaUsed(bits) => write!(f, "x86 ISA used: {}", bit_names(*bits, &isa)),
+            Property::Other { pr_type, data } => write!(f, "<type {:#x}>: {}", pr_type, hex(data)),
+        }
+    }
+}
+
+fn bit_names(bits: u32, names: &[(u32, &str)]) -> String {
+    if bits == 0 {
+        return String::from("<None>");
+    }
+    let mut list: Vec<String> = names.iter()
+        .filter(|(bit, _)| (bits & bit) != 0)
+        .map(|(_, name)| name.to_string())
+        .collect();
+    let known = names.iter().fold(0, |all, (bit, _)| all | bit);
+    if (bits & !known) != 0 {
+        list.push(format!("<unknown: {:#x}>", bits & !known));
+    }
+    list.join(", ")
+}
+
+fn align_up(n: usize, align: usize) -> Option<usize> {
+    Some(n.checked_add(align - 1)? & !(align - 1))
+}
@@ -0,0 +1,377 @@
+use crate::{
+    elf::{
+        address::{self, Location}, elf32, elf64, value, view,
+        writer::{self, ElfWriter, OutputSection, StringTableBuilder},
+    },
+    error::{Error, Result},
+};
+
+
+const PAGE_SIZE: u64 = 0x1000;
+// largest p_align the appended segment follows, as of x86-64 before binutils 2.31
+const MAX_ALIGN: u64 = 0x20_0000;
+
+// Edits of a linked file by address and name, on top of ElfWriter.
+// Content that outgrows its place is appended in a new PT_LOAD at the end of
+// file and memory, the program header table moves there to make room for it.
+// Every edit is recorded in a log readable by humans.
+pub struct Patcher {
+    writer: ElfWriter,
+    log: Vec<String>,
+    // index in segments of the appended PT_LOAD
+    extension: Option<usize>,
+}
+
+impl Patcher {
+    pub fn new(writer: ElfWriter) -> Patcher {
+        Patcher { writer, log: Vec::new(), extension: None }
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<Patcher> {
+        Ok(Patcher::new(ElfWriter::from_bytes(data)?))
+    }
+
+    pub fn writer(&self) -> &ElfWriter {
+        &self.writer
+    }
+
+    pub fn log(&self) -> &[String] {
+        &self.log
+    }
+
+    pub fn to_bytes(&mut self) -> Result<Vec<u8>> {
+        self.writer.to_bytes()
+    }
+
+    // overwrite bytes mapped at vaddr, the range must lie in file content of one PT_LOAD
+    pub fn write_vaddr(&mut self, vaddr: u64, data: &[u8]) -> Result<()> {
+        let offset = self.vaddr_to_offset(vaddr, data.len() as u64)?;
+        self.writer.write_at(offset, data)?;
+        self.log.push(format!("write {:#x} bytes at address {:#x}, offset {:#x}", data.len(), vaddr, offset));
+        Ok(())
+    }
+
+    // set st_value of name in .symtab and .dynsym, returns the number of entries changed
+    pub fn set_symbol_value(&mut self, name: &str, st_value: u64) -> Result<usize> {
+        let (class, endian) = (self.writer.class, self.writer.endian);
+        let min_size = if class == value::ELFCLASS32 { 0x10 } else { 0x18 };
+        let mut changes: Vec<(String, u64, u64, Vec<u8>)> = Vec::new();
+        for section in &self.writer.sections {
+            let shdr = &section.header;
+            if (shdr.sh_type != value::SHT_SYMTAB && shdr.sh_type != value::SHT_DYNSYM) || shdr.sh_entsize < min_size {
+                continue;
+            }
+            let strtab = match self.writer.sections.get(shdr.sh_link as usize) {
+                Some(s) => &s.data[..],
+                None => continue,
+            };
+            for (index, data) in section.data.chunks_exact(shdr.sh_entsize as usize).enumerate() {
+                let mut entry = match class {
+                    value::ELFCLASS32 => elf64::SymbolEntry::from(&elf32::SymbolEntry::parse(data, endian)),
+                    _ => elf64::SymbolEntry::parse(data, endian),
+                };
+                if view::cstr(strtab, entry.st_name as usize) != Some(name.as_bytes()) {
+                    continue;
+                }
+                let old = entry.st_value;
+                entry.st_value = st_value;
+                let mut buf: Vec<u8> = Vec::new();
+                writer::write_symbol(&mut buf, class, endian, &entry);
+                changes.push((section.name.clone(), old, shdr.sh_offset + index as u64 * shdr.sh_entsize, buf));
+            }
+        }
+        if changes.is_empty() {
+            return Err(Error::NotFound(format!("symbol {}", name)));
+        }
+        for (table, old, offset, buf) in &changes {
+            self.writer.write_at(*offset, buf)?;
+            self.log.push(format!("set {} of {} from {:#x} to {:#x}", name, table, old, st_value));
+        }
+        Ok(changes.len())
+    }
+
+    // Add a section with header fields of header except name, offset and size.
+    // Sections with SHF_ALLOC are mapped by the appended PT_LOAD at sh_addr it
+    // gets, others are placed at the end of file. Returns the section index.
+    pub fn add_section(&mut self, name: &str, header: elf64::SectionHeader, data: Vec<u8>) -> Result<usize> {
+        if header.sh_type == value::SHT_NOBITS || header.sh_type == value::SHT_NULL {
+            return Err(Error::invalid(format!("section {}", name), "no content to add"));
+        }
+        self.ensure_shstrtab();
+        let mut section = OutputSection { name: name.to_string(), header, data };
+        section.header.sh_name = 0;
+        section.header.sh_size = section.data.len() as u64;
+        if (section.header.sh_flags & value::SHF_ALLOC as u64) != 0 {
+            let mut flags = value::PF_R;
+            if (section.header.sh_flags & value::SHF_WRITE as u64) != 0 {
+                flags |= value::PF_W;
+            }
+            if (section.header.sh_flags & value::SHF_EXECINSTR as u64) != 0 {
+                flags |= value::PF_X;
+            }
+            let (offset, vaddr) = self.extend(&section.data, section.header.sh_addralign, flags)?;
+            section.header.sh_offset = offset;
+            section.header.sh_addr = vaddr;
+            self.log.push(format!("add section {} of {:#x} bytes at address {:#x}, offset {:#x}",
+                name, section.data.len(), vaddr, offset));
+        }
+        else {
+            // layout moves it to the end of file
+            section.header.sh_offset = 0;
+            section.header.sh_addr = 0;
+            self.log.push(format!("add section {} of {:#x} bytes at end of file", name, section.data.len()));
+        }
+        self.writer.sections.push(section);
+        Ok(self.writer.sections.len() - 1)
+    }
+
+    // Add DT_NEEDED of name after the last one. .dynstr and .dynamic are
+    // extended in place while they have room, else moved to the appended PT_LOAD.
+    pub fn add_needed(&mut self, name: &str) -> Result<()> {
+        let (class, endian) = (self.writer.class, self.writer.endian);
+        let entry_size: u64 = if class == value::ELFCLASS32 { 0x8 } else { 0x10 };
+        let (dyn_offset, dyn_size) = self.dynamic_table()?;
+        let table = self.bytes_at(dyn_offset, dyn_size - dyn_size % entry_size, "dynamic section")?;
+        let mut entries: Vec<elf64::DynamicEntry> = table.chunks_exact(entry_size as usize)
+            .map(|data| match class {
+                value::ELFCLASS32 => elf64::DynamicEntry::from(&elf32::DynamicEntry::parse(data, endian)),
+                _ => elf64::DynamicEntry::parse(data, endian),
+            })
+            .take_while(|entry| entry.d_tag != value::DT_NULL)
+            .collect();
+        let capacity = table.len() as u64 / entry_size;
+        let tag = |entries: &[elf64::DynamicEntry], tag: i64| entries.iter().find(|e| e.d_tag == tag).map(|e| e.d_val);
+        let (strtab_addr, strtab_size) = match (tag(&entries, value::DT_STRTAB), tag(&entries, value::DT_STRSZ)) {
+            (Some(addr), Some(size)) => (addr, size),
+            _ => return Err(Error::NotFound(String::from("DT_STRTAB"))),
+        };
+        let strtab_offset = self.vaddr_to_offset(strtab_addr, strtab_size)?;
+        let strtab = self.bytes_at(strtab_offset, strtab_size, "dynamic string table")?;
+
+        // a string already in .dynstr is shared
+        let mut strings = StringTableBuilder::from_bytes(&strtab);
+        let name_offset = strings.add(name);
+        if strings.data().len() != strtab.len() {
+            let data = strings.into_bytes();
+            let (offset, vaddr) = self.relocate(strtab_offset, strtab.len(), &data, 1, value::PF_R)?;
+            self.retarget(strtab_offset, value::SHT_STRTAB, offset, vaddr, &data);
+            for entry in entries.iter_mut() {
+                match entry.d_tag {
+                    value::DT_STRTAB => entry.d_val = vaddr,
+                    value::DT_STRSZ => entry.d_val = data.len() as u64,
+                    _ => { },
+                }
+            }
+            self.log.push(format!("dynamic string table of {:#x} bytes at address {:#x}, offset {:#x}",
+                data.len(), vaddr, offset));
+        }
+
+        let position = entries.iter().rposition(|e| e.d_tag == value::DT_NEEDED).map_or(0, |i| i + 1);
+        entries.insert(position, elf64::DynamicEntry { d_tag: value::DT_NEEDED, d_val: name_offset as u64 });
+        let mut data: Vec<u8> = Vec::new();
+        for entry in &entries {
+            writer::write_dynamic(&mut data, class, endian, entry);
+        }
+        // DT_NULL ends the table, spare slots are left as DT_NULL too
+        if (entries.len() as u64) < capacity {
+            data.resize(table.len(), 0);
+            self.writer.write_at(dyn_offset, &data)?;
+        }
+        else {
+            data.resize(data.len() + entry_size as usize, 0);
+            let (offset, vaddr) = self.relocate(dyn_offset, table.len(), &data, entry_size, value::PF_R | value::PF_W)?;
+            self.retarget(dyn_offset, value::SHT_DYNAMIC, offset, vaddr, &data);
+            for phdr in self.writer.segments.iter_mut().filter(|p| p.p_type == value::PT_DYNAMIC) {
+                phdr.p_offset = offset;
+                phdr.p_vaddr = vaddr;
+                phdr.p_paddr = vaddr;
+                phdr.p_filesz = data.len() as u64;
+                phdr.p_memsz = data.len() as u64;
+            }
+            self.log.push(format!("dynamic section of {:#x} bytes at address {:#x}, offset {:#x}",
+                data.len(), vaddr, offset));
+        }
+        self.log.push(format!("add DT_NEEDED {}", name));
+        Ok(())
+    }
+
+    // file offset of size bytes at vaddr, all of them in file content of one PT_LOAD
+    fn vaddr_to_offset(&self, vaddr: u64, size: u64) -> Result<u64> {
+        let segments = &self.writer.segments;
+        let phdr = match address::segment_for_vaddr(segments, vaddr) {
+            Some(index) => &segments[index],
+            None => return Err(Error::NotFound(format!("segment of address {:#x}", vaddr))),
+        };
+        let offset = match address::vaddr_to_offset(segments, vaddr) {
+            Some(Location::File(offset)) => offset,
+            _ => return Err(Error::invalid(format!("address {:#x}", vaddr), "zero-filled, not in file")),
+        };
+        if (vaddr - phdr.p_vaddr).saturating_add(size) > phdr.p_filesz {
+            return Err(Error::invalid(format!("address {:#x}", vaddr),
+                format!("{:#x} bytes do not fit file content of segment at {:#x}", size, phdr.p_vaddr)));
+        }
+        Ok(offset)
+    }
+
+    fn bytes_at(&self, offset: u64, size: u64, what: &str) -> Result<Vec<u8>> {
+        match self.writer.bytes_at(offset, size) {
+            Some(data) => Ok(data.to_vec()),
+            None => Err(Error::truncated(what, offset, size)),
+        }
+    }
+
+    // offset and size of the dynamic table, PT_DYNAMIC first as the loader does
+    fn dynamic_table(&self) -> Result<(u64, u64)> {
+        if let Some(phdr) = self.writer.segments.iter().find(|p| p.p_type == value::PT_DYNAMIC) {
+            return Ok((phdr.p_offset, phdr.p_filesz));
+        }
+        match self.writer.sections.iter().find(|s| s.header.sh_type == value::SHT_DYNAMIC) {
+            Some(s) => Ok((s.header.sh_offset, s.data.len() as u64)),
+            None => Err(Error::NotFound(String::from("dynamic section"))),
+        }
+    }
+
+    // point the section of sh_type found at old_offset to content moved to offset
+    fn retarget(&mut self, old_offset: u64, sh_type: u32, offset: u64, vaddr: u64, data: &[u8]) {
+        let found = self.writer.sections.iter_mut()
+            .find(|s| s.header.sh_type == sh_type && s.header.sh_offset == old_offset);
+        if let Some(section) = found {
+            section.header.sh_offset = offset;
+            section.header.sh_addr = vaddr;
+            section.header.sh_size = data.len() as u64;
+            section.data = data.to_vec();
+        }
+    }
+
+    // names of added sections need a section string table
+    fn ensure_shstrtab(&mut self) {
+        let index = self.writer.shstrndx();
+        let valid = self.writer.sections.get(index)
+            .is_some_and(|s| index != value::SHN_UNDEF && s.header.sh_type == value::SHT_STRTAB);
+        if valid {
+            return;
+        }
+        if self.writer.sections.is_empty() {
+            self.writer.sections.push(OutputSection::default());
+        }
+        self.writer.sections.push(OutputSection {
+            name: String::from(".shstrtab"),
+            header: elf64::SectionHeader { sh_type: value::SHT_STRTAB, sh_addralign: 1, ..Default::default() },
+            data: StringTableBuilder::new().into_bytes(),
+        });
+        let index = self.writer.sections.len() - 1;
+        self.writer.set_shstrndx(index);
+        self.log.push(String::from("add section .shstrtab"));
+    }
+
+    // Give data, the grown content of size bytes at offset, a place in the
+    // appended PT_LOAD. Content already ending it grows in place, the rest is
+    // copied. Returns the new file offset and address.
+    fn relocate(&mut self, offset: u64, size: usize, data: &[u8], align: u64, flags: u32) -> Result<(u64, u64)> {
+        if let Some(index) = self.extension {
+            let phdr = &self.writer.segments[index];
+            if offset >= phdr.p_offset && offset + size as u64 == phdr.p_offset + phdr.p_filesz {
+                let vaddr = phdr.p_vaddr + (offset - phdr.p_offset);
+                self.extend(&data[size..], 1, flags)?;
+                self.writer.write_at(offset, &data[..size])?;
+                return Ok((offset, vaddr));
+            }
+        }
+        self.extend(data, align, flags)
+    }
+
+    // Append data to the PT_LOAD at the end of file and memory, created on
+    // first use together with the moved program header table. Returns the
+    // file offset and address data was placed at.
+    fn extend(&mut self, data: &[u8], align: u64, flags: u32) -> Result<(u64, u64)> {
+        let index = match self.extension {
+            Some(index) => index,
+            None => self.add_extension()?,
+        };
+        let phdr = &self.writer.segments[index];
+        let end = phdr.p_offset + phdr.p_filesz;
+        if self.writer.file_end() > end {
+            return Err(Error::invalid("appended segment", "no longer at the end of file"));
+        }
+        let offset = align_up(end, align);
+        let vaddr = phdr.p_vaddr.saturating_add(offset - phdr.p_offset);
+        self.check_address(vaddr, data.len() as u64)?;
+        self.writer.write_at(offset, data)?;
+        let phdr = &mut self.writer.segments[index];
+        phdr.p_filesz = offset + data.len() as u64 - phdr.p_offset;
+        phdr.p_memsz = phdr.p_filesz;
+        phdr.p_flags |= flags;
+        Ok((offset, vaddr))
+    }
+
+    // the appended segment must stay in the address space of the class
+    fn check_address(&self, vaddr: u64, size: u64) -> Result<()> {
+        let limit = if self.writer.class == value::ELFCLASS32 { u32::MAX as u64 } else { u64::MAX };
+        match vaddr.checked_add(size) {
+            Some(end) if end <= limit => Ok(()),
+            _ => Err(Error::invalid("appended segment", format!("address {:#x} out of address space", vaddr))),
+        }
+    }
+
+    fn add_extension(&mut self) -> Result<usize> {
+        let header = &self.writer.header;
+        if header.e_type != value::ET_DYN && header.e_type != value::ET_EXEC {
+            return Err(Error::invalid("ELF type", format!("{} cannot get a new segment", header.e_type)));
+        }
+        let loads: Vec<&elf64::ProgramHeader> = self.writer.segments.iter()
+            .filter(|p| p.p_type == value::PT_LOAD)
+            .collect();
+        if loads.is_empty() {
+            return Err(Error::NotFound(String::from("PT_LOAD")));
+        }
+        // offset and address congruent modulo the largest alignment in use
+        let align = loads.iter().map(|p| p.p_align).fold(PAGE_SIZE, u64::max);
+        if align > MAX_ALIGN || !align.is_power_of_two() {
+            return Err(Error::invalid("p_align", format!("{:#x}", align)));
+        }
+        let memory_end = loads.iter().map(|p| p.p_vaddr.saturating_add(p.p_memsz)).max().unwrap_or(0);
+        let offset = align_up(self.writer.file_end(), align);
+        let vaddr = align_up(memory_end, align);
+        let (_, phdr_size, _) = writer::entry_sizes(self.writer.class);
+        let entry_size = match header.e_phentsize {
+            0 => phdr_size,
+            n => n as u64,
+        };
+        let table_size = (self.writer.segments.len() as u64 + 1) * entry_size;
+        self.check_address(vaddr, table_size)?;
+
+        // PT_LOAD entries are sorted by address, the new one goes after the last of them
+        let position = self.writer.segments.iter().rposition(|p| p.p_type == value::PT_LOAD).map_or(0, |i| i + 1);
+        self.writer.segments.insert(position, elf64::ProgramHeader {
+            p_type: value::PT_LOAD,
+            p_flags: value::PF_R,
+            p_offset: offset,
+            p_vaddr: vaddr,
+            p_paddr: vaddr,
+            p_filesz: table_size,
+            p_memsz: table_size,
+            p_align: align,
+        });
+        for phdr in self.writer.segments.iter_mut().filter(|p| p.p_type == value::PT_PHDR) {
+            phdr.p_offset = offset;
+            phdr.p_vaddr = vaddr;
+            phdr.p_paddr = vaddr;
+            phdr.p_filesz = table_size;
+            phdr.p_memsz = table_size;
+        }
+        self.writer.header.e_phoff = offset;
+        self.writer.header.e_phentsize = entry_size as u16;
+        // room for the table, to_bytes writes the entries
+        self.writer.write_at(offset, &vec![0; table_size as usize])?;
+        self.extension = Some(position);
+        self.log.push(format!("add PT_LOAD at address {:#x}, offset {:#x}, program headers moved there", vaddr, offset));
+        Ok(position)
+    }
+}
+
+fn align_up(offset: u64, align: u64) -> u64 {
+    match align {
+        0 | 1 => offset,
+        n => offset.div_ceil(n).saturating_mul(n),
+    }
+}
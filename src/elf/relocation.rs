@@ -0,0 +1,550 @@
+use crate::{
+    elf::{elf64, endian::Endian, file::Symbol, leb128, value},
+    error::{Error, Result},
+};
+
+
+// groups sharing offset delta, r_info and addend take no bytes per entry,
+// counts beyond this are taken as corrupt rather than decoded into memory
+const MAX_PACKED_RELOCATIONS: i64 = 1 << 22;
+
+// relocation of any class, joined to its symbol
+#[derive(Debug, Clone)]
+pub struct Relocation {
+    pub offset: u64,
+    pub info: u64,
+    pub symbol_index: u32,
+    pub rel_type: u32,
+    // None for SHT_REL, the addend is stored at offset
+    pub addend: Option<i64>,
+    // None for symbol index 0
+    pub symbol: Option<Symbol>,
+    // e_machine gives meaning to rel_type
+    pub machine: u16,
+}
+
+// relocations of one table, by section or by dynamic tag
+#[derive(Debug, Default, Clone)]
+pub struct RelocationSection {
+    pub name: String,
+    pub offset: u64,
+    pub relocations: Vec<Relocation>,
+}
+
+impl Relocation {
+    pub fn type_name(&self) -> String {
+        match type_name(self.machine, self.rel_type) {
+            Some(name) => name.to_string(),
+            None => format!("<unknown {:#x}>", self.rel_type),
+        }
+    }
+
+    pub fn symbol_name(&self) -> String {
+        self.symbol.as_ref().map_or(String::new(), |s| s.versioned_name())
+    }
+
+    pub fn demangled_symbol_name(&self) -> String {
+        self.symbol.as_ref().map_or(String::new(), |s| s.versioned_demangled_name())
+    }
+}
+
+impl RelocationSection {
+    pub fn new() -> RelocationSection {
+        Self::default()
+    }
+}
+
+// symbol index and type of widened r_info.
+// MIPS64 packs three types after the symbol, and little-endian files
+// store the symbol first so the u64 read is not the usual split.
+pub fn split_info(machine: u16, class: u8, endian: Endian, info: u64) -> (u32, u32) {
+    if machine == value::EM_MIPS && class == value::ELFCLASS64 {
+        return match endian {
+            Endian::Little => ((info & 0xFFFFFFFF) as u32, ((info >> 56) & 0xFF) as u32),
+            Endian::Big => (value::relocation64_symbol(info), (info & 0xFF) as u32),
+        };
+    }
+    (value::relocation64_symbol(info), value::relocation64_type(info))
+}
+
+// relocation type a RELR entry stands for
+pub fn relative_type(machine: u16) -> Option<u32> {
+    match machine {
+        value::EM_386 | value::EM_X86_64 => Some(8),
+        value::EM_ARM => Some(23),
+        value::EM_AARCH64 => Some(1027),
+        value::EM_MIPS | value::EM_MIPS_RS4_BE => Some(3),
+        value::EM_RISCV => Some(3),
+        _ => None,
+    }
+}
+
+// APS2 packed relocations of DT_ANDROID_REL and DT_ANDROID_RELA.
+// Entries come in groups whose flags tell which of offset delta, r_info
+// and addend are shared by the group, the rest follow each entry as SLEB128.
+pub fn decode_aps2(data: &[u8], class: u8) -> Result<Vec<elf64::RelaEntry>> {
+    if data.len() < 4 || &data[..4] != b"APS2" {
+        return Err(Error::invalid("packed relocations", "no APS2 magic"));
+    }
+    let mut cursor = &data[4..];
+    let mut next = || match leb128::read_sleb128(&mut cursor) {
+        Some(n) => Ok(n),
+        None => Err(Error::truncated("packed relocations", (data.len() - cursor.len()) as u64, 1)),
+    };
+
+    let count = next()?;
+    if count > MAX_PACKED_RELOCATIONS {
+        return Err(Error::invalid("packed relocation count", count.to_string()));
+    }
+    let mut r_offset = next()? as u64;
+    let mut r_info: u64 = 0;
+    let mut r_addend: i64 = 0;
+    let mut entries: Vec<elf64::RelaEntry> = Vec::new();
+    while (entries.len() as i64) < count {
+        let group_size = next()?;
+        if group_size <= 0 || group_size > count - entries.len() as i64 {
+            return Err(Error::invalid("packed relocation group size", group_size.to_string()));
+        }
+        let flags = next()? as u64;
+        let by_offset = (flags & value::RELOCATION_GROUPED_BY_OFFSET_DELTA_FLAG) != 0;
+        let by_info = (flags & value::RELOCATION_GROUPED_BY_INFO_FLAG) != 0;
+        let by_addend = (flags & value::RELOCATION_GROUPED_BY_ADDEND_FLAG) != 0;
+        let has_addend = (flags & value::RELOCATION_GROUP_HAS_ADDEND_FLAG) != 0;
+
+        let offset_delta = if by_offset { next()? as u64 } else { 0 };
+        if by_info {
+            r_info = next()? as u64;
+        }
+        if has_addend && by_addend {
+            r_addend = r_addend.wrapping_add(next()?);
+        } else if !has_addend {
+            r_addend = 0;
+        }
+
+        for _ in 0..group_size {
+            r_offset = r_offset.wrapping_add(if by_offset { offset_delta } else { next()? as u64 });
+            if !by_info {
+                r_info = next()? as u64;
+            }
+            if has_addend && !by_addend {
+                r_addend = r_addend.wrapping_add(next()?);
+            }
+            // widen r_info of ELF32 as the plain loaders do
+            let info = match class {
+                value::ELFCLASS32 => value::relocation64_info(value::relocation_symbol(r_info as u32),
+                    value::relocation_type(r_info as u32)),
+                _ => r_info,
+            };
+            entries.push(elf64::RelaEntry { r_offset, r_info: info, r_addend });
+        }
+    }
+    Ok(entries)
+}
+
+// DT_RELR table, each word is an address or a bitmap of the words following it.
+// Entries are relative relocations of rel_type with addends in place.
+pub fn decode_relr(data: &[u8], endian: Endian, class: u8, rel_type: u32) -> Vec<elf64::RelEntry> {
+    let word_size: u64 = if class == value::ELFCLASS32 { 4 } else { 8 };
+    let r_info = value::relocation64_info(0, rel_type);
+    let mut cursor = data;
+    let mut entries: Vec<elf64::RelEntry> = Vec::new();
+    let mut base: u64 = 0;
+    while cursor.len() as u64 >= word_size {
+        let word = match word_size {
+            4 => endian.read_u32(&mut cursor) as u64,
+            _ => endian.read_u64(&mut cursor),
+        };
+        if (word & 1) == 0 {
+            entries.push(elf64::RelEntry { r_offset: word, r_info });
+            base = word.wrapping_add(word_size);
+            continue;
+        }
+        // bit n marks the word at base + (n - 1) words
+        let bits = word_size * 8 - 1;
+        for n in 1..=bits {
+            if ((word >> n) & 1) != 0 {
+                entries.push(elf64::RelEntry { r_offset: base.wrapping_add((n - 1) * word_size), r_info });
+            }
+        }
+        base = base.wrapping_add(bits * word_size);
+    }
+    entries
+}
+
+// readelf name of relocation type
+pub fn type_name(machine: u16, rel_type: u32) -> Option<&'static str> {
+    let names: &[(u32, &'static str)] = match machine {
+        value::EM_386 => I386_NAMES,
+        value::EM_X86_64 => X86_64_NAMES,
+        value::EM_ARM => ARM_NAMES,
+        value::EM_AARCH64 => AARCH64_NAMES,
+        value::EM_MIPS | value::EM_MIPS_RS4_BE => MIPS_NAMES,
+        value::EM_RISCV => RISCV_NAMES,
+        _ => return None,
+    };
+    names.iter().find(|(n, _)| *n == rel_type).map(|(_, name)| *name)
+}
+
+const I386_NAMES: &[(u32, &str)] = &[
+    (0, "R_386_NONE"),
+    (1, "R_386_32"),
+    (2, "R_386_PC32"),
+    (3, "R_386_GOT32"),
+    (4, "R_386_PLT32"),
+    (5, "R_386_COPY"),
+    (6, "R_386_GLOB_DAT"),
+    (7, "R_386_JUMP_SLOT"),
+    (8, "R_386_RELATIVE"),
+    (9, "R_386_GOTOFF"),
+    (10, "R_386_GOTPC"),
+    (11, "R_386_32PLT"),
+    (14, "R_386_TLS_TPOFF"),
+    (15, "R_386_TLS_IE"),
+    (16, "R_386_TLS_GOTIE"),
+    (17, "R_386_TLS_LE"),
+    (18, "R_386_TLS_GD"),
+    (19, "R_386_TLS_LDM"),
+    (20, "R_386_16"),
+    (21, "R_386_PC16"),
+    (22, "R_386_8"),
+    (23, "R_386_PC8"),
+    (24, "R_386_TLS_GD_32"),
+    (25, "R_386_TLS_GD_PUSH"),
+    (26, "R_386_TLS_GD_CALL"),
+    (27, "R_386_TLS_GD_POP"),
+    (28, "R_386_TLS_LDM_32"),
+    (29, "R_386_TLS_LDM_PUSH"),
+    (30, "R_386_TLS_LDM_CALL"),
+    (31, "R_386_TLS_LDM_POP"),
+    (32, "R_386_TLS_LDO_32"),
+    (33, "R_386_TLS_IE_32"),
+    (34, "R_386_TLS_LE_32"),
+    (35, "R_386_TLS_DTPMOD32"),
+    (36, "R_386_TLS_DTPOFF32"),
+    (37, "R_386_TLS_TPOFF32"),
+    (38, "R_386_SIZE32"),
+    (39, "R_386_TLS_GOTDESC"),
+    (40, "R_386_TLS_DESC_CALL"),
+    (41, "R_386_TLS_DESC"),
+    (42, "R_386_IRELATIVE"),
+    (43, "R_386_GOT32X"),
+];
+
+const X86_64_NAMES: &[(u32, &str)] = &[
+    (0, "R_X86_64_NONE"),
+    (1, "R_X86_64_64"),
+    (2, "R_X86_64_PC32"),
+    (3, "R_X86_64_GOT32"),
+    (4, "R_X86_64_PLT32"),
+    (5, "R_X86_64_COPY"),
+    (6, "R_X86_64_GLOB_DAT"),
+    (7, "R_X86_64_JUMP_SLOT"),
+    (8, "R_X86_64_RELATIVE"),
+    (9, "R_X86_64_GOTPCREL"),
+    (10, "R_X86_64_32"),
+    (11, "R_X86_64_32S"),
+    (12, "R_X86_64_16"),
+    (13, "R_X86_64_PC16"),
+    (14, "R_X86_64_8"),
+    (15, "R_X86_64_PC8"),
+    (16, "R_X86_64_DTPMOD64"),
+    (17, "R_X86_64_DTPOFF64"),
+    (18, "R_X86_64_TPOFF64"),
+    (19, "R_X86_64_TLSGD"),
+    (20, "R_X86_64_TLSLD"),
+    (21, "R_X86_64_DTPOFF32"),
+    (22, "R_X86_64_GOTTPOFF"),
+    (23, "R_X86_64_TPOFF32"),
+    (24, "R_X86_64_PC64"),
+    (25, "R_X86_64_GOTOFF64"),
+    (26, "R_X86_64_GOTPC32"),
+    (27, "R_X86_64_GOT64"),
+    (28, "R_X86_64_GOTPCREL64"),
+    (29, "R_X86_64_GOTPC64"),
+    (30, "R_X86_64_GOTPLT64"),
+    (31, "R_X86_64_PLTOFF64"),
+    (32, "R_X86_64_SIZE32"),
+    (33, "R_X86_64_SIZE64"),
+    (34, "R_X86_64_GOTPC32_TLSDESC"),
+    (35, "R_X86_64_TLSDESC_CALL"),
+    (36, "R_X86_64_TLSDESC"),
+    (37, "R_X86_64_IRELATIVE"),
+    (38, "R_X86_64_RELATIVE64"),
+    (41, "R_X86_64_GOTPCRELX"),
+    (42, "R_X86_64_REX_GOTPCRELX"),
+];
+
+const ARM_NAMES: &[(u32, &str)] = &[
+    (0, "R_ARM_NONE"),
+    (1, "R_ARM_PC24"),
+    (2, "R_ARM_ABS32"),
+    (3, "R_ARM_REL32"),
+    (4, "R_ARM_LDR_PC_G0"),
+    (5, "R_ARM_ABS16"),
+    (6, "R_ARM_ABS12"),
+    (7, "R_ARM_THM_ABS5"),
+    (8, "R_ARM_ABS8"),
+    (9, "R_ARM_SBREL32"),
+    (10, "R_ARM_THM_CALL"),
+    (11, "R_ARM_THM_PC8"),
+    (12, "R_ARM_BREL_ADJ"),
+    (13, "R_ARM_TLS_DESC"),
+    (14, "R_ARM_THM_SWI8"),
+    (15, "R_ARM_XPC25"),
+    (16, "R_ARM_THM_XPC22"),
+    (17, "R_ARM_TLS_DTPMOD32"),
+    (18, "R_ARM_TLS_DTPOFF32"),
+    (19, "R_ARM_TLS_TPOFF32"),
+    (20, "R_ARM_COPY"),
+    (21, "R_ARM_GLOB_DAT"),
+    (22, "R_ARM_JUMP_SLOT"),
+    (23, "R_ARM_RELATIVE"),
+    (24, "R_ARM_GOTOFF32"),
+    (25, "R_ARM_BASE_PREL"),
+    (26, "R_ARM_GOT_BREL"),
+    (27, "R_ARM_PLT32"),
+    (28, "R_ARM_CALL"),
+    (29, "R_ARM_JUMP24"),
+    (30, "R_ARM_THM_JUMP24"),
+    (31, "R_ARM_BASE_ABS"),
+    (38, "R_ARM_TARGET1"),
+    (39, "R_ARM_SBREL31"),
+    (40, "R_ARM_V4BX"),
+    (41, "R_ARM_TARGET2"),
+    (42, "R_ARM_PREL31"),
+    (43, "R_ARM_MOVW_ABS_NC"),
+    (44, "R_ARM_MOVT_ABS"),
+    (45, "R_ARM_MOVW_PREL_NC"),
+    (46, "R_ARM_MOVT_PREL"),
+    (47, "R_ARM_THM_MOVW_ABS_NC"),
+    (48, "R_ARM_THM_MOVT_ABS"),
+    (49, "R_ARM_THM_MOVW_PREL_NC"),
+    (50, "R_ARM_THM_MOVT_PREL"),
+    (51, "R_ARM_THM_JUMP19"),
+    (52, "R_ARM_THM_JUMP6"),
+    (53, "R_ARM_THM_ALU_PREL_11_0"),
+    (54, "R_ARM_THM_PC12"),
+    (55, "R_ARM_ABS32_NOI"),
+    (56, "R_ARM_REL32_NOI"),
+    (94, "R_ARM_PLT32_ABS"),
+    (95, "R_ARM_GOT_ABS"),
+    (96, "R_ARM_GOT_PREL"),
+    (97, "R_ARM_GOT_BREL12"),
+    (98, "R_ARM_GOTOFF12"),
+    (99, "R_ARM_GOTRELAX"),
+    (100, "R_ARM_GNU_VTENTRY"),
+    (101, "R_ARM_GNU_VTINHERIT"),
+    (102, "R_ARM_THM_JUMP11"),
+    (103, "R_ARM_THM_JUMP8"),
+    (104, "R_ARM_TLS_GD32"),
+    (105, "R_ARM_TLS_LDM32"),
+    (106, "R_ARM_TLS_LDO32"),
+    (107, "R_ARM_TLS_IE32"),
+    (108, "R_ARM_TLS_LE32"),
+    (109, "R_ARM_TLS_LDO12"),
+    (110, "R_ARM_TLS_LE12"),
+    (111, "R_ARM_TLS_IE12GP"),
+    (160, "R_ARM_IRELATIVE"),
+];
+
+const AARCH64_NAMES: &[(u32, &str)] = &[
+    (0, "R_AARCH64_NONE"),
+    (257, "R_AARCH64_ABS64"),
+    (258, "R_AARCH64_ABS32"),
+    (259, "R_AARCH64_ABS16"),
+    (260, "R_AARCH64_PREL64"),
+    (261, "R_AARCH64_PREL32"),
+    (262, "R_AARCH64_PREL16"),
+    (263, "R_AARCH64_MOVW_UABS_G0"),
+    (264, "R_AARCH64_MOVW_UABS_G0_NC"),
+    (265, "R_AARCH64_MOVW_UABS_G1"),
+    (266, "R_AARCH64_MOVW_UABS_G1_NC"),
+    (267, "R_AARCH64_MOVW_UABS_G2"),
+    (268, "R_AARCH64_MOVW_UABS_G2_NC"),
+    (269, "R_AARCH64_MOVW_UABS_G3"),
+    (270, "R_AARCH64_MOVW_SABS_G0"),
+    (271, "R_AARCH64_MOVW_SABS_G1"),
+    (272, "R_AARCH64_MOVW_SABS_G2"),
+    (273, "R_AARCH64_LD_PREL_LO19"),
+    (274, "R_AARCH64_ADR_PREL_LO21"),
+    (275, "R_AARCH64_ADR_PREL_PG_HI21"),
+    (276, "R_AARCH64_ADR_PREL_PG_HI21_NC"),
+    (277, "R_AARCH64_ADD_ABS_LO12_NC"),
+    (278, "R_AARCH64_LDST8_ABS_LO12_NC"),
+    (279, "R_AARCH64_TSTBR14"),
+    (280, "R_AARCH64_CONDBR19"),
+    (282, "R_AARCH64_JUMP26"),
+    (283, "R_AARCH64_CALL26"),
+    (284, "R_AARCH64_LDST16_ABS_LO12_NC"),
+    (285, "R_AARCH64_LDST32_ABS_LO12_NC"),
+    (286, "R_AARCH64_LDST64_ABS_LO12_NC"),
+    (287, "R_AARCH64_MOVW_PREL_G0"),
+    (288, "R_AARCH64_MOVW_PREL_G0_NC"),
+    (289, "R_AARCH64_MOVW_PREL_G1"),
+    (290, "R_AARCH64_MOVW_PREL_G1_NC"),
+    (291, "R_AARCH64_MOVW_PREL_G2"),
+    (292, "R_AARCH64_MOVW_PREL_G2_NC"),
+    (293, "R_AARCH64_MOVW_PREL_G3"),
+    (299, "R_AARCH64_LDST128_ABS_LO12_NC"),
+    (300, "R_AARCH64_MOVW_GOTOFF_G0"),
+    (301, "R_AARCH64_MOVW_GOTOFF_G0_NC"),
+    (302, "R_AARCH64_MOVW_GOTOFF_G1"),
+    (303, "R_AARCH64_MOVW_GOTOFF_G1_NC"),
+    (304, "R_AARCH64_MOVW_GOTOFF_G2"),
+    (305, "R_AARCH64_MOVW_GOTOFF_G2_NC"),
+    (306, "R_AARCH64_MOVW_GOTOFF_G3"),
+    (307, "R_AARCH64_GOTREL64"),
+    (308, "R_AARCH64_GOTREL32"),
+    (309, "R_AARCH64_GOT_LD_PREL19"),
+    (310, "R_AARCH64_LD64_GOTOFF_LO15"),
+    (311, "R_AARCH64_ADR_GOT_PAGE"),
+    (312, "R_AARCH64_LD64_GOT_LO12_NC"),
+    (313, "R_AARCH64_LD64_GOTPAGE_LO15"),
+    (512, "R_AARCH64_TLSGD_ADR_PREL21"),
+    (513, "R_AARCH64_TLSGD_ADR_PAGE21"),
+    (514, "R_AARCH64_TLSGD_ADD_LO12_NC"),
+    (539, "R_AARCH64_TLSIE_MOVW_GOTTPREL_G1"),
+    (540, "R_AARCH64_TLSIE_MOVW_GOTTPREL_G0_NC"),
+    (541, "R_AARCH64_TLSIE_ADR_GOTTPREL_PAGE21"),
+    (542, "R_AARCH64_TLSIE_LD64_GOTTPREL_LO12_NC"),
+    (543, "R_AARCH64_TLSIE_LD_GOTTPREL_PREL19"),
+    (544, "R_AARCH64_TLSLE_MOVW_TPREL_G2"),
+    (545, "R_AARCH64_TLSLE_MOVW_TPREL_G1"),
+    (546, "R_AARCH64_TLSLE_MOVW_TPREL_G1_NC"),
+    (547, "R_AARCH64_TLSLE_MOVW_TPREL_G0"),
+    (548, "R_AARCH64_TLSLE_MOVW_TPREL_G0_NC"),
+    (549, "R_AARCH64_TLSLE_ADD_TPREL_HI12"),
+    (550, "R_AARCH64_TLSLE_ADD_TPREL_LO12"),
+    (551, "R_AARCH64_TLSLE_ADD_TPREL_LO12_NC"),
+    (560, "R_AARCH64_TLSDESC_LD_PREL19"),
+    (561, "R_AARCH64_TLSDESC_ADR_PREL21"),
+    (562, "R_AARCH64_TLSDESC_ADR_PAGE21"),
+    (563, "R_AARCH64_TLSDESC_LD64_LO12"),
+    (564, "R_AARCH64_TLSDESC_ADD_LO12"),
+    (565, "R_AARCH64_TLSDESC_OFF_G1"),
+    (566, "R_AARCH64_TLSDESC_OFF_G0_NC"),
+    (567, "R_AARCH64_TLSDESC_LDR"),
+    (568, "R_AARCH64_TLSDESC_ADD"),
+    (569, "R_AARCH64_TLSDESC_CALL"),
+    (1024, "R_AARCH64_COPY"),
+    (1025, "R_AARCH64_GLOB_DAT"),
+    (1026, "R_AARCH64_JUMP_SLOT"),
+    (1027, "R_AARCH64_RELATIVE"),
+    (1028, "R_AARCH64_TLS_DTPMOD64"),
+    (1029, "R_AARCH64_TLS_DTPREL64"),
+    (1030, "R_AARCH64_TLS_TPREL64"),
+    (1031, "R_AARCH64_TLSDESC"),
+    (1032, "R_AARCH64_IRELATIVE"),
+];
+
+const MIPS_NAMES: &[(u32, &str)] = &[
+    (0, "R_MIPS_NONE"),
+    (1, "R_MIPS_16"),
+    (2, "R_MIPS_32"),
+    (3, "R_MIPS_REL32"),
+    (4, "R_MIPS_26"),
+    (5, "R_MIPS_HI16"),
+    (6, "R_MIPS_LO16"),
+    (7, "R_MIPS_GPREL16"),
+    (8, "R_MIPS_LITERAL"),
+    (9, "R_MIPS_GOT16"),
+    (10, "R_MIPS_PC16"),
+    (11, "R_MIPS_CALL16"),
+    (12, "R_MIPS_GPREL32"),
+    (16, "R_MIPS_SHIFT5"),
+    (17, "R_MIPS_SHIFT6"),
+    (18, "R_MIPS_64"),
+    (19, "R_MIPS_GOT_DISP"),
+    (20, "R_MIPS_GOT_PAGE"),
+    (21, "R_MIPS_GOT_OFST"),
+    (22, "R_MIPS_GOT_HI16"),
+    (23, "R_MIPS_GOT_LO16"),
+    (24, "R_MIPS_SUB"),
+    (25, "R_MIPS_INSERT_A"),
+    (26, "R_MIPS_INSERT_B"),
+    (27, "R_MIPS_DELETE"),
+    (28, "R_MIPS_HIGHER"),
+    (29, "R_MIPS_HIGHEST"),
+    (30, "R_MIPS_CALL_HI16"),
+    (31, "R_MIPS_CALL_LO16"),
+    (32, "R_MIPS_SCN_DISP"),
+    (33, "R_MIPS_REL16"),
+    (34, "R_MIPS_ADD_IMMEDIATE"),
+    (35, "R_MIPS_PJUMP"),
+    (36, "R_MIPS_RELGOT"),
+    (37, "R_MIPS_JALR"),
+    (38, "R_MIPS_TLS_DTPMOD32"),
+    (39, "R_MIPS_TLS_DTPREL32"),
+    (40, "R_MIPS_TLS_DTPMOD64"),
+    (41, "R_MIPS_TLS_DTPREL64"),
+    (42, "R_MIPS_TLS_GD"),
+    (43, "R_MIPS_TLS_LDM"),
+    (44, "R_MIPS_TLS_DTPREL_HI16"),
+    (45, "R_MIPS_TLS_DTPREL_LO16"),
+    (46, "R_MIPS_TLS_GOTTPREL"),
+    (47, "R_MIPS_TLS_TPREL32"),
+    (48, "R_MIPS_TLS_TPREL64"),
+    (49, "R_MIPS_TLS_TPREL_HI16"),
+    (50, "R_MIPS_TLS_TPREL_LO16"),
+    (51, "R_MIPS_GLOB_DAT"),
+    (126, "R_MIPS_COPY"),
+    (127, "R_MIPS_JUMP_SLOT"),
+];
+
+const RISCV_NAMES: &[(u32, &str)] = &[
+    (0, "R_RISCV_NONE"),
+    (1, "R_RISCV_32"),
+    (2, "R_RISCV_64"),
+    (3, "R_RISCV_RELATIVE"),
+    (4, "R_RISCV_COPY"),
+    (5, "R_RISCV_JUMP_SLOT"),
+    (6, "R_RISCV_TLS_DTPMOD32"),
+    (7, "R_RISCV_TLS_DTPMOD64"),
+    (8, "R_RISCV_TLS_DTPREL32"),
+    (9, "R_RISCV_TLS_DTPREL64"),
+    (10, "R_RISCV_TLS_TPREL32"),
+    (11, "R_RISCV_TLS_TPREL64"),
+    (12, "R_RISCV_TLSDESC"),
+    (16, "R_RISCV_BRANCH"),
+    (17, "R_RISCV_JAL"),
+    (18, "R_RISCV_CALL"),
+    (19, "R_RISCV_CALL_PLT"),
+    (20, "R_RISCV_GOT_HI20"),
+    (21, "R_RISCV_TLS_GOT_HI20"),
+    (22, "R_RISCV_TLS_GD_HI20"),
+    (23, "R_RISCV_PCREL_HI20"),
+    (24, "R_RISCV_PCREL_LO12_I"),
+    (25, "R_RISCV_PCREL_LO12_S"),
+    (26, "R_RISCV_HI20"),
+    (27, "R_RISCV_LO12_I"),
+    (28, "R_RISCV_LO12_S"),
+    (29, "R_RISCV_TPREL_HI20"),
+    (30, "R_RISCV_TPREL_LO12_I"),
+    (31, "R_RISCV_TPREL_LO12_S"),
+    (32, "R_RISCV_TPREL_ADD"),
+    (33, "R_RISCV_ADD8"),
+    (34, "R_RISCV_ADD16"),
+    (35, "R_RISCV_ADD32"),
+    (36, "R_RISCV_ADD64"),
+    (37, "R_RISCV_SUB8"),
+    (38, "R_RISCV_SUB16"),
+    (39, "R_RISCV_SUB32"),
+    (40, "R_RISCV_SUB64"),
+    (41, "R_RISCV_GNU_VTINHERIT"),
+    (42, "R_RISCV_GNU_VTENTRY"),
+    (43, "R_RISCV_ALIGN"),
+    (44, "R_RISCV_RVC_BRANCH"),
+    (45, "R_RISCV_RVC_JUMP"),
+    (46, "R_RISCV_RVC_LUI"),
+    (51, "R_RISCV_RELAX"),
+    (52, "R_RISCV_SUB6"),
+    (53, "R_RISCV_SET6"),
+    (54, "R_RISCV_SET8"),
+    (55, "R_RISCV_SET16"),
+    (56, "R_RISCV_SET32"),
+    (57, "R_RISCV_32_PCREL"),
+    (58, "R_RISCV_IRELATIVE"),
+    (59, "R_RISCV_PLT32"),
+    (60, "R_RISCV_SET_ULEB128"),
+    (61, "R_RISCV_SUB_ULEB128"),
+];
@@ -0,0 +1,235 @@
+use std::fmt;
+use crate::{
+    elf::{endian::Endian, value},
+    error::{Error, Result},
+};
+
+
+// decoded sh_type, processor-specific values depend on e_machine
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SectionType {
+    Null,
+    Progbits,
+    Symtab,
+    Strtab,
+    Rela,
+    Hash,
+    Dynamic,
+    Note,
+    Nobits,
+    Rel,
+    Shlib,
+    Dynsym,
+    InitArray,
+    FiniArray,
+    PreinitArray,
+    Group,
+    SymtabShndx,
+    Relr,
+    AndroidRel,
+    AndroidRela,
+    AndroidRelr,
+    GnuAttributes,
+    GnuHash,
+    GnuLiblist,
+    Checksum,
+    GnuVerdef,
+    GnuVerneed,
+    GnuVersym,
+    ArmExidx,
+    ArmPreemptmap,
+    ArmAttributes,
+    ArmDebugoverlay,
+    ArmOverlaysection,
+    X86_64Unwind,
+    Os(u32),
+    Processor(u32),
+    User(u32),
+    Other(u32),
+}
+
+// sh_flags, shown with the key letters of readelf -S
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SectionFlags {
+    pub bits: u64,
+    // processor-specific flags are told apart by e_machine
+    pub machine: u16,
+}
+
+// SHT_GROUP section: signature, flags and indices of member sections
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Group {
+    // index of the SHT_GROUP section
+    pub index: usize,
+    // name of the symbol of sh_info, of its section for STT_SECTION
+    pub signature: String,
+    pub flags: u32,
+    pub members: Vec<u32>,
+}
+
+impl SectionType {
+    pub fn new(sh_type: u32, machine: u16) -> SectionType {
+        match (sh_type, machine) {
+            (value::SHT_NULL, _) => SectionType::Null,
+            (value::SHT_PROGBITS, _) => SectionType::Progbits,
+            (value::SHT_SYMTAB, _) => SectionType::Symtab,
+            (value::SHT_STRTAB, _) => SectionType::Strtab,
+            (value::SHT_RELA, _) => SectionType::Rela,
+            (value::SHT_HASH, _) => SectionType::Hash,
+            (value::SHT_DYNAMIC, _) => SectionType::Dynamic,
+            (value::SHT_NOTE, _) => SectionType::Note,
+            (value::SHT_NOBITS, _) => SectionType::Nobits,
+            (value::SHT_REL, _) => SectionType::Rel,
+            (value::SHT_SHLIB, _) => SectionType::Shlib,
+            (value::SHT_DYNSYM, _) => SectionType::Dynsym,
+            (value::SHT_INIT_ARRAY, _) => SectionType::InitArray,
+            (value::SHT_FINI_ARRAY, _) => SectionType::FiniArray,
+            (value::SHT_PREINIT_ARRAY, _) => SectionType::PreinitArray,
+            (value::SHT_GROUP, _) => SectionType::Group,
+            (value::SHT_SYMTAB_SHNDX, _) => SectionType::SymtabShndx,
+            (value::SHT_RELR, _) => SectionType::Relr,
+            (value::SHT_ANDROID_REL, _) => SectionType::AndroidRel,
+            (value::SHT_ANDROID_RELA, _) => SectionType::AndroidRela,
+            (value::SHT_ANDROID_RELR, _) => SectionType::AndroidRelr,
+            (value::SHT_GNU_ATTRIBUTES, _) => SectionType::GnuAttributes,
+            (value::SHT_GNU_HASH, _) => SectionType::GnuHash,
+            (value::SHT_GNU_LIBLIST, _) => SectionType::GnuLiblist,
+            (value::SHT_CHECKSUM, _) => SectionType::Checksum,
+            (value::SHT_GNU_VERDEF, _) => SectionType::GnuVerdef,
+            (value::SHT_GNU_VERNEED, _) => SectionType::GnuVerneed,
+            (value::SHT_GNU_VERSYM, _) => SectionType::GnuVersym,
+            (value::SHT_ARM_EXIDX, value::EM_ARM) => SectionType::ArmExidx,
+            (value::SHT_ARM_PREEMPTMAP, value::EM_ARM) => SectionType::ArmPreemptmap,
+            (value::SHT_ARM_ATTRIBUTES, value::EM_ARM) => SectionType::ArmAttributes,
+            (value::SHT_ARM_DEBUGOVERLAY, value::EM_ARM) => SectionType::ArmDebugoverlay,
+            (value::SHT_ARM_OVERLAYSECTION, value::EM_ARM) => SectionType::ArmOverlaysection,
+            (value::SHT_X86_64_UNWIND, value::EM_X86_64) => SectionType::X86_64Unwind,
+            (n, _) if (value::SHT_LOOS..=value::SHT_HIOS).contains(&n) => SectionType::Os(n),
+            (n, _) if (value::SHT_LOPROC..=value::SHT_HIPROC).contains(&n) => SectionType::Processor(n),
+            (n, _) if n >= value::SHT_LOUSER => SectionType::User(n),
+            (n, _) => SectionType::Other(n),
+        }
+    }
+}
+
+impl SectionFlags {
+    pub fn new(sh_flags: u64, machine: u16) -> SectionFlags {
+        SectionFlags { bits: sh_flags, machine }
+    }
+
+    pub fn contains(&self, flag: u32) -> bool {
+        (self.bits & flag as u64) != 0
+    }
+
+    // known flags with their key letter, in order of bits as readelf shows them
+    fn keys(&self) -> Vec<(u32, char)> {
+        let mut keys = vec![
+            (value::SHF_WRITE, 'W'),
+            (value::SHF_ALLOC, 'A'),
+            (value::SHF_EXECINSTR, 'X'),
+            (value::SHF_MERGE, 'M'),
+            (value::SHF_STRINGS, 'S'),
+            (value::SHF_INFO_LINK, 'I'),
+            (value::SHF_LINK_ORDER, 'L'),
+            (value::SHF_OS_NONCONFORMING, 'O'),
+            (value::SHF_GROUP, 'G'),
+            (value::SHF_TLS, 'T'),
+            (value::SHF_COMPRESSED, 'C'),
+            (value::SHF_GNU_RETAIN, 'R'),
+        ];
+        match self.machine {
+            value::EM_X86_64 => keys.push((value::SHF_X86_64_LARGE, 'l')),
+            value::EM_ARM => keys.push((value::SHF_ARM_PURECODE, 'y')),
+            _ => { },
+        }
+        keys.push((value::SHF_EXCLUDE, 'E'));
+        keys
+    }
+}
+
+impl Group {
+    // words of section data in file byte order: flags, then member section indices
+    pub fn parse(index: usize, signature: String, data: &[u8], endian: Endian) -> Result<Group> {
+        if data.len() < 4 || !data.len().is_multiple_of(4) {
+            return Err(Error::invalid("SHT_GROUP", format!("size {:#x} of section {}", data.len(), index)));
+        }
+        let mut cursor = data;
+        let flags = endian.read_u32(&mut cursor);
+        let members = (0..cursor.len() / 4).map(|_| endian.read_u32(&mut cursor)).collect();
+        Ok(Group { index, signature, flags, members })
+    }
+
+    // linker keeps one group of each signature and drops the others
+    pub fn is_comdat(&self) -> bool {
+        (self.flags & value::GRP_COMDAT) != 0
+    }
+}
+
+impl fmt::Display for SectionType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SectionType::Null => write!(f, "NULL"),
+            SectionType::Progbits => write!(f, "PROGBITS"),
+            SectionType::Symtab => write!(f, "SYMTAB"),
+            SectionType::Strtab => write!(f, "STRTAB"),
+            SectionType::Rela => write!(f, "RELA"),
+            SectionType::Hash => write!(f, "HASH"),
+            SectionType::Dynamic => write!(f, "DYNAMIC"),
+            SectionType::Note => write!(f, "NOTE"),
+            SectionType::Nobits => write!(f, "NOBITS"),
+            SectionType::Rel => write!(f, "REL"),
+            SectionType::Shlib => write!(f, "SHLIB"),
+            SectionType::Dynsym => write!(f, "DYNSYM"),
+            SectionType::InitArray => write!(f, "INIT_ARRAY"),
+            SectionType::FiniArray => write!(f, "FINI_ARRAY"),
+            SectionType::PreinitArray => write!(f, "PREINIT_ARRAY"),
+            SectionType::Group => write!(f, "GROUP"),
+            SectionType::SymtabShndx => write!(f, "SYMTAB SECTION INDICES"),
+            SectionType::Relr => write!(f, "RELR"),
+            SectionType::AndroidRel => write!(f, "ANDROID_REL"),
+            SectionType::AndroidRela => write!(f, "ANDROID_RELA"),
+            SectionType::AndroidRelr => write!(f, "ANDROID_RELR"),
+            SectionType::GnuAttributes => write!(f, "GNU_ATTRIBUTES"),
+            SectionType::GnuHash => write!(f, "GNU_HASH"),
+            SectionType::GnuLiblist => write!(f, "GNU_LIBLIST"),
+            SectionType::Checksum => write!(f, "CHECKSUM"),
+            SectionType::GnuVerdef => write!(f, "VERDEF"),
+            SectionType::GnuVerneed => write!(f, "VERNEED"),
+            SectionType::GnuVersym => write!(f, "VERSYM"),
+            SectionType::ArmExidx => write!(f, "ARM_EXIDX"),
+            SectionType::ArmPreemptmap => write!(f, "ARM_PREEMPTMAP"),
+            SectionType::ArmAttributes => write!(f, "ARM_ATTRIBUTES"),
+            SectionType::ArmDebugoverlay => write!(f, "ARM_DEBUGOVERLAY"),
+            SectionType::ArmOverlaysection => write!(f, "ARM_OVERLAYSECTION"),
+            SectionType::X86_64Unwind => write!(f, "X86_64_UNWIND"),
+            SectionType::Os(n) => write!(f, "LOOS+{:#x}", n - value::SHT_LOOS),
+            SectionType::Processor(n) => write!(f, "LOPROC+{:#x}", n - value::SHT_LOPROC),
+            SectionType::User(n) => write!(f, "LOUSER+{:#x}", n - value::SHT_LOUSER),
+            SectionType::Other(n) => write!(f, "<unknown>: {:#x}", n),
+        }
+    }
+}
+
+// bits without a key are o for OS, p for processor and x for the rest
+impl fmt::Display for SectionFlags {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut known: u64 = 0;
+        for (flag, key) in self.keys() {
+            if self.contains(flag) {
+                write!(f, "{}", key)?;
+            }
+            known |= flag as u64;
+        }
+        let rest = self.bits & !known;
+        if (rest & value::SHF_MASKOS as u64) != 0 {
+            write!(f, "o")?;
+        }
+        if (rest & value::SHF_MASKPROC as u64) != 0 {
+            write!(f, "p")?;
+        }
+        if (rest & !(value::SHF_MASKOS | value::SHF_MASKPROC) as u64) != 0 {
+            write!(f, "x")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,144 @@
+use std::fmt;
+use crate::elf::{elf64, value};
+
+
+// decoded p_type, processor-specific values depend on e_machine
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SegmentType {
+    Null,
+    Load,
+    Dynamic,
+    Interp,
+    Note,
+    Shlib,
+    Phdr,
+    Tls,
+    GnuEhFrame,
+    GnuStack,
+    GnuRelro,
+    GnuProperty,
+    ArmExidx,
+    Os(u32),
+    Processor(u32),
+    Other(u32),
+}
+
+// p_flags, shown as readelf does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SegmentFlags(pub u32);
+
+impl SegmentType {
+    pub fn new(p_type: u32, machine: u16) -> SegmentType {
+        match p_type {
+            value::PT_NULL => SegmentType::Null,
+            value::PT_LOAD => SegmentType::Load,
+            value::PT_DYNAMIC => SegmentType::Dynamic,
+            value::PT_INTERP => SegmentType::Interp,
+            value::PT_NOTE => SegmentType::Note,
+            value::PT_SHLIB => SegmentType::Shlib,
+            value::PT_PHDR => SegmentType::Phdr,
+            value::PT_TLS => SegmentType::Tls,
+            value::PT_GNU_EH_FRAME => SegmentType::GnuEhFrame,
+            value::PT_GNU_STACK => SegmentType::GnuStack,
+            value::PT_GNU_RELRO => SegmentType::GnuRelro,
+            value::PT_GNU_PROPERTY => SegmentType::GnuProperty,
+            value::PT_ARM_EXIDX if machine == value::EM_ARM => SegmentType::ArmExidx,
+            n if (value::PT_LOOS..=value::PT_HIOS).contains(&n) => SegmentType::Os(n),
+            n if (value::PT_LOPROC..=value::PT_HIPROC).contains(&n) => SegmentType::Processor(n),
+            n => SegmentType::Other(n),
+        }
+    }
+}
+
+impl SegmentFlags {
+    pub fn readable(&self) -> bool {
+        (self.0 & value::PF_R) != 0
+    }
+
+    pub fn writable(&self) -> bool {
+        (self.0 & value::PF_W) != 0
+    }
+
+    pub fn executable(&self) -> bool {
+        (self.0 & value::PF_X) != 0
+    }
+}
+
+impl fmt::Display for SegmentType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SegmentType::Null => write!(f, "NULL"),
+            SegmentType::Load => write!(f, "LOAD"),
+            SegmentType::Dynamic => write!(f, "DYNAMIC"),
+            SegmentType::Interp => write!(f, "INTERP"),
+            SegmentType::Note => write!(f, "NOTE"),
+            SegmentType::Shlib => write!(f, "SHLIB"),
+            SegmentType::Phdr => write!(f, "PHDR"),
+            SegmentType::Tls => write!(f, "TLS"),
+            SegmentType::GnuEhFrame => write!(f, "GNU_EH_FRAME"),
+            SegmentType::GnuStack => write!(f, "GNU_STACK"),
+            SegmentType::GnuRelro => write!(f, "GNU_RELRO"),
+            SegmentType::GnuProperty => write!(f, "GNU_PROPERTY"),
+            SegmentType::ArmExidx => write!(f, "EXIDX"),
+            SegmentType::Os(n) => write!(f, "LOOS+{:#x}", n - value::PT_LOOS),
+            SegmentType::Processor(n) => write!(f, "LOPROC+{:#x}", n - value::PT_LOPROC),
+            SegmentType::Other(n) => write!(f, "<unknown>: {:#x}", n),
+        }
+    }
+}
+
+impl fmt::Display for SegmentFlags {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}{}",
+            if self.readable() { 'R' } else { ' ' },
+            if self.writable() { 'W' } else { ' ' },
+            if self.executable() { 'E' } else { ' ' })?;
+        match self.0 & !(value::PF_R | value::PF_W | value::PF_X) {
+            0 => Ok(()),
+            n => write!(f, " {:#x}", n),
+        }
+    }
+}
+
+// Whether section is part of segment as readelf -l shows it: by file offset
+// and address both, .tbss only in PT_TLS, sections without SHF_ALLOC never in
+// segments the loader maps, empty sections not at the edges of PT_DYNAMIC and PT_NOTE.
+pub fn section_in_segment(shdr: &elf64::SectionHeader, phdr: &elf64::ProgramHeader) -> bool {
+    let tls = (shdr.sh_flags & value::SHF_TLS as u64) != 0;
+    let alloc = (shdr.sh_flags & value::SHF_ALLOC as u64) != 0;
+    let nobits = shdr.sh_type == value::SHT_NOBITS;
+    if shdr.sh_type == value::SHT_NULL || (tls && nobits && phdr.p_type != value::PT_TLS) {
+        return false;
+    }
+    let segment_ok = match tls {
+        true => matches!(phdr.p_type, value::PT_TLS | value::PT_GNU_RELRO | value::PT_LOAD),
+        false => phdr.p_type != value::PT_TLS && phdr.p_type != value::PT_PHDR,
+    };
+    let mapped = matches!(phdr.p_type, value::PT_LOAD | value::PT_DYNAMIC | value::PT_GNU_EH_FRAME |
+        value::PT_GNU_STACK | value::PT_GNU_RELRO);
+    if !segment_ok || (!alloc && mapped) {
+        return false;
+    }
+    let size = shdr.sh_size;
+    let in_file = nobits || (shdr.sh_offset >= phdr.p_offset &&
+        shdr.sh_offset - phdr.p_offset <= phdr.p_filesz.wrapping_sub(1) &&
+        (shdr.sh_offset - phdr.p_offset).checked_add(size).is_some_and(|end| end <= phdr.p_filesz));
+    let in_memory = !alloc || (shdr.sh_addr >= phdr.p_vaddr &&
+        shdr.sh_addr - phdr.p_vaddr <= phdr.p_memsz.wrapping_sub(1) &&
+        (shdr.sh_addr - phdr.p_vaddr).checked_add(size).is_some_and(|end| end <= phdr.p_memsz));
+    if !in_file || !in_memory {
+        return false;
+    }
+    if (phdr.p_type != value::PT_DYNAMIC && phdr.p_type != value::PT_NOTE) || size != 0 || phdr.p_memsz == 0 {
+        return true;
+    }
+    (nobits || (shdr.sh_offset > phdr.p_offset && shdr.sh_offset - phdr.p_offset < phdr.p_filesz)) &&
+        (!alloc || (shdr.sh_addr > phdr.p_vaddr && shdr.sh_addr - phdr.p_vaddr < phdr.p_memsz))
+}
+
+// indices of sections in each segment, in order of segments
+pub fn section_mapping(segments: &[elf64::ProgramHeader], sections: &[elf64::SectionHeader]) -> Vec<Vec<usize>> {
+    segments.iter()
+        .map(|phdr| (0..sections.len()).filter(|i| section_in_segment(&sections[*i], phdr)).collect())
+        .collect()
+}
@@ -0,0 +1,114 @@
+use std::fmt;
+use crate::elf::value;
+
+
+// decoded symbol_bind(st_info)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolBind {
+    Local,
+    Global,
+    Weak,
+    GnuUnique,
+    Other(u8),
+}
+
+// decoded symbol_type(st_info)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolType {
+    NoType,
+    Object,
+    Func,
+    Section,
+    File,
+    Common,
+    Tls,
+    GnuIFunc,
+    Other(u8),
+}
+
+// decoded symbol_visibility(st_other)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolVisibility {
+    Default,
+    Internal,
+    Hidden,
+    Protected,
+}
+
+impl From<u8> for SymbolBind {
+    fn from(b: u8) -> SymbolBind {
+        match b {
+            value::STB_LOCAL => SymbolBind::Local,
+            value::STB_GLOBAL => SymbolBind::Global,
+            value::STB_WEAK => SymbolBind::Weak,
+            value::STB_GNU_UNIQUE => SymbolBind::GnuUnique,
+            n => SymbolBind::Other(n),
+        }
+    }
+}
+
+impl From<u8> for SymbolType {
+    fn from(t: u8) -> SymbolType {
+        match t {
+            value::STT_NOTYPE => SymbolType::NoType,
+            value::STT_OBJECT => SymbolType::Object,
+            value::STT_FUNC => SymbolType::Func,
+            value::STT_SECTION => SymbolType::Section,
+            value::STT_FILE => SymbolType::File,
+            value::STT_COMMON => SymbolType::Common,
+            value::STT_TLS => SymbolType::Tls,
+            value::STT_GNU_IFUNC => SymbolType::GnuIFunc,
+            n => SymbolType::Other(n),
+        }
+    }
+}
+
+impl From<u8> for SymbolVisibility {
+    fn from(v: u8) -> SymbolVisibility {
+        match value::symbol_visibility(v) {
+            value::STV_INTERNAL => SymbolVisibility::Internal,
+            value::STV_HIDDEN => SymbolVisibility::Hidden,
+            value::STV_PROTECTED => SymbolVisibility::Protected,
+            _ => SymbolVisibility::Default,
+        }
+    }
+}
+
+impl fmt::Display for SymbolBind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SymbolBind::Local => write!(f, "LOCAL"),
+            SymbolBind::Global => write!(f, "GLOBAL"),
+            SymbolBind::Weak => write!(f, "WEAK"),
+            SymbolBind::GnuUnique => write!(f, "UNIQUE"),
+            SymbolBind::Other(n) => write!(f, "<{}>", n),
+        }
+    }
+}
+
+impl fmt::Display for SymbolType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SymbolType::NoType => write!(f, "NOTYPE"),
+            SymbolType::Object => write!(f, "OBJECT"),
+            SymbolType::Func => write!(f, "FUNC"),
+            SymbolType::Section => write!(f, "SECTION"),
+            SymbolType::File => write!(f, "FILE"),
+            SymbolType::Common => write!(f, "COMMON"),
+            SymbolType::Tls => write!(f, "TLS"),
+            SymbolType::GnuIFunc => write!(f, "IFUNC"),
+            SymbolType::Other(n) => write!(f, "<{}>", n),
+        }
+    }
+}
+
+impl fmt::Display for SymbolVisibility {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SymbolVisibility::Default => write!(f, "DEFAULT"),
+            SymbolVisibility::Internal => write!(f, "INTERNAL"),
+            SymbolVisibility::Hidden => write!(f, "HIDDEN"),
+            SymbolVisibility::Protected => write!(f, "PROTECTED"),
+        }
+    }
+}
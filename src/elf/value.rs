@@ -17,6 +17,18 @@ pub const EM_88K: u16 = 5;
 pub const EM_860: u16 = 7;
 pub const EM_MIPS: u16 = 8;
 pub const EM_MIPS_RS4_BE: u16 = 10;
+pub const EM_PARISC: u16 = 15;
+pub const EM_PPC: u16 = 20;
+pub const EM_PPC64: u16 = 21;
+pub const EM_S390: u16 = 22;
+pub const EM_ARM: u16 = 40;
+pub const EM_SH: u16 = 42;
+pub const EM_SPARCV9: u16 = 43;
+pub const EM_IA_64: u16 = 50;
+pub const EM_X86_64: u16 = 62;
+pub const EM_AARCH64: u16 = 183;
+pub const EM_RISCV: u16 = 243;
+pub const EM_LOONGARCH: u16 = 258;
 
 // ELFHeader.e_version
 pub const EV_NONE: u32 = 0;
@@ -56,8 +68,12 @@ pub const SHN_LOPROC: usize = 0xFF00;
 pub const SHN_HIPROC: usize = 0xFF1F;
 pub const SHN_ABS: usize = 0xFFF1;
 pub const SHN_COMMON: usize = 0xFFF2;
+pub const SHN_XINDEX: usize = 0xFFFF;
 pub const SHN_HIRESERVE: usize = 0xFFFF;
 
+// ELFHeader.e_phnum of extended numbering, count is in sh_info of section 0
+pub const PN_XNUM: u16 = 0xFFFF;
+
 // SectionHeader.sh_type
 pub const SHT_NULL: u32 = 0;
 pub const SHT_PROGBITS: u32 = 1;
@@ -71,7 +87,31 @@ pub const SHT_NOBITS: u32 = 8;
 pub const SHT_REL: u32 = 9;
 pub const SHT_SHLIB: u32 = 10;
 pub const SHT_DYNSYM: u32 = 11;
+pub const SHT_INIT_ARRAY: u32 = 14;
+pub const SHT_FINI_ARRAY: u32 = 15;
+pub const SHT_PREINIT_ARRAY: u32 = 16;
+pub const SHT_GROUP: u32 = 17;
+pub const SHT_SYMTAB_SHNDX: u32 = 18;
+pub const SHT_RELR: u32 = 19;
+pub const SHT_LOOS: u32 = 0x60000000;
+pub const SHT_ANDROID_REL: u32 = 0x60000001;
+pub const SHT_ANDROID_RELA: u32 = 0x60000002;
+pub const SHT_ANDROID_RELR: u32 = 0x6fffff00;
+pub const SHT_GNU_ATTRIBUTES: u32 = 0x6ffffff5;
+pub const SHT_GNU_HASH: u32 = 0x6ffffff6;
+pub const SHT_GNU_LIBLIST: u32 = 0x6ffffff7;
+pub const SHT_CHECKSUM: u32 = 0x6ffffff8;
+pub const SHT_GNU_VERDEF: u32 = 0x6ffffffd;
+pub const SHT_GNU_VERNEED: u32 = 0x6ffffffe;
+pub const SHT_GNU_VERSYM: u32 = 0x6fffffff;
+pub const SHT_HIOS: u32 = 0x6fffffff;
 pub const SHT_LOPROC: u32 = 0x70000000;
+pub const SHT_ARM_EXIDX: u32 = 0x70000001;
+pub const SHT_ARM_PREEMPTMAP: u32 = 0x70000002;
+pub const SHT_ARM_ATTRIBUTES: u32 = 0x70000003;
+pub const SHT_ARM_DEBUGOVERLAY: u32 = 0x70000004;
+pub const SHT_ARM_OVERLAYSECTION: u32 = 0x70000005;
+pub const SHT_X86_64_UNWIND: u32 = 0x70000001;
 pub const SHT_HIPROC: u32 = 0x7fffffff;
 pub const SHT_LOUSER: u32 = 0x80000000;
 pub const SHT_HIUSER: u32 = 0xffffffff;
@@ -80,8 +120,52 @@ pub const SHT_HIUSER: u32 = 0xffffffff;
 pub const SHF_WRITE: u32 = 0x1;
 pub const SHF_ALLOC: u32 = 0x2;
 pub const SHF_EXECINSTR: u32 = 0x4;
+pub const SHF_MERGE: u32 = 0x10;
+pub const SHF_STRINGS: u32 = 0x20;
+pub const SHF_INFO_LINK: u32 = 0x40;
+pub const SHF_LINK_ORDER: u32 = 0x80;
+pub const SHF_OS_NONCONFORMING: u32 = 0x100;
+pub const SHF_GROUP: u32 = 0x200;
+pub const SHF_TLS: u32 = 0x400;
+pub const SHF_COMPRESSED: u32 = 0x800;
+pub const SHF_GNU_RETAIN: u32 = 0x200000;
+pub const SHF_MASKOS: u32 = 0x0FF00000;
+pub const SHF_X86_64_LARGE: u32 = 0x10000000;
+pub const SHF_ARM_PURECODE: u32 = 0x20000000;
+pub const SHF_EXCLUDE: u32 = 0x80000000;
 pub const SHF_MASKPROC: u32 = 0xF0000000;
 
+// first word of SHT_GROUP
+pub const GRP_COMDAT: u32 = 0x1;
+pub const GRP_MASKOS: u32 = 0x0FF00000;
+pub const GRP_MASKPROC: u32 = 0xF0000000;
+
+// ProgramHeader.p_type
+pub const PT_NULL: u32 = 0;
+pub const PT_LOAD: u32 = 1;
+pub const PT_DYNAMIC: u32 = 2;
+pub const PT_INTERP: u32 = 3;
+pub const PT_NOTE: u32 = 4;
+pub const PT_SHLIB: u32 = 5;
+pub const PT_PHDR: u32 = 6;
+pub const PT_TLS: u32 = 7;
+pub const PT_LOOS: u32 = 0x60000000;
+pub const PT_GNU_EH_FRAME: u32 = 0x6474e550;
+pub const PT_GNU_STACK: u32 = 0x6474e551;
+pub const PT_GNU_RELRO: u32 = 0x6474e552;
+pub const PT_GNU_PROPERTY: u32 = 0x6474e553;
+pub const PT_HIOS: u32 = 0x6fffffff;
+pub const PT_LOPROC: u32 = 0x70000000;
+pub const PT_ARM_EXIDX: u32 = 0x70000001;
+pub const PT_HIPROC: u32 = 0x7fffffff;
+
+// ProgramHeader.p_flags
+pub const PF_X: u32 = 0x1;
+pub const PF_W: u32 = 0x2;
+pub const PF_R: u32 = 0x4;
+pub const PF_MASKOS: u32 = 0x0ff00000;
+pub const PF_MASKPROC: u32 = 0xf0000000;
+
 // symbol table index
 pub const STN_UNDEF: usize = 0;
 
@@ -100,6 +184,7 @@ pub fn symbol_info(b: u8, t: u8) -> u8 {
 pub const STB_LOCAL: u8 = 0;
 pub const STB_GLOBAL: u8 = 1;
 pub const STB_WEAK: u8 = 2;
+pub const STB_GNU_UNIQUE: u8 = 10;
 pub const STB_LOPROC: u8 = 13;
 pub const STB_HIPROC: u8 = 15;
 
@@ -109,16 +194,285 @@ pub const STT_OBJECT: u8 = 1;
 pub const STT_FUNC: u8 = 2;
 pub const STT_SECTION: u8 = 3;
 pub const STT_FILE: u8 = 4;
+pub const STT_COMMON: u8 = 5;
+pub const STT_TLS: u8 = 6;
+pub const STT_GNU_IFUNC: u8 = 10;
 pub const STT_LOPROC: u8 = 13;
 pub const STT_HIPROC: u8 = 15;
 
+// symbol other macro
+pub fn symbol_visibility(o: u8) -> u8 {
+    o & 0x3
+}
+
+// symbol visibility
+pub const STV_DEFAULT: u8 = 0;
+pub const STV_INTERNAL: u8 = 1;
+pub const STV_HIDDEN: u8 = 2;
+pub const STV_PROTECTED: u8 = 3;
+
 // rel & rela macro
 pub fn relocation_symbol(i: u32) -> u32 {
     i >> 8
 }
 pub fn relocation_type(i: u32) -> u32 {
-    i & 0xF
+    i & 0xFF
 }
 pub fn relocation_info(s: u32, t: u32) -> u32 {
-    (s << 8) | (t & 0xF)
+    (s << 8) | (t & 0xFF)
 }
+
+// rel & rela macro of ELF64, r_info is split into 32-bit symbol and type
+pub fn relocation64_symbol(i: u64) -> u32 {
+    (i >> 32) as u32
+}
+pub fn relocation64_type(i: u64) -> u32 {
+    (i & 0xFFFFFFFF) as u32
+}
+pub fn relocation64_info(s: u32, t: u32) -> u64 {
+    ((s as u64) << 32) | (t as u64)
+}
+
+// DynamicEntry.d_tag
+pub const DT_NULL: i64 = 0;
+pub const DT_NEEDED: i64 = 1;
+pub const DT_PLTRELSZ: i64 = 2;
+pub const DT_PLTGOT: i64 = 3;
+pub const DT_HASH: i64 = 4;
+pub const DT_STRTAB: i64 = 5;
+pub const DT_SYMTAB: i64 = 6;
+pub const DT_RELA: i64 = 7;
+pub const DT_RELASZ: i64 = 8;
+pub const DT_RELAENT: i64 = 9;
+pub const DT_STRSZ: i64 = 10;
+pub const DT_SYMENT: i64 = 11;
+pub const DT_INIT: i64 = 12;
+pub const DT_FINI: i64 = 13;
+pub const DT_SONAME: i64 = 14;
+pub const DT_RPATH: i64 = 15;
+pub const DT_SYMBOLIC: i64 = 16;
+pub const DT_REL: i64 = 17;
+pub const DT_RELSZ: i64 = 18;
+pub const DT_RELENT: i64 = 19;
+pub const DT_PLTREL: i64 = 20;
+pub const DT_DEBUG: i64 = 21;
+pub const DT_TEXTREL: i64 = 22;
+pub const DT_JMPREL: i64 = 23;
+pub const DT_BIND_NOW: i64 = 24;
+pub const DT_INIT_ARRAY: i64 = 25;
+pub const DT_FINI_ARRAY: i64 = 26;
+pub const DT_INIT_ARRAYSZ: i64 = 27;
+pub const DT_FINI_ARRAYSZ: i64 = 28;
+pub const DT_RUNPATH: i64 = 29;
+pub const DT_FLAGS: i64 = 30;
+pub const DT_PREINIT_ARRAY: i64 = 32;
+pub const DT_PREINIT_ARRAYSZ: i64 = 33;
+pub const DT_SYMTAB_SHNDX: i64 = 34;
+pub const DT_RELRSZ: i64 = 35;
+pub const DT_RELR: i64 = 36;
+pub const DT_RELRENT: i64 = 37;
+pub const DT_LOOS: i64 = 0x6000000D;
+pub const DT_ANDROID_REL: i64 = 0x6000000F;
+pub const DT_ANDROID_RELSZ: i64 = 0x60000010;
+pub const DT_ANDROID_RELA: i64 = 0x60000011;
+pub const DT_ANDROID_RELASZ: i64 = 0x60000012;
+pub const DT_ANDROID_RELR: i64 = 0x6FFFE000;
+pub const DT_ANDROID_RELRSZ: i64 = 0x6FFFE001;
+pub const DT_ANDROID_RELRENT: i64 = 0x6FFFE003;
+pub const DT_GNU_HASH: i64 = 0x6FFFFEF5;
+pub const DT_TLSDESC_PLT: i64 = 0x6FFFFEF6;
+pub const DT_TLSDESC_GOT: i64 = 0x6FFFFEF7;
+pub const DT_VERSYM: i64 = 0x6FFFFFF0;
+pub const DT_RELACOUNT: i64 = 0x6FFFFFF9;
+pub const DT_RELCOUNT: i64 = 0x6FFFFFFA;
+pub const DT_FLAGS_1: i64 = 0x6FFFFFFB;
+pub const DT_VERDEF: i64 = 0x6FFFFFFC;
+pub const DT_VERDEFNUM: i64 = 0x6FFFFFFD;
+pub const DT_VERNEED: i64 = 0x6FFFFFFE;
+pub const DT_VERNEEDNUM: i64 = 0x6FFFFFFF;
+pub const DT_HIOS: i64 = 0x6FFFF000;
+pub const DT_LOPROC: i64 = 0x70000000;
+pub const DT_AUXILIARY: i64 = 0x7FFFFFFD;
+pub const DT_FILTER: i64 = 0x7FFFFFFF;
+pub const DT_HIPROC: i64 = 0x7FFFFFFF;
+
+// DT_FLAGS value
+pub const DF_ORIGIN: u64 = 0x1;
+pub const DF_SYMBOLIC: u64 = 0x2;
+pub const DF_TEXTREL: u64 = 0x4;
+pub const DF_BIND_NOW: u64 = 0x8;
+pub const DF_STATIC_TLS: u64 = 0x10;
+
+// DT_FLAGS_1 value
+pub const DF_1_NOW: u64 = 0x1;
+pub const DF_1_GLOBAL: u64 = 0x2;
+pub const DF_1_GROUP: u64 = 0x4;
+pub const DF_1_NODELETE: u64 = 0x8;
+pub const DF_1_LOADFLTR: u64 = 0x10;
+pub const DF_1_INITFIRST: u64 = 0x20;
+pub const DF_1_NOOPEN: u64 = 0x40;
+pub const DF_1_ORIGIN: u64 = 0x80;
+pub const DF_1_DIRECT: u64 = 0x100;
+pub const DF_1_INTERPOSE: u64 = 0x400;
+pub const DF_1_NODEFLIB: u64 = 0x800;
+pub const DF_1_NODUMP: u64 = 0x1000;
+pub const DF_1_PIE: u64 = 0x08000000;
+
+// symbol version index of SHT_GNU_VERSYM
+pub const VER_NDX_LOCAL: u16 = 0;
+pub const VER_NDX_GLOBAL: u16 = 1;
+pub const VERSYM_HIDDEN: u16 = 0x8000;
+pub const VERSYM_VERSION: u16 = 0x7fff;
+
+// Verdef.vd_flags and Vernaux.vna_flags
+pub const VER_FLG_BASE: u16 = 0x1;
+pub const VER_FLG_WEAK: u16 = 0x2;
+
+// group flags of APS2 packed relocations
+pub const RELOCATION_GROUPED_BY_INFO_FLAG: u64 = 0x1;
+pub const RELOCATION_GROUPED_BY_OFFSET_DELTA_FLAG: u64 = 0x2;
+pub const RELOCATION_GROUPED_BY_ADDEND_FLAG: u64 = 0x4;
+pub const RELOCATION_GROUP_HAS_ADDEND_FLAG: u64 = 0x8;
+
+// note type of owner "GNU"
+pub const NT_GNU_ABI_TAG: u32 = 1;
+pub const NT_GNU_HWCAP: u32 = 2;
+pub const NT_GNU_BUILD_ID: u32 = 3;
+pub const NT_GNU_GOLD_VERSION: u32 = 4;
+pub const NT_GNU_PROPERTY_TYPE_0: u32 = 5;
+
+// note type of owner "Android"
+pub const NT_ANDROID_TYPE_IDENT: u32 = 1;
+
+// OS of NT_GNU_ABI_TAG
+pub const ELF_NOTE_OS_LINUX: u32 = 0;
+pub const ELF_NOTE_OS_GNU: u32 = 1;
+pub const ELF_NOTE_OS_SOLARIS2: u32 = 2;
+pub const ELF_NOTE_OS_FREEBSD: u32 = 3;
+
+// pr_type of NT_GNU_PROPERTY_TYPE_0, 0xc0000000 and above depend on e_machine
+pub const GNU_PROPERTY_STACK_SIZE: u32 = 1;
+pub const GNU_PROPERTY_NO_COPY_ON_PROTECTED: u32 = 2;
+pub const GNU_PROPERTY_LOPROC: u32 = 0xc0000000;
+pub const GNU_PROPERTY_AARCH64_FEATURE_1_AND: u32 = 0xc0000000;
+pub const GNU_PROPERTY_X86_ISA_1_USED: u32 = 0xc0010002;
+pub const GNU_PROPERTY_X86_ISA_1_NEEDED: u32 = 0xc0008002;
+pub const GNU_PROPERTY_X86_FEATURE_1_AND: u32 = 0xc0000002;
+pub const GNU_PROPERTY_HIPROC: u32 = 0xdfffffff;
+
+// GNU_PROPERTY_AARCH64_FEATURE_1_AND bits
+pub const GNU_PROPERTY_AARCH64_FEATURE_1_BTI: u32 = 0x1;
+pub const GNU_PROPERTY_AARCH64_FEATURE_1_PAC: u32 = 0x2;
+
+// GNU_PROPERTY_X86_FEATURE_1_AND bits
+pub const GNU_PROPERTY_X86_FEATURE_1_IBT: u32 = 0x1;
+pub const GNU_PROPERTY_X86_FEATURE_1_SHSTK: u32 = 0x2;
+
+// GNU_PROPERTY_X86_ISA_1_USED and GNU_PROPERTY_X86_ISA_1_NEEDED bits
+pub const GNU_PROPERTY_X86_ISA_1_BASELINE: u32 = 0x1;
+pub const GNU_PROPERTY_X86_ISA_1_V2: u32 = 0x2;
+pub const GNU_PROPERTY_X86_ISA_1_V3: u32 = 0x4;
+pub const GNU_PROPERTY_X86_ISA_1_V4: u32 = 0x8;
+
+// standard opcode of .debug_line
+pub const DW_LNS_COPY: u8 = 0x1;
+pub const DW_LNS_ADVANCE_PC: u8 = 0x2;
+pub const DW_LNS_ADVANCE_LINE: u8 = 0x3;
+pub const DW_LNS_SET_FILE: u8 = 0x4;
+pub const DW_LNS_SET_COLUMN: u8 = 0x5;
+pub const DW_LNS_NEGATE_STMT: u8 = 0x6;
+pub const DW_LNS_SET_BASIC_BLOCK: u8 = 0x7;
+pub const DW_LNS_CONST_ADD_PC: u8 = 0x8;
+pub const DW_LNS_FIXED_ADVANCE_PC: u8 = 0x9;
+pub const DW_LNS_SET_PROLOGUE_END: u8 = 0xa;
+pub const DW_LNS_SET_EPILOGUE_BEGIN: u8 = 0xb;
+pub const DW_LNS_SET_ISA: u8 = 0xc;
+
+// extended opcode of .debug_line
+pub const DW_LNE_END_SEQUENCE: u8 = 0x1;
+pub const DW_LNE_SET_ADDRESS: u8 = 0x2;
+pub const DW_LNE_DEFINE_FILE: u8 = 0x3;
+pub const DW_LNE_SET_DISCRIMINATOR: u8 = 0x4;
+
+// content type of DWARF 5 directory and file name entry formats
+pub const DW_LNCT_PATH: u64 = 0x1;
+pub const DW_LNCT_DIRECTORY_INDEX: u64 = 0x2;
+pub const DW_LNCT_TIMESTAMP: u64 = 0x3;
+pub const DW_LNCT_SIZE: u64 = 0x4;
+pub const DW_LNCT_MD5: u64 = 0x5;
+
+// attribute form, those allowed in line table headers
+pub const DW_FORM_BLOCK2: u64 = 0x03;
+pub const DW_FORM_BLOCK4: u64 = 0x04;
+pub const DW_FORM_DATA2: u64 = 0x05;
+pub const DW_FORM_DATA4: u64 = 0x06;
+pub const DW_FORM_DATA8: u64 = 0x07;
+pub const DW_FORM_STRING: u64 = 0x08;
+pub const DW_FORM_BLOCK: u64 = 0x09;
+pub const DW_FORM_BLOCK1: u64 = 0x0a;
+pub const DW_FORM_DATA1: u64 = 0x0b;
+pub const DW_FORM_UDATA: u64 = 0x0f;
+pub const DW_FORM_STRP: u64 = 0x0e;
+pub const DW_FORM_DATA16: u64 = 0x1e;
+pub const DW_FORM_LINE_STRP: u64 = 0x1f;
+pub const DW_FORM_STRX: u64 = 0x1a;
+pub const DW_FORM_STRX1: u64 = 0x25;
+pub const DW_FORM_STRX2: u64 = 0x26;
+pub const DW_FORM_STRX3: u64 = 0x27;
+pub const DW_FORM_STRX4: u64 = 0x28;
+
+// pointer encoding of .eh_frame and .eh_frame_hdr, a format or'ed with an application
+pub const DW_EH_PE_ABSPTR: u8 = 0x00;
+pub const DW_EH_PE_ULEB128: u8 = 0x01;
+pub const DW_EH_PE_UDATA2: u8 = 0x02;
+pub const DW_EH_PE_UDATA4: u8 = 0x03;
+pub const DW_EH_PE_UDATA8: u8 = 0x04;
+pub const DW_EH_PE_SLEB128: u8 = 0x09;
+pub const DW_EH_PE_SDATA2: u8 = 0x0a;
+pub const DW_EH_PE_SDATA4: u8 = 0x0b;
+pub const DW_EH_PE_SDATA8: u8 = 0x0c;
+pub const DW_EH_PE_PCREL: u8 = 0x10;
+pub const DW_EH_PE_TEXTREL: u8 = 0x20;
+pub const DW_EH_PE_DATAREL: u8 = 0x30;
+pub const DW_EH_PE_FUNCREL: u8 = 0x40;
+pub const DW_EH_PE_ALIGNED: u8 = 0x50;
+pub const DW_EH_PE_INDIRECT: u8 = 0x80;
+pub const DW_EH_PE_OMIT: u8 = 0xff;
+
+// call frame instruction, the high 2 bits hold the first three with their operand
+pub const DW_CFA_ADVANCE_LOC: u8 = 0x40;
+pub const DW_CFA_OFFSET: u8 = 0x80;
+pub const DW_CFA_RESTORE: u8 = 0xc0;
+pub const DW_CFA_NOP: u8 = 0x00;
+pub const DW_CFA_SET_LOC: u8 = 0x01;
+pub const DW_CFA_ADVANCE_LOC1: u8 = 0x02;
+pub const DW_CFA_ADVANCE_LOC2: u8 = 0x03;
+pub const DW_CFA_ADVANCE_LOC4: u8 = 0x04;
+pub const DW_CFA_OFFSET_EXTENDED: u8 = 0x05;
+pub const DW_CFA_RESTORE_EXTENDED: u8 = 0x06;
+pub const DW_CFA_UNDEFINED: u8 = 0x07;
+pub const DW_CFA_SAME_VALUE: u8 = 0x08;
+pub const DW_CFA_REGISTER: u8 = 0x09;
+pub const DW_CFA_REMEMBER_STATE: u8 = 0x0a;
+pub const DW_CFA_RESTORE_STATE: u8 = 0x0b;
+pub const DW_CFA_DEF_CFA: u8 = 0x0c;
+pub const DW_CFA_DEF_CFA_REGISTER: u8 = 0x0d;
+pub const DW_CFA_DEF_CFA_OFFSET: u8 = 0x0e;
+pub const DW_CFA_DEF_CFA_EXPRESSION: u8 = 0x0f;
+pub const DW_CFA_EXPRESSION: u8 = 0x10;
+pub const DW_CFA_OFFSET_EXTENDED_SF: u8 = 0x11;
+pub const DW_CFA_DEF_CFA_SF: u8 = 0x12;
+pub const DW_CFA_DEF_CFA_OFFSET_SF: u8 = 0x13;
+pub const DW_CFA_VAL_OFFSET: u8 = 0x14;
+pub const DW_CFA_VAL_OFFSET_SF: u8 = 0x15;
+pub const DW_CFA_VAL_EXPRESSION: u8 = 0x16;
+pub const DW_CFA_GNU_WINDOW_SAVE: u8 = 0x2d;
+pub const DW_CFA_GNU_ARGS_SIZE: u8 = 0x2e;
+pub const DW_CFA_GNU_NEGATIVE_OFFSET_EXTENDED: u8 = 0x2f;
+
+// CompressionHeader.ch_type
+pub const ELFCOMPRESS_ZLIB: u32 = 1;
+pub const ELFCOMPRESS_ZSTD: u32 = 2;
+
+// second word of an .ARM.exidx entry for functions which cannot be unwound
+pub const EXIDX_CANTUNWIND: u32 = 0x1;
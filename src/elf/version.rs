@@ -0,0 +1,192 @@
+use std::fmt;
+use crate::elf::{endian::Endian, value};
+
+
+// Elf_Verdef, same layout in both classes
+#[derive(Debug, Default, Clone)]
+pub struct VerdefEntry {
+    pub vd_version: u16,
+    pub vd_flags: u16,
+    pub vd_ndx: u16,
+    pub vd_cnt: u16,
+    pub vd_hash: u32,
+    pub vd_aux: u32,
+    pub vd_next: u32,
+}
+
+// Elf_Verdaux
+#[derive(Debug, Default, Clone)]
+pub struct VerdauxEntry {
+    pub vda_name: u32,
+    pub vda_next: u32,
+}
+
+// Elf_Verneed
+#[derive(Debug, Default, Clone)]
+pub struct VerneedEntry {
+    pub vn_version: u16,
+    pub vn_cnt: u16,
+    pub vn_file: u32,
+    pub vn_aux: u32,
+    pub vn_next: u32,
+}
+
+// Elf_Vernaux
+#[derive(Debug, Default, Clone)]
+pub struct VernauxEntry {
+    pub vna_hash: u32,
+    pub vna_flags: u16,
+    pub vna_other: u16,
+    pub vna_name: u32,
+    pub vna_next: u32,
+}
+
+impl VerdefEntry {
+    pub const SIZE: usize = 0x14;
+
+    pub fn parse(data: &[u8], endian: Endian) -> VerdefEntry {
+        let mut cursor = data;
+        VerdefEntry {
+            vd_version: endian.read_u16(&mut cursor),
+            vd_flags: endian.read_u16(&mut cursor),
+            vd_ndx: endian.read_u16(&mut cursor),
+            vd_cnt: endian.read_u16(&mut cursor),
+            vd_hash: endian.read_u32(&mut cursor),
+            vd_aux: endian.read_u32(&mut cursor),
+            vd_next: endian.read_u32(&mut cursor),
+        }
+    }
+}
+
+impl VerdauxEntry {
+    pub const SIZE: usize = 0x8;
+
+    pub fn parse(data: &[u8], endian: Endian) -> VerdauxEntry {
+        let mut cursor = data;
+        VerdauxEntry {
+            vda_name: endian.read_u32(&mut cursor),
+            vda_next: endian.read_u32(&mut cursor),
+        }
+    }
+}
+
+impl VerneedEntry {
+    pub const SIZE: usize = 0x10;
+
+    pub fn parse(data: &[u8], endian: Endian) -> VerneedEntry {
+        let mut cursor = data;
+        VerneedEntry {
+            vn_version: endian.read_u16(&mut cursor),
+            vn_cnt: endian.read_u16(&mut cursor),
+            vn_file: endian.read_u32(&mut cursor),
+            vn_aux: endian.read_u32(&mut cursor),
+            vn_next: endian.read_u32(&mut cursor),
+        }
+    }
+}
+
+impl VernauxEntry {
+    pub const SIZE: usize = 0x10;
+
+    pub fn parse(data: &[u8], endian: Endian) -> VernauxEntry {
+        let mut cursor = data;
+        VernauxEntry {
+            vna_hash: endian.read_u32(&mut cursor),
+            vna_flags: endian.read_u16(&mut cursor),
+            vna_other: endian.read_u16(&mut cursor),
+            vna_name: endian.read_u32(&mut cursor),
+            vna_next: endian.read_u32(&mut cursor),
+        }
+    }
+}
+
+// version defined by this file, first name is the version, the rest are parents
+#[derive(Debug, Default, Clone)]
+pub struct VersionDef {
+    pub index: u16,
+    pub flags: u16,
+    pub hash: u32,
+    pub names: Vec<String>,
+}
+
+// versions required from one DT_NEEDED file
+#[derive(Debug, Default, Clone)]
+pub struct VersionNeed {
+    pub file: String,
+    pub versions: Vec<VersionRequirement>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct VersionRequirement {
+    pub index: u16,
+    pub flags: u16,
+    pub hash: u32,
+    pub name: String,
+}
+
+// version of one symbol
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolVersion {
+    pub index: u16,
+    pub name: String,
+    // not the default version, only reachable by explicit version
+    pub hidden: bool,
+    // file providing the version, for required versions
+    pub file: Option<String>,
+}
+
+// versym, verdef and verneed of dynamic symbols
+#[derive(Debug, Default, Clone)]
+pub struct Versions {
+    // one entry per dynamic symbol
+    pub versym: Vec<u16>,
+    pub defs: Vec<VersionDef>,
+    pub needs: Vec<VersionNeed>,
+}
+
+impl VersionDef {
+    pub fn name(&self) -> &str {
+        self.names.first().map_or("", |s| s.as_str())
+    }
+}
+
+impl Versions {
+    pub fn new() -> Versions {
+        Self::default()
+    }
+
+    // version of dynamic symbol, None for local and unversioned global symbols
+    pub fn symbol_version(&self, symbol_index: usize) -> Option<SymbolVersion> {
+        let versym = *self.versym.get(symbol_index)?;
+        let index = versym & value::VERSYM_VERSION;
+        if index == value::VER_NDX_LOCAL || index == value::VER_NDX_GLOBAL {
+            return None;
+        }
+        let hidden = (versym & value::VERSYM_HIDDEN) != 0;
+        if let Some(def) = self.defs.iter().find(|d| d.index == index) {
+            return Some(SymbolVersion { index, name: def.name().to_string(), hidden, file: None });
+        }
+        for need in &self.needs {
+            if let Some(version) = need.versions.iter().find(|v| v.index == index) {
+                return Some(SymbolVersion {
+                    index,
+                    name: version.name.clone(),
+                    hidden,
+                    file: Some(need.file.clone()),
+                });
+            }
+        }
+        None
+    }
+}
+
+// readelf style suffix, "@@" marks the default version of a definition
+impl fmt::Display for SymbolVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.hidden || self.file.is_some() {
+            write!(f, "@{}", self.name)
+        } else {
+            write!(f, "@@{}", self.name)
+        }
+    }
+}
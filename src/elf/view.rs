@@ -0,0 +1,500 @@
+use std::{borrow::Cow, convert::TryFrom, str};
+use crate::{
+    elf::{address::{self, Location}, arm_exidx::ArmExidx, compress::Compression, demangle, dwarf::{self, LineProgram, LineSections, SourceLocation},
+        eh_frame::{EhFrame, EhFrameHdr}, elf32, elf64, endian::Endian, note::{Note, NoteIter}, section::Group, symbol::SymbolType, value},
+    error::{Error, Result},
+};
+
+
+// ELF of any class borrowed from bytes, as a mapped file or a memory dump.
+// Headers are decoded when asked for, names and contents are slices of data,
+// nothing is read or allocated per entry.
+pub struct ElfView<'a> {
+    data: &'a [u8],
+    pub class: u8,
+    pub endian: Endian,
+    pub header: elf64::ELFHeader,
+    shstrtab: &'a [u8],
+    // e_shnum and e_phnum, or the counts of extended numbering in section 0
+    section_count: usize,
+    segment_count: usize,
+}
+
+// section header with name borrowed from .shstrtab, decoded lossily as Elf
+// does so both find the same sections
+#[derive(Debug, Clone)]
+pub struct SectionRef<'a> {
+    pub index: usize,
+    pub name: Cow<'a, str>,
+    pub header: elf64::SectionHeader,
+}
+
+// symbol entry with name borrowed from its string table, decoded lossily
+#[derive(Debug, Clone)]
+pub struct SymbolRef<'a> {
+    pub index: usize,
+    pub name: Cow<'a, str>,
+    pub entry: elf64::SymbolEntry,
+    // st_shndx, the SHT_SYMTAB_SHNDX entry when it is SHN_XINDEX
+    pub shndx: u32,
+}
+
+// symbols of one table, decoded while iterating
+pub struct SymbolIter<'a> {
+    table: &'a [u8],
+    strtab: &'a [u8],
+    // SHT_SYMTAB_SHNDX linked to table, empty if there is none
+    shndx: &'a [u8],
+    entry_size: usize,
+    index: usize,
+    class: u8,
+    endian: Endian,
+}
+
+impl<'a> ElfView<'a> {
+    pub fn parse(data: &'a [u8]) -> Result<ElfView<'a>> {
+        if data.len() < value::EI_NIDENT {
+            return Err(Error::truncated("e_ident", 0, value::EI_NIDENT as u64));
+        }
+        if data[..4] != [value::ELFMAG0, value::ELFMAG1, value::ELFMAG2, value::ELFMAG3] {
+            return Err(Error::BadMagic);
+        }
+        let class = data[value::EI_CLASS];
+        let header = match class {
+            value::ELFCLASS32 => elf64::ELFHeader::from(&elf32::ELFHeader::parse(data)?),
+            value::ELFCLASS64 => elf64::ELFHeader::parse(data)?,
+            n => return Err(Error::UnsupportedClass(n)),
+        };
+        let mut view = ElfView {
+            data,
+            class,
+            endian: header.endian(),
+            section_count: header.e_shnum as usize,
+            segment_count: header.e_phnum as usize,
+            header,
+            shstrtab: &[],
+        };
+
+        // entry sizes smaller than the class layout cannot be decoded
+        let (shdr_size, phdr_size) = view.entry_sizes();
+        if view.header.e_shnum != 0 && (view.header.e_shentsize as usize) < shdr_size {
+            return Err(Error::invalid("e_shentsize", format!("{:#x}", view.header.e_shentsize)));
+        }
+        if view.header.e_phnum != 0 && (view.header.e_phentsize as usize) < phdr_size {
+            return Err(Error::invalid("e_phentsize", format!("{:#x}", view.header.e_phentsize)));
+        }
+
+        // Section 0 holds what does not fit the 16-bit fields, extended numbering.
+        // Counts from there are cut to the entries in data, a dump may have anything at e_shoff.
+        let mut name_index = view.header.e_shstrndx as usize;
+        let first = match view.header.e_shoff {
+            0 => None,
+            _ if (view.header.e_shentsize as usize) < shdr_size => None,
+            offset => view.header_at(offset, view.header.e_shentsize, 0),
+        };
+        if let Some(first) = first {
+            let table_size = |offset: u64, size: u16| (data.len() as u64).saturating_sub(offset) / (size as u64).max(1);
+            if view.header.e_shnum == 0 {
+                view.section_count = first.sh_size.min(table_size(view.header.e_shoff, view.header.e_shentsize)) as usize;
+            }
+            if name_index == value::SHN_XINDEX {
+                name_index = first.sh_link as usize;
+            }
+            if view.header.e_phnum == value::PN_XNUM {
+                view.segment_count = (first.sh_info as u64).min(table_size(view.header.e_phoff, view.header.e_phentsize)) as usize;
+            }
+        }
+        if let Some(shdr) = view.section_header(name_index) {
+            view.shstrtab = view.slice(shdr.sh_offset, shdr.sh_size).unwrap_or(&[]);
+        }
+        Ok(view)
+    }
+
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    pub fn section_count(&self) -> usize {
+        self.section_count
+    }
+
+    pub fn section(&self, index: usize) -> Option<SectionRef<'a>> {
+        let header = self.section_header(index)?;
+        Some(SectionRef {
+            index,
+            name: String::from_utf8_lossy(cstr(self.shstrtab, header.sh_name as usize).unwrap_or(b"")),
+            header,
+        })
+    }
+
+    pub fn sections(&self) -> impl Iterator<Item = SectionRef<'a>> + '_ {
+        (0..self.section_count()).filter_map(move |i| self.section(i))
+    }
+
+    pub fn section_by_name(&self, name: &str) -> Option<SectionRef<'a>> {
+        self.sections().find(|s| s.name == name)
+    }
+
+    // content of section, empty for SHT_NOBITS, None if out of data
+    pub fn section_data(&self, header: &elf64::SectionHeader) -> Option<&'a [u8]> {
+        if header.sh_type == value::SHT_NOBITS {
+            return Some(&[]);
+        }
+        self.slice(header.sh_offset, header.sh_size)
+    }
+
+    // content of section, decompressed when SHF_COMPRESSED or .zdebug
+    pub fn uncompressed_data(&self, section: &SectionRef<'a>) -> Result<Cow<'a, [u8]>> {
+        let header = &section.header;
+        let data = self.section_data(header).ok_or_else(|| Error::truncated(section.name.as_ref(), header.sh_offset, header.sh_size))?;
+        match Compression::detect(&section.name, header, data, self.class, self.endian)? {
+            Some(compression) => compression.decompress(data).map(Cow::Owned),
+            None => Ok(Cow::Borrowed(data)),
+        }
+    }
+
+    // compression of section with its stored and uncompressed sizes, None if stored as is
+    pub fn section_compression(&self, section: &SectionRef<'a>) -> Result<Option<Compression>> {
+        let header = &section.header;
+        let data = self.section_data(header).ok_or_else(|| Error::truncated(section.name.as_ref(), header.sh_offset, header.sh_size))?;
+        Compression::detect(&section.name, header, data, self.class, self.endian)
+    }
+
+    pub fn segment_count(&self) -> usize {
+        self.segment_count
+    }
+
+    pub fn segment(&self, index: usize) -> Option<elf64::ProgramHeader> {
+        if index >= self.segment_count() {
+            return None;
+        }
+        let size = self.header.e_phentsize as u64;
+        let data = self.slice(self.header.e_phoff.checked_add(index as u64 * size)?, size)?;
+        Some(match self.class {
+            value::ELFCLASS32 => elf64::ProgramHeader::from(&elf32::ProgramHeader::parse(data, self.endian)),
+            _ => elf64::ProgramHeader::parse(data, self.endian),
+        })
+    }
+
+    pub fn segments(&self) -> impl Iterator<Item = elf64::ProgramHeader> + '_ {
+        (0..self.segment_count()).filter_map(move |i| self.segment(i))
+    }
+
+    // notes of SHT_NOTE sections, of PT_NOTE segments when there is none
+    pub fn notes(&self) -> impl Iterator<Item = Note<'a>> + '_ {
+        let mut places: Vec<(u64, u64, u64)> = self.sections()
+            .filter(|s| s.header.sh_type == value::SHT_NOTE)
+            .map(|s| (s.header.sh_offset, s.header.sh_size, s.header.sh_addralign))
+            .collect();
+        if places.is_empty() {
+            places = self.segments()
+                .filter(|p| p.p_type == value::PT_NOTE)
+                .map(|p| (p.p_offset, p.p_filesz, p.p_align))
+                .collect();
+        }
+        places.into_iter().flat_map(move |(offset, size, align)| {
+            NoteIter::new(self.slice(offset, size).unwrap_or(&[]), self.class, self.endian, align)
+        })
+    }
+
+    pub fn build_id(&self) -> Option<&'a [u8]> {
+        self.notes().find_map(|n| n.build_id())
+    }
+
+    // line number programs of .debug_line, empty without debug info
+    pub fn line_programs(&self) -> Result<Vec<LineProgram>> {
+        // .zdebug twins of older toolchains
+        let data = |name: &str| -> Result<Cow<'a, [u8]>> {
+            let legacy = format!(".z{}", name.trim_start_matches('.'));
+            match self.section_by_name(name).or_else(|| self.section_by_name(&legacy)) {
+                Some(section) => self.uncompressed_data(&section),
+                None => Ok(Cow::Borrowed(&[])),
+            }
+        };
+        let (debug_line, debug_line_str, debug_str) = (data(".debug_line")?, data(".debug_line_str")?, data(".debug_str")?);
+        let sections = LineSections {
+            debug_line: &debug_line,
+            debug_line_str: &debug_line_str,
+            debug_str: &debug_str,
+        };
+        let address_size = if self.class == value::ELFCLASS32 { 4 } else { 8 };
+        dwarf::parse_line_programs(&sections, self.endian, address_size)
+    }
+
+    pub fn addr2line(&self, vaddr: u64) -> Result<Option<SourceLocation>> {
+        Ok(dwarf::find_location(&self.line_programs()?, vaddr))
+    }
+
+    // .eh_frame_hdr section, or PT_GNU_EH_FRAME segment without sections
+    pub fn eh_frame_hdr(&self) -> Result<Option<EhFrameHdr>> {
+        let place = match self.section_by_name(".eh_frame_hdr") {
+            Some(s) => Some((s.header.sh_offset, s.header.sh_size, s.header.sh_addr)),
+            None => self.segments()
+                .find(|p| p.p_type == value::PT_GNU_EH_FRAME)
+                .map(|p| (p.p_offset, p.p_filesz, p.p_vaddr)),
+        };
+        let (offset, size, vaddr) = match place {
+            Some(place) => place,
+            None => return Ok(None),
+        };
+        let data = self.slice(offset, size).ok_or_else(|| Error::truncated(".eh_frame_hdr", offset, size))?;
+        EhFrameHdr::parse(data, vaddr, self.class, self.endian).map(Some)
+    }
+
+    // .eh_frame section, or where .eh_frame_hdr points without sections
+    pub fn eh_frame(&self) -> Result<Option<EhFrame>> {
+        let (offset, size, vaddr) = match self.section_by_name(".eh_frame") {
+            Some(s) if s.header.sh_type != value::SHT_NOBITS => (s.header.sh_offset, s.header.sh_size, s.header.sh_addr),
+            _ => {
+                let vaddr = match self.eh_frame_hdr()? {
+                    Some(hdr) => hdr.eh_frame_ptr,
+                    None => return Ok(None),
+                };
+                let (offset, phdr) = match (self.vaddr_to_offset(vaddr), self.segment_for_vaddr(vaddr)) {
+                    (Some(Location::File(offset)), Some(phdr)) => (offset, phdr),
+                    _ => return Err(Error::invalid("eh_frame_ptr", format!("{:#x} is not in file", vaddr))),
+                };
+                // rest of the segment, p_offset + p_filesz of a crafted PT_LOAD may overflow
+                match phdr.p_offset.checked_add(phdr.p_filesz).and_then(|end| end.checked_sub(offset)) {
+                    Some(size) => (offset, size, vaddr),
+                    None => return Err(Error::invalid("PT_LOAD", format!("{:#x} + {:#x} does not hold offset {:#x}",
+                        phdr.p_offset, phdr.p_filesz, offset))),
+                }
+            },
+        };
+        let data = self.slice(offset, size).ok_or_else(|| Error::truncated(".eh_frame", offset, size))?;
+        EhFrame::parse(data.to_vec(), vaddr, self.class, self.endian).map(Some)
+    }
+
+    // .ARM.exidx section, or PT_ARM_EXIDX segment without sections
+    pub fn arm_exidx(&self) -> Result<Option<ArmExidx>> {
+        if self.header.e_machine != value::EM_ARM {
+            return Ok(None);
+        }
+        let place = match self.sections().find(|s| s.header.sh_type == value::SHT_ARM_EXIDX) {
+            Some(s) => Some((s.header.sh_offset, s.header.sh_size, s.header.sh_addr)),
+            None => self.segments()
+                .find(|p| p.p_type == value::PT_ARM_EXIDX)
+                .map(|p| (p.p_offset, p.p_filesz, p.p_vaddr)),
+        };
+        let (offset, size, vaddr) = match place {
+            Some(place) => place,
+            None => return Ok(None),
+        };
+        let data = self.slice(offset, size).ok_or_else(|| Error::truncated(".ARM.exidx", offset, size))?;
+        let extab = match self.section_by_name(".ARM.extab") {
+            Some(s) if s.header.sh_type != value::SHT_NOBITS => Some((s.header.sh_offset, s.header.sh_size, s.header.sh_addr)),
+            _ => self.segment_for_vaddr(vaddr).map(|p| (p.p_offset, p.p_filesz, p.p_vaddr)),
+        };
+        let (extab, extab_vaddr) = match extab {
+            Some((offset, size, vaddr)) => (self.slice(offset, size).ok_or_else(|| Error::truncated(".ARM.extab", offset, size))?, vaddr),
+            None => (&[][..], 0),
+        };
+        ArmExidx::parse(data, vaddr, extab, extab_vaddr, self.endian).map(Some)
+    }
+
+    // SHT_GROUP sections with signature and members, as COMDAT groups of relocatable files
+    pub fn groups(&self) -> Result<Vec<Group>> {
+        let mut groups: Vec<Group> = Vec::new();
+        for section in self.sections().filter(|s| s.header.sh_type == value::SHT_GROUP) {
+            let header = &section.header;
+            let table = match self.section(header.sh_link as usize) {
+                Some(s) if s.header.sh_type == value::SHT_SYMTAB => s,
+                _ => return Err(Error::invalid("sh_link of SHT_GROUP", format!("section {} is not SHT_SYMTAB", header.sh_link))),
+            };
+            let symbols = self.table_symbols(&table);
+            let symbol = match symbols.get(header.sh_info as usize) {
+                Some(symbol) => symbol,
+                None => return Err(Error::out_of_range("sh_info of SHT_GROUP", header.sh_info as u64, symbols.len() as u64)),
+            };
+            // signature of a section symbol is the name of its section
+            let signature = match symbol.entry.symbol_type() {
+                SymbolType::Section => self.section(symbol.shndx as usize).map_or(Cow::Borrowed(""), |s| s.name),
+                _ => symbol.name,
+            };
+            let data = self.section_data(header).ok_or_else(|| Error::truncated(section.name.as_ref(), header.sh_offset, header.sh_size))?;
+            groups.push(Group::parse(section.index, signature.into_owned(), data, self.endian)?);
+        }
+        Ok(groups)
+    }
+
+    // program interpreter path of PT_INTERP, None if missing
+    pub fn interpreter(&self) -> Option<Cow<'a, str>> {
+        let phdr = self.segments().find(|p| p.p_type == value::PT_INTERP)?;
+        cstr(self.slice(phdr.p_offset, phdr.p_filesz)?, 0).map(String::from_utf8_lossy)
+    }
+
+    pub fn vaddr_to_offset(&self, vaddr: u64) -> Option<Location> {
+        address::vaddr_to_offset(&self.segments().collect::<Vec<_>>(), vaddr)
+    }
+
+    pub fn offset_to_vaddr(&self, offset: u64) -> Option<u64> {
+        address::offset_to_vaddr(&self.segments().collect::<Vec<_>>(), offset)
+    }
+
+    pub fn segment_for_vaddr(&self, vaddr: u64) -> Option<elf64::ProgramHeader> {
+        let segments: Vec<elf64::ProgramHeader> = self.segments().collect();
+        address::segment_for_vaddr(&segments, vaddr).map(|i| segments[i].clone())
+    }
+
+    pub fn section_for_vaddr(&self, vaddr: u64) -> Option<SectionRef<'a>> {
+        // unreadable headers keep their place so indices stay those of the file
+        let headers: Vec<elf64::SectionHeader> = (0..self.section_count())
+            .map(|i| self.section_header(i).unwrap_or_default())
+            .collect();
+        address::section_for_vaddr(&headers, vaddr).and_then(|i| self.section(i))
+    }
+
+    // symbols of .symtab
+    pub fn symbols(&self) -> SymbolIter<'a> {
+        self.symbol_table(value::SHT_SYMTAB)
+    }
+
+    // symbols of .dynsym
+    pub fn dynamic_symbols(&self) -> SymbolIter<'a> {
+        self.symbol_table(value::SHT_DYNSYM)
+    }
+
+    // entries of PT_DYNAMIC or SHT_DYNAMIC up to DT_NULL
+    pub fn dynamic(&self) -> impl Iterator<Item = elf64::DynamicEntry> + 'a {
+        let table = match self.segments().find(|p| p.p_type == value::PT_DYNAMIC) {
+            Some(phdr) => self.slice(phdr.p_offset, phdr.p_filesz),
+            None => self.sections()
+                .find(|s| s.header.sh_type == value::SHT_DYNAMIC)
+                .and_then(|s| self.section_data(&s.header)),
+        };
+        let (class, endian) = (self.class, self.endian);
+        let entry_size = if class == value::ELFCLASS32 { 0x8 } else { 0x10 };
+        table.unwrap_or(&[])
+            .chunks_exact(entry_size)
+            .map(move |data| match class {
+                value::ELFCLASS32 => elf64::DynamicEntry::from(&elf32::DynamicEntry::parse(data, endian)),
+                _ => elf64::DynamicEntry::parse(data, endian),
+            })
+            .take_while(|entry| entry.d_tag != value::DT_NULL)
+    }
+
+    // bytes at file offset, None if out of data
+    pub fn slice(&self, offset: u64, size: u64) -> Option<&'a [u8]> {
+        let start = usize::try_from(offset).ok()?;
+        let end = start.checked_add(usize::try_from(size).ok()?)?;
+        self.data.get(start..end)
+    }
+
+    fn section_header(&self, index: usize) -> Option<elf64::SectionHeader> {
+        if index >= self.section_count() {
+            return None;
+        }
+        self.header_at(self.header.e_shoff, self.header.e_shentsize, index)
+    }
+
+    // section header of table at offset, whatever the count is
+    fn header_at(&self, offset: u64, size: u16, index: usize) -> Option<elf64::SectionHeader> {
+        let size = size as u64;
+        let data = self.slice(offset.checked_add((index as u64).checked_mul(size)?)?, size)?;
+        Some(match self.class {
+            value::ELFCLASS32 => elf64::SectionHeader::from(&elf32::SectionHeader::parse(data, self.endian)),
+            _ => elf64::SectionHeader::parse(data, self.endian),
+        })
+    }
+
+    fn symbol_table(&self, sh_type: u32) -> SymbolIter<'a> {
+        match self.sections().find(|s| s.header.sh_type == sh_type) {
+            Some(section) => self.table_symbols(&section),
+            None => self.no_symbols(),
+        }
+    }
+
+    fn no_symbols(&self) -> SymbolIter<'a> {
+        SymbolIter {
+            table: &[],
+            strtab: &[],
+            shndx: &[],
+            entry_size: 1,
+            index: 0,
+            class: self.class,
+            endian: self.endian,
+        }
+    }
+
+    // symbols of table, none if its entries cannot be decoded
+    fn table_symbols(&self, section: &SectionRef<'a>) -> SymbolIter<'a> {
+        let mut iter = self.no_symbols();
+        let min_size = if self.class == value::ELFCLASS32 { 0x10 } else { 0x18 };
+        if (section.header.sh_entsize as usize) < min_size {
+            return iter;
+        }
+        iter.table = self.section_data(&section.header).unwrap_or(&[]);
+        iter.entry_size = section.header.sh_entsize as usize;
+        // symbol names live in the string table of sh_link
+        if let Some(strtab) = self.section_header(section.header.sh_link as usize) {
+            iter.strtab = self.section_data(&strtab).unwrap_or(&[]);
+        }
+        if let Some(shndx) = self.sections().find(|s| s.header.sh_type == value::SHT_SYMTAB_SHNDX && s.header.sh_link as usize == section.index) {
+            iter.shndx = self.section_data(&shndx.header).unwrap_or(&[]);
+        }
+        iter
+    }
+
+    fn entry_sizes(&self) -> (usize, usize) {
+        match self.class {
+            value::ELFCLASS32 => (0x28, 0x20),
+            _ => (0x40, 0x38),
+        }
+    }
+}
+
+impl<'a> SymbolRef<'a> {
+    // C++ or Rust name in readable form, None if name is not mangled
+    pub fn demangled(&self) -> Option<String> {
+        demangle::demangle(&self.name)
+    }
+}
+
+impl<'a> SymbolIter<'a> {
+    // symbol by index in table, whatever the iteration has reached
+    pub fn get(&self, index: usize) -> Option<SymbolRef<'a>> {
+        let start = index.checked_mul(self.entry_size)?;
+        let data = self.table.get(start..start.checked_add(self.entry_size)?)?;
+        let entry = match self.class {
+            value::ELFCLASS32 => elf64::SymbolEntry::from(&elf32::SymbolEntry::parse(data, self.endian)),
+            _ => elf64::SymbolEntry::parse(data, self.endian),
+        };
+        // section indices past SHN_LORESERVE are in SHT_SYMTAB_SHNDX
+        let shndx = match entry.st_shndx as usize {
+            value::SHN_XINDEX => self.shndx.get(index * 4..index * 4 + 4)
+                .map_or(value::SHN_XINDEX as u32, |mut word| self.endian.read_u32(&mut word)),
+            n => n as u32,
+        };
+        Some(SymbolRef {
+            index,
+            name: String::from_utf8_lossy(cstr(self.strtab, entry.st_name as usize).unwrap_or(b"")),
+            entry,
+            shndx,
+        })
+    }
+}
+
+impl<'a> Iterator for SymbolIter<'a> {
+    type Item = SymbolRef<'a>;
+
+    fn next(&mut self) -> Option<SymbolRef<'a>> {
+        let symbol = self.get(self.index)?;
+        self.index += 1;
+        Some(symbol)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = (self.table.len() / self.entry_size).saturating_sub(self.index);
+        (left, Some(left))
+    }
+}
+
+impl<'a> ExactSizeIterator for SymbolIter<'a> { }
+
+// null-terminated string at pos without its terminator, None if out of range
+pub fn cstr(table: &[u8], pos: usize) -> Option<&[u8]> {
+    let rest = table.get(pos..)?;
+    let end = rest.iter().position(|c| *c == b'\0').unwrap_or(rest.len());
+    Some(&rest[..end])
+}
@@ -0,0 +1,498 @@
+use std::{convert::TryFrom, io::Write};
+use crate::{
+    elf::{elf32, elf64, endian::Endian, value, view::ElfView},
+    error::{Error, Result},
+};
+
+
+// largest sh_addralign honoured when moving a section, the biggest page size
+const MAX_ALIGN: u64 = 0x10000;
+
+// ELF file to serialize, entries of any class as the elf64 types.
+// Bytes no header accounts for, as padding between sections, come from the
+// file the writer was read from, so an unmodified file is written back as is.
+#[derive(Debug, Clone)]
+pub struct ElfWriter {
+    pub class: u8,
+    pub endian: Endian,
+    pub header: elf64::ELFHeader,
+    pub segments: Vec<elf64::ProgramHeader>,
+    pub sections: Vec<OutputSection>,
+    base: Vec<u8>,
+}
+
+// section with its content, sh_size follows data when written
+#[derive(Debug, Default, Clone)]
+pub struct OutputSection {
+    pub name: String,
+    pub header: elf64::SectionHeader,
+    // empty for SHT_NOBITS
+    pub data: Vec<u8>,
+}
+
+// string table content, strings already in it keep their offsets
+#[derive(Debug, Clone)]
+pub struct StringTableBuilder {
+    data: Vec<u8>,
+}
+
+impl ElfWriter {
+    // empty file of class and byte order, without sections or segments
+    pub fn new(class: u8, endian: Endian) -> ElfWriter {
+        let mut header = elf64::ELFHeader::new();
+        header.e_ident[..4].copy_from_slice(&[value::ELFMAG0, value::ELFMAG1, value::ELFMAG2, value::ELFMAG3]);
+        header.e_ident[value::EI_CLASS] = class;
+        header.e_ident[value::EI_DATA] = match endian {
+            Endian::Little => value::ELFDATA2LSB,
+            Endian::Big => value::ELFDATA2MSB,
+        };
+        header.e_ident[value::EI_VERSION] = value::EV_CURRENT as u8;
+        header.e_version = value::EV_CURRENT;
+        let (ehdr_size, _, _) = entry_sizes(class);
+        header.e_ehsize = ehdr_size as u16;
+        ElfWriter {
+            class, endian, header,
+            segments: Vec::new(),
+            sections: Vec::new(),
+            base: Vec::new(),
+        }
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<ElfWriter> {
+        let view = ElfView::parse(data)?;
+        let mut segments: Vec<elf64::ProgramHeader> = Vec::new();
+        for index in 0..view.segment_count() {
+            match view.segment(index) {
+                Some(phdr) => segments.push(phdr),
+                None => return Err(Error::truncated(format!("program header {}", index),
+                    view.header.e_phoff, view.header.e_phentsize as u64)),
+            }
+        }
+        let mut sections: Vec<OutputSection> = Vec::new();
+        for index in 0..view.section_count() {
+            let section = match view.section(index) {
+                Some(s) => s,
+                None => return Err(Error::truncated(format!("section header {}", index),
+                    view.header.e_shoff, view.header.e_shentsize as u64)),
+            };
+            let data = match occupies_file(&section.header) {
+                true => match view.section_data(&section.header) {
+                    Some(data) => data.to_vec(),
+                    None => return Err(Error::truncated(format!("section {}", section.name),
+                        section.header.sh_offset, section.header.sh_size)),
+                },
+                false => Vec::new(),
+            };
+            sections.push(OutputSection {
+                name: section.name.to_string(),
+                header: section.header,
+                data,
+            });
+        }
+        Ok(ElfWriter {
+            class: view.class,
+            endian: view.endian,
+            header: view.header.clone(),
+            segments, sections,
+            base: data.to_vec(),
+        })
+    }
+
+    pub fn section_by_name(&self, name: &str) -> Option<&OutputSection> {
+        self.sections.iter().find(|s| s.name == name)
+    }
+
+    pub fn section_by_name_mut(&mut self, name: &str) -> Option<&mut OutputSection> {
+        self.sections.iter_mut().find(|s| s.name == name)
+    }
+
+    // Bring headers in line with content: names into the section string table,
+    // sizes and counts from data. A section grown into what follows it moves
+    // to the end of file, as does a section with sh_offset 0, and so does a
+    // program header table without e_phoff. Sections mapped by a segment cannot
+    // move without a new layout of memory, that is an error.
+    pub fn layout(&mut self) -> Result<()> {
+        let (_, phdr_size, shdr_size) = entry_sizes(self.class);
+        // entry sizes of empty tables are left as they are, relocatables have e_phentsize 0
+        if !self.segments.is_empty() && self.header.e_phentsize == 0 {
+            self.header.e_phentsize = phdr_size as u16;
+        }
+        if !self.sections.is_empty() && self.header.e_shentsize == 0 {
+            self.header.e_shentsize = shdr_size as u16;
+        }
+        if (!self.segments.is_empty() && (self.header.e_phentsize as u64) < phdr_size) ||
+            (!self.sections.is_empty() && (self.header.e_shentsize as u64) < shdr_size) {
+            return Err(Error::invalid("entry size", format!("e_phentsize {:#x} e_shentsize {:#x}",
+                self.header.e_phentsize, self.header.e_shentsize)));
+        }
+        // extended numbering keeps counts in section 0
+        if self.segments.len() >= value::PN_XNUM as usize && self.sections.is_empty() {
+            return Err(Error::invalid("segment count", format!("{} without section 0", self.segments.len())));
+        }
+        self.update_names()?;
+        let (section_count, segment_count) = self.header_counts();
+
+        // extents taken by what does not move
+        let mut used: Vec<(u64, u64)> = vec![(0, self.header.e_ehsize as u64)];
+        let phdr_table = (self.header.e_phoff, self.segments.len() as u64 * self.header.e_phentsize as u64);
+        let phdr_grown = self.segments.len() > segment_count;
+        if !self.segments.is_empty() && !phdr_grown && self.header.e_phoff != 0 {
+            used.push(phdr_table);
+        }
+        let grown: Vec<bool> = self.sections.iter()
+            .map(|s| s.header.sh_offset == 0 || s.data.len() as u64 > s.header.sh_size)
+            .collect();
+        for (section, grown) in self.sections.iter().zip(&grown) {
+            if occupies_file(&section.header) && !section.data.is_empty() && !grown {
+                used.push((section.header.sh_offset, section.data.len() as u64));
+            }
+        }
+        if section_count >= self.sections.len() && self.header.e_shoff != 0 {
+            used.push((self.header.e_shoff, self.sections.len() as u64 * self.header.e_shentsize as u64));
+        }
+        // a new table goes right after the ELF header if that is free, else to the end of file.
+        // a table mapped by PT_PHDR cannot move, it must have been given room.
+        if !self.segments.is_empty() && (self.header.e_phoff == 0 || (phdr_grown && overlaps(&used, phdr_table))) {
+            let mapped = self.segments.iter().any(|p| p.p_type == value::PT_PHDR);
+            if self.header.e_phoff != 0 && mapped {
+                return Err(Error::invalid("program header table", "grows into following data"));
+            }
+            let word = if self.class == value::ELFCLASS32 { 4 } else { 8 };
+            let after_header = align_up(self.header.e_ehsize as u64, word);
+            self.header.e_phoff = match overlaps(&used, (after_header, phdr_table.1)) {
+                false => after_header,
+                true => align_up(end_of(&used, self.base.len() as u64), word),
+            };
+            used.push((self.header.e_phoff, phdr_table.1));
+        }
+        else if phdr_grown {
+            used.push(phdr_table);
+        }
+
+        let file_end = self.base.len() as u64;
+        for (section, grown) in self.sections.iter_mut().zip(grown) {
+            if !occupies_file(&section.header) {
+                continue;
+            }
+            let size = section.data.len() as u64;
+            let extent = (section.header.sh_offset, size);
+            if grown && size != 0 && (extent.0 == 0 || overlaps(&used, extent)) {
+                if section.header.sh_offset != 0 && (section.header.sh_flags & value::SHF_ALLOC as u64) != 0 {
+                    return Err(Error::invalid(format!("section {}", section.name),
+                        "grows into following data and is mapped by a segment"));
+                }
+                let align = section.header.sh_addralign;
+                if align > MAX_ALIGN || !(align == 0 || align.is_power_of_two()) {
+                    return Err(Error::invalid(format!("section {}", section.name),
+                        format!("sh_addralign {:#x}", align)));
+                }
+                section.header.sh_offset = align_up(end_of(&used, file_end), align);
+            }
+            section.header.sh_size = size;
+            if size != 0 {
+                used.push((section.header.sh_offset, size));
+            }
+        }
+
+        // section header table follows everything else if it has no room
+        let table = (self.header.e_shoff, self.sections.len() as u64 * self.header.e_shentsize as u64);
+        if !self.sections.is_empty() && (self.header.e_shoff == 0 ||
+            (self.sections.len() > section_count && overlaps(&used, table))) {
+            let word = if self.class == value::ELFCLASS32 { 4 } else { 8 };
+            self.header.e_shoff = align_up(end_of(&used, self.base.len() as u64), word);
+        }
+        if self.sections.is_empty() {
+            self.header.e_shoff = 0;
+        }
+        match self.sections.len() {
+            n if n >= value::SHN_LORESERVE => {
+                self.header.e_shnum = 0;
+                self.sections[0].header.sh_size = n as u64;
+            },
+            n => self.header.e_shnum = n as u16,
+        }
+        match self.segments.len() {
+            n if n >= value::PN_XNUM as usize => {
+                self.header.e_phnum = value::PN_XNUM;
+                self.sections[0].header.sh_info = n as u32;
+            },
+            n => self.header.e_phnum = n as u16,
+        }
+        Ok(())
+    }
+
+    // index of the section string table, in sh_link of section 0 when e_shstrndx is SHN_XINDEX
+    pub fn shstrndx(&self) -> usize {
+        match self.header.e_shstrndx as usize {
+            value::SHN_XINDEX => self.sections.first().map_or(value::SHN_UNDEF, |s| s.header.sh_link as usize),
+            n => n,
+        }
+    }
+
+    // index past the 16-bit field goes to section 0, which must be there
+    pub fn set_shstrndx(&mut self, index: usize) {
+        if index >= value::SHN_LORESERVE {
+            self.header.e_shstrndx = value::SHN_XINDEX as u16;
+            self.sections[0].header.sh_link = index as u32;
+        }
+        else {
+            self.header.e_shstrndx = index as u16;
+        }
+    }
+
+    // section and segment counts the header was read with
+    fn header_counts(&self) -> (usize, usize) {
+        let first = self.sections.first().map(|s| &s.header);
+        let sections = match (self.header.e_shnum, first) {
+            (0, Some(first)) if self.header.e_shoff != 0 => first.sh_size as usize,
+            (n, _) => n as usize,
+        };
+        let segments = match (self.header.e_phnum, first) {
+            (value::PN_XNUM, Some(first)) => first.sh_info as usize,
+            (n, _) => n as usize,
+        };
+        (sections, segments)
+    }
+
+    // lay out and serialize: section data, then program headers, section headers and ELF header
+    pub fn to_bytes(&mut self) -> Result<Vec<u8>> {
+        self.layout()?;
+        let header = &self.header;
+        let mut out = self.base.clone();
+        out.resize(self.file_end() as usize, 0);
+
+        for section in self.sections.iter().filter(|s| occupies_file(&s.header) && !s.data.is_empty()) {
+            store_at(&mut out, section.header.sh_offset, &section.data);
+        }
+        for (index, phdr) in self.segments.iter().enumerate() {
+            let mut buf: Vec<u8> = Vec::new();
+            write_program(&mut buf, self.class, self.endian, phdr);
+            store_at(&mut out, header.e_phoff + index as u64 * header.e_phentsize as u64, &buf);
+        }
+        for (index, section) in self.sections.iter().enumerate() {
+            let mut buf: Vec<u8> = Vec::new();
+            write_section(&mut buf, self.class, self.endian, &section.header);
+            store_at(&mut out, header.e_shoff + index as u64 * header.e_shentsize as u64, &buf);
+        }
+        let mut buf: Vec<u8> = Vec::new();
+        write_header(&mut buf, self.class, header);
+        store_at(&mut out, 0, &buf);
+        Ok(out)
+    }
+
+    // end of the last byte any header or content takes in file
+    pub fn file_end(&self) -> u64 {
+        // offsets of empty tables point anywhere and take no room
+        let table_end = |offset: u64, count: usize, size: u16| match count {
+            0 => 0,
+            n => offset.saturating_add(n as u64 * size as u64),
+        };
+        let phdr_end = table_end(self.header.e_phoff, self.segments.len(), self.header.e_phentsize);
+        let shdr_end = table_end(self.header.e_shoff, self.sections.len(), self.header.e_shentsize);
+        let data_end = self.sections.iter()
+            .filter(|s| occupies_file(&s.header) && !s.data.is_empty())
+            .map(|s| s.header.sh_offset.saturating_add(s.data.len() as u64))
+            .max()
+            .unwrap_or(0);
+        [self.base.len() as u64, self.header.e_ehsize as u64, phdr_end, shdr_end, data_end].iter().copied().max().unwrap_or(0)
+    }
+
+    // file content at offset as it was read or written with write_at, None if out of file
+    pub fn bytes_at(&self, offset: u64, size: u64) -> Option<&[u8]> {
+        let start = usize::try_from(offset).ok()?;
+        let end = start.checked_add(usize::try_from(size).ok()?)?;
+        self.base.get(start..end)
+    }
+
+    // overwrite file content at offset, the file grows as needed and data of
+    // sections covering the range is updated alike
+    pub fn write_at(&mut self, offset: u64, data: &[u8]) -> Result<()> {
+        let start = usize::try_from(offset).map_err(|_| Error::truncated("file", offset, data.len() as u64))?;
+        let end = start.checked_add(data.len()).ok_or_else(|| Error::truncated("file", offset, data.len() as u64))?;
+        if end > self.base.len() {
+            self.base.resize(end, 0);
+        }
+        self.base[start..end].copy_from_slice(data);
+        for section in self.sections.iter_mut().filter(|s| occupies_file(&s.header)) {
+            let begin = section.header.sh_offset;
+            let len = section.data.len() as u64;
+            if begin == 0 || !overlaps(&[(begin, len)], (offset, data.len() as u64)) {
+                continue;
+            }
+            let from = begin.max(offset);
+            let to = (begin + len).min(offset + data.len() as u64);
+            section.data[(from - begin) as usize..(to - begin) as usize]
+                .copy_from_slice(&data[(from - offset) as usize..(to - offset) as usize]);
+        }
+        Ok(())
+    }
+
+    pub fn write<W: Write>(&mut self, out: &mut W) -> Result<()> {
+        let data = self.to_bytes()?;
+        out.write_all(&data)?;
+        Ok(())
+    }
+
+    // names are kept at sh_name when the string there still matches
+    fn update_names(&mut self) -> Result<()> {
+        let index = self.shstrndx();
+        match self.sections.get(index) {
+            Some(s) if index != value::SHN_UNDEF && s.header.sh_type == value::SHT_STRTAB => { },
+            _ => return Ok(()),
+        };
+        let mut strings = StringTableBuilder::from_bytes(&self.sections[index].data);
+        for section in self.sections.iter_mut() {
+            if !strings.contains_at(section.header.sh_name as usize, &section.name) {
+                section.header.sh_name = strings.add(&section.name);
+            }
+        }
+        self.sections[index].data = strings.into_bytes();
+        Ok(())
+    }
+}
+
+impl StringTableBuilder {
+    // table holding only the empty string
+    pub fn new() -> StringTableBuilder {
+        StringTableBuilder { data: vec![0] }
+    }
+
+    pub fn from_bytes(data: &[u8]) -> StringTableBuilder {
+        match data.is_empty() {
+            true => StringTableBuilder::new(),
+            false => StringTableBuilder { data: data.to_vec() },
+        }
+    }
+
+    pub fn contains_at(&self, pos: usize, name: &str) -> bool {
+        let end = pos.saturating_add(name.len());
+        self.data.get(pos..end) == Some(name.as_bytes()) && self.data.get(end) == Some(&0)
+    }
+
+    // offset of name, a string ending with name is shared as linkers do
+    pub fn add(&mut self, name: &str) -> u32 {
+        let mut wanted = name.as_bytes().to_vec();
+        wanted.push(0);
+        if let Some(pos) = self.data.windows(wanted.len()).position(|w| w == &wanted[..]) {
+            return pos as u32;
+        }
+        if self.data.last() != Some(&0) {
+            self.data.push(0);
+        }
+        let pos = self.data.len();
+        self.data.extend_from_slice(&wanted);
+        pos as u32
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+impl Default for StringTableBuilder {
+    fn default() -> StringTableBuilder {
+        StringTableBuilder::new()
+    }
+}
+
+// sizes of ELF header, program header and section header by class
+pub fn entry_sizes(class: u8) -> (u64, u64, u64) {
+    match class {
+        value::ELFCLASS32 => (0x34, 0x20, 0x28),
+        _ => (0x40, 0x38, 0x40),
+    }
+}
+
+// encoders by class, ELF32 entries are narrowed from the elf64 types
+pub fn write_header(buf: &mut Vec<u8>, class: u8, header: &elf64::ELFHeader) {
+    if class == value::ELFCLASS32 {
+        elf32::ELFHeader::from(header).write_to(buf);
+    }
+    else {
+        header.write_to(buf);
+    }
+}
+
+pub fn write_program(buf: &mut Vec<u8>, class: u8, endian: Endian, phdr: &elf64::ProgramHeader) {
+    if class == value::ELFCLASS32 {
+        elf32::ProgramHeader::from(phdr).write_to(endian, buf);
+    }
+    else {
+        phdr.write_to(endian, buf);
+    }
+}
+
+pub fn write_section(buf: &mut Vec<u8>, class: u8, endian: Endian, shdr: &elf64::SectionHeader) {
+    if class == value::ELFCLASS32 {
+        elf32::SectionHeader::from(shdr).write_to(endian, buf);
+    }
+    else {
+        shdr.write_to(endian, buf);
+    }
+}
+
+pub fn write_dynamic(buf: &mut Vec<u8>, class: u8, endian: Endian, entry: &elf64::DynamicEntry) {
+    if class == value::ELFCLASS32 {
+        elf32::DynamicEntry::from(entry).write_to(endian, buf);
+    }
+    else {
+        entry.write_to(endian, buf);
+    }
+}
+
+pub fn write_symbol(buf: &mut Vec<u8>, class: u8, endian: Endian, entry: &elf64::SymbolEntry) {
+    if class == value::ELFCLASS32 {
+        elf32::SymbolEntry::from(entry).write_to(endian, buf);
+    }
+    else {
+        entry.write_to(endian, buf);
+    }
+}
+
+pub fn write_rel(buf: &mut Vec<u8>, class: u8, endian: Endian, entry: &elf64::RelEntry) {
+    if class == value::ELFCLASS32 {
+        elf32::RelEntry::from(entry).write_to(endian, buf);
+    }
+    else {
+        entry.write_to(endian, buf);
+    }
+}
+
+pub fn write_rela(buf: &mut Vec<u8>, class: u8, endian: Endian, entry: &elf64::RelaEntry) {
+    if class == value::ELFCLASS32 {
+        elf32::RelaEntry::from(entry).write_to(endian, buf);
+    }
+    else {
+        entry.write_to(endian, buf);
+    }
+}
+
+// SHT_NULL and SHT_NOBITS take no bytes whatever sh_size says
+fn occupies_file(header: &elf64::SectionHeader) -> bool {
+    header.sh_type != value::SHT_NULL && header.sh_type != value::SHT_NOBITS
+}
+
+fn overlaps(used: &[(u64, u64)], (offset, size): (u64, u64)) -> bool {
+    used.iter().any(|&(o, s)| s != 0 && size != 0 && offset < o.saturating_add(s) && o < offset.saturating_add(size))
+}
+
+fn end_of(used: &[(u64, u64)], min: u64) -> u64 {
+    used.iter().filter(|&&(_, s)| s != 0).map(|&(o, s)| o.saturating_add(s)).fold(min, u64::max)
+}
+
+fn align_up(offset: u64, align: u64) -> u64 {
+    match align {
+        0 | 1 => offset,
+        n => offset.div_ceil(n).saturating_mul(n),
+    }
+}
+
+// out is sized for every piece before storing
+fn store_at(out: &mut [u8], offset: u64, data: &[u8]) {
+    let begin = offset as usize;
+    out[begin..begin + data.len()].copy_from_slice(data);
+}
@@ -0,0 +1,100 @@
+use std::{fmt, io, io::Read, path::PathBuf};
+
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug)]
+pub enum Error {
+    // I/O failure, with the file involved when known
+    Io { path: Option<PathBuf>, source: io::Error },
+    // data ends before size bytes of what could be read at offset
+    Truncated { what: String, offset: u64, size: u64 },
+    BadMagic,
+    UnsupportedClass(u8),
+    UnsupportedEncoding(u8),
+    // index into a table of len entries
+    OutOfRange { what: String, index: u64, len: u64 },
+    // field or structure with a value that cannot be decoded
+    Invalid { what: String, reason: String },
+    // malformed line of a text file such as /proc/<pid>/maps
+    Parse { path: PathBuf, line: usize, reason: String },
+    NotFound(String),
+}
+
+impl Error {
+    pub fn io<P: Into<PathBuf>>(path: P, source: io::Error) -> Error {
+        Error::Io { path: Some(path.into()), source }
+    }
+
+    pub fn truncated<S: Into<String>>(what: S, offset: u64, size: u64) -> Error {
+        Error::Truncated { what: what.into(), offset, size }
+    }
+
+    pub fn out_of_range<S: Into<String>>(what: S, index: u64, len: u64) -> Error {
+        Error::OutOfRange { what: what.into(), index, len }
+    }
+
+    pub fn invalid<S: Into<String>, R: Into<String>>(what: S, reason: R) -> Error {
+        Error::Invalid { what: what.into(), reason: reason.into() }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io { path: Some(path), source } => write!(f, "{}: {}", path.display(), source),
+            Error::Io { path: None, source } => write!(f, "{}", source),
+            Error::Truncated { what, offset, size } =>
+                write!(f, "truncated {}: {:#x} bytes at offset {:#x}", what, size, offset),
+            Error::BadMagic => write!(f, "invalid ELF magic"),
+            Error::UnsupportedClass(n) => write!(f, "unsupported ELF class {}", n),
+            Error::UnsupportedEncoding(n) => write!(f, "unsupported data encoding {}", n),
+            Error::OutOfRange { what, index, len } =>
+                write!(f, "{} index {} out of range of {} entries", what, index, len),
+            Error::Invalid { what, reason } => write!(f, "invalid {}: {}", what, reason),
+            Error::Parse { path, line, reason } => write!(f, "{}:{}: {}", path.display(), line, reason),
+            Error::NotFound(what) => write!(f, "{} not found", what),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(source: io::Error) -> Error {
+        Error::Io { path: None, source }
+    }
+}
+
+// seek to offset and fill buf, running out of data is reported as Truncated of what
+pub(crate) fn read_at<T, F>(reader: &mut T, offset: u64, buf: &mut [u8], what: F) -> Result<()>
+    where T: io::Read + io::Seek, F: FnOnce() -> String
+{
+    reader.seek(io::SeekFrom::Start(offset))?;
+    match reader.read_exact(buf) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof =>
+            Err(Error::truncated(what(), offset, buf.len() as u64)),
+        Err(e) => Err(Error::from(e)),
+    }
+}
+
+// size bytes at offset, read as far as data goes so hostile sizes are never allocated up front
+pub(crate) fn read_vec_at<T, F>(reader: &mut T, offset: u64, size: u64, what: F) -> Result<Vec<u8>>
+    where T: io::Read + io::Seek, F: FnOnce() -> String
+{
+    reader.seek(io::SeekFrom::Start(offset))?;
+    let mut data: Vec<u8> = Vec::new();
+    reader.by_ref().take(size).read_to_end(&mut data)?;
+    if (data.len() as u64) != size {
+        return Err(Error::truncated(what(), offset, size));
+    }
+    Ok(data)
+}
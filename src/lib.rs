@@ -1,2 +1,3 @@
 pub mod proc;
-pub mod elf;
\ No newline at end of file
+pub mod elf;
+pub mod error;
@@ -3,7 +3,9 @@ use std::{
     fs::File,
     io::BufReader,
     io::prelude::*,
+    path::Path,
 };
+use crate::error::{Error, Result};
 
 
 #[derive(Clone)]
@@ -74,61 +76,78 @@ impl fmt::Display for Perms {
     }
 }
 
-pub fn read_maps(pid: u64) -> Result<Vec<PageInfo>, String> {
+pub fn read_maps(pid: u64) -> Result<Vec<PageInfo>> {
+    read_maps_file(format!("/proc/{}/maps", pid))
+}
+
+// file in the format of /proc/<pid>/maps, such as a copy kept with a dump
+pub fn read_maps_file<P: AsRef<Path>>(path: P) -> Result<Vec<PageInfo>> {
     // open maps
-    let path = format!("/proc/{}/maps", pid);
-    let maps = match File::open(&path) {
+    let path = path.as_ref();
+    let maps = match File::open(path) {
         Ok(f) => BufReader::new(f),
-        Err(e) => return Err(e.to_string()),
+        Err(e) => return Err(Error::io(path, e)),
     };
 
     // resule
     let mut result: Vec<PageInfo> = Vec::new();
 
-    // read line by line
-    for line in maps.lines() {
+    // read line by line, line numbers start at 1
+    for (index, line) in maps.lines().enumerate() {
         let line_str = match line {
             Ok(s) => s,
-            Err(e) => return Err(e.to_string()),
+            Err(e) => return Err(Error::io(path, e)),
         };
-
-        let data: Vec<&str> = line_str.splitn(6, ' ').collect();
-        // address
-        let address = data[0];
-        let mid = address.find('-').expect(address);
-        let begin = u64::from_str_radix(&address[..mid], 16).expect(address);
-        let end = u64::from_str_radix(&address[(mid+1)..], 16).expect(address);
-        // perms
-        let perms = data[1].as_bytes();
-        let read = perms[0] == b'r';
-        let write = perms[1] == b'w';
-        let execute = perms[2] == b'x';
-        let shared = perms[3] == b's';
-        let private = perms[3] == b'p';
-        let perms = Perms{ read, write, execute, shared, private};
-        // offset
-        let offset = u64::from_str_radix(data[2], 16).expect(data[2]);
-        // dev
-        let dev_str = data[3];
-        let mid = dev_str.find(':').expect(dev_str);
-        let major = u32::from_str_radix(&dev_str[..mid], 16).expect(dev_str);
-        let minor = u32::from_str_radix(&dev_str[(mid+1)..], 16).expect(dev_str);
-        let dev = Dev{ major, minor };
-        // inode
-        let inode: u32 = data[4].parse().expect(data[4]);
-        // pathname
-        let mut pathname = String::new();
-        for (i, c) in data[5].chars().enumerate() {
-            if c == ' ' {
-                continue;
-            }
-            pathname = String::from(&data[5][i..]);
-            break;
+        match parse_line(&line_str) {
+            Some(info) => result.push(info),
+            None => return Err(Error::Parse {
+                path: path.to_path_buf(),
+                line: index + 1,
+                reason: format!("malformed maps line \"{}\"", line_str),
+            }),
         }
-
-        // collect them
-        result.push(PageInfo{ begin, end, perms, offset, dev, inode, pathname });
     }
 
     Ok(result)
 }
+
+// one line of maps, None if any field is malformed
+fn parse_line(line: &str) -> Option<PageInfo> {
+    let data: Vec<&str> = line.splitn(6, ' ').collect();
+    if data.len() < 5 {
+        return None;
+    }
+    // address
+    let address = data[0];
+    let mid = address.find('-')?;
+    let begin = u64::from_str_radix(&address[..mid], 16).ok()?;
+    let end = u64::from_str_radix(&address[(mid+1)..], 16).ok()?;
+    // perms
+    let perms = data[1].as_bytes();
+    if perms.len() < 4 {
+        return None;
+    }
+    let read = perms[0] == b'r';
+    let write = perms[1] == b'w';
+    let execute = perms[2] == b'x';
+    let shared = perms[3] == b's';
+    let private = perms[3] == b'p';
+    let perms = Perms{ read, write, execute, shared, private};
+    // offset
+    let offset = u64::from_str_radix(data[2], 16).ok()?;
+    // dev
+    let dev_str = data[3];
+    let mid = dev_str.find(':')?;
+    let major = u32::from_str_radix(&dev_str[..mid], 16).ok()?;
+    let minor = u32::from_str_radix(&dev_str[(mid+1)..], 16).ok()?;
+    let dev = Dev{ major, minor };
+    // inode
+    let inode: u32 = data[4].parse().ok()?;
+    // pathname, anonymous pages have none
+    let pathname = match data.get(5) {
+        Some(s) => String::from(s.trim_start_matches(' ')),
+        None => String::new(),
+    };
+
+    Some(PageInfo{ begin, end, perms, offset, dev, inode, pathname })
+}
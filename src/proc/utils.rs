@@ -3,21 +3,22 @@ use std::{
     io::BufReader,
     io::prelude::*
 };
+use crate::error::{Error, Result};
 
 
-pub fn package_pid(package: &str) -> Result<u64, String> {
+pub fn package_pid(package: &str) -> Result<u64> {
     // get pid
     let pid = match std::process::Command::new("pidof").arg(package).output() {
         Ok(output) => output,
-        Err(e) => return Err(e.to_string()),
+        Err(e) => return Err(Error::io("pidof", e)),
     };
     // if pidof failed, -1 is return
     if !pid.status.success() {
-        return Err(format!("{} not running or not exist", package));
+        return Err(Error::NotFound(format!("running process of {}", package)));
     }
     let mut pid = match String::from_utf8(pid.stdout) {
         Ok(s) => s,
-        Err(e) => return Err(e.to_string()),
+        Err(e) => return Err(Error::invalid("output of pidof", e.to_string())),
     };
     // remove newline of command output
     if pid.ends_with('\n') {
@@ -26,24 +27,24 @@ pub fn package_pid(package: &str) -> Result<u64, String> {
     // parse pid to integer
     let pid = match pid.parse::<u64>() {
         Ok(p) => p,
-        Err(e) => return Err(e.to_string()),
+        Err(e) => return Err(Error::invalid("output of pidof", format!("{}: \"{}\"", e, pid))),
     };
 
     Ok(pid)
 }
 
-pub fn command_line(pid: u64) -> Result<Vec<String>, String> {
+pub fn command_line(pid: u64) -> Result<Vec<String>> {
     let path = format!("/proc/{}/cmdline", pid);
-    let cmdline = match File::open(path) {
+    let cmdline = match File::open(&path) {
         Ok(f) => BufReader::new(f),
-        Err(e) => return Err(e.to_string()),
+        Err(e) => return Err(Error::io(&path, e)),
     };
 
     let mut result: Vec<String> = Vec::new();
     for s in cmdline.split(b'\0') {
         let s = match s {
-            Ok(raw) => String::from_utf8(raw).expect("bad utf8"),
-            Err(e) => return Err(e.to_string()),
+            Ok(raw) => String::from_utf8_lossy(&raw).into_owned(),
+            Err(e) => return Err(Error::io(&path, e)),
         };
         // cmdline maybe end with '\0\0', so last iter element is empty, skip it
         if !s.is_empty() {
@@ -0,0 +1,62 @@
+// Address translation against section headers of the seeds and a segment with .bss.
+mod common;
+use std::io::Cursor;
+use satori::elf::{address::{self, Location}, elf64, file::Elf, value};
+use common::seed;
+
+
+#[test]
+fn sections_agree() {
+    for name in &["lib32.so", "lib64.so", "relr64.so"] {
+        let data = seed(name);
+        let elf = Elf::load(Cursor::new(&data[..])).unwrap();
+        for section in elf.sections().iter().filter(|s| (s.header.sh_flags & value::SHF_ALLOC as u64) != 0) {
+            let shdr = &section.header;
+            if shdr.sh_size == 0 {
+                continue;
+            }
+            let last = shdr.sh_addr + shdr.sh_size - 1;
+            assert_eq!(elf.section_for_vaddr(shdr.sh_addr).map(|s| s.index), Some(section.index), "{} {}", name, section.name);
+            assert_eq!(elf.section_for_vaddr(last).map(|s| s.index), Some(section.index), "{} {}", name, section.name);
+            assert!(elf.segment_for_vaddr(last).is_some(), "{} {}", name, section.name);
+            if shdr.sh_type == value::SHT_NOBITS {
+                continue;
+            }
+            assert_eq!(elf.vaddr_to_offset(shdr.sh_addr), Some(Location::File(shdr.sh_offset)), "{} {}", name, section.name);
+            assert_eq!(elf.offset_to_vaddr(shdr.sh_offset), Some(shdr.sh_addr), "{} {}", name, section.name);
+        }
+        assert_eq!(elf.vaddr_to_offset(0xdead_0000), None, "{}", name);
+        assert!(elf.section_for_vaddr(0xdead_0000).is_none(), "{}", name);
+    }
+}
+
+#[test]
+fn zero_filled_tail() {
+    let segments = vec![
+        elf64::ProgramHeader { p_type: value::PT_LOAD, p_offset: 0, p_vaddr: 0, p_filesz: 0x800, p_memsz: 0x800, ..Default::default() },
+        elf64::ProgramHeader { p_type: value::PT_NOTE, p_offset: 0x1000, p_vaddr: 0x2000, p_filesz: 0x100, p_memsz: 0x100, ..Default::default() },
+        elf64::ProgramHeader { p_type: value::PT_LOAD, p_offset: 0x800, p_vaddr: 0x1800, p_filesz: 0x100, p_memsz: 0x400, ..Default::default() },
+    ];
+    assert_eq!(address::vaddr_to_offset(&segments, 0x18ff), Some(Location::File(0x8ff)));
+    assert_eq!(address::vaddr_to_offset(&segments, 0x1900), Some(Location::ZeroFill(0)));
+    assert_eq!(address::vaddr_to_offset(&segments, 0x1bff), Some(Location::ZeroFill(0x2ff)));
+    assert_eq!(address::vaddr_to_offset(&segments, 0x1c00), None);
+    // only PT_LOAD maps memory
+    assert_eq!(address::segment_for_vaddr(&segments, 0x2000), None);
+    assert_eq!(address::segment_for_vaddr(&segments, 0x1a00), Some(2));
+    assert_eq!(address::offset_to_vaddr(&segments, 0x7ff), Some(0x7ff));
+    assert_eq!(address::offset_to_vaddr(&segments, 0x880), Some(0x1880));
+    assert_eq!(address::offset_to_vaddr(&segments, 0x900), None);
+
+    let bss = elf64::SectionHeader {
+        sh_type: value::SHT_NOBITS,
+        sh_flags: (value::SHF_ALLOC | value::SHF_WRITE) as u64,
+        sh_addr: 0x1900,
+        sh_size: 0x300,
+        ..Default::default()
+    };
+    let tbss = elf64::SectionHeader { sh_flags: bss.sh_flags | value::SHF_TLS as u64, sh_addr: 0x1800, ..bss.clone() };
+    let sections = [elf64::SectionHeader::default(), tbss, bss];
+    assert_eq!(address::section_for_vaddr(&sections, 0x1880), None);
+    assert_eq!(address::section_for_vaddr(&sections, 0x1a00), Some(2));
+}
@@ -0,0 +1,82 @@
+// ARM exception index entries and their unwind instructions.
+mod common;
+use std::io::Cursor;
+use satori::elf::{arm_exidx::{self, Model, UnwindOp}, eh_frame::{CfaRule, RegisterRule}, file::Elf, view::ElfView};
+use common::seed;
+
+
+// DWARF numbers of ARM registers
+const R7: u64 = 7;
+const SP: u64 = 13;
+const LR: u64 = 14;
+const PC: u64 = 15;
+const D8: u64 = 264;
+
+#[test]
+fn entries() {
+    let mut elf = Elf::load(Cursor::new(seed("exidx32.so"))).unwrap();
+    let exidx = elf.arm_exidx().unwrap().unwrap();
+    assert_eq!(exidx.function_starts(), [0x100, 0x110, 0x120, 0x140, 0x150, 0x160]);
+    assert_eq!(exidx.entries[0].model, Model::Compact { personality: 0, table: None, opcodes: vec![0xa8, 0xb0, 0xb0] });
+    assert_eq!(exidx.entries[1].model, Model::CantUnwind);
+    assert_eq!(exidx.entries[2].model, Model::Compact {
+        personality: 1,
+        table: Some(0x160),
+        opcodes: vec![0x97, 0x43, 0xd0, 0x84, 0x0b, 0xb0],
+    });
+    assert!(matches!(exidx.entries[3].model, Model::Generic { personality: 0x150, table: 0x168, .. }));
+    assert_eq!(exidx.find(0x12c).unwrap().function, 0x120);
+    assert!(exidx.find(0xff).is_none());
+
+    // without section headers the table is found through PT_ARM_EXIDX
+    let mut data = seed("exidx32.so");
+    data[0x20..0x24].fill(0);
+    data[0x30..0x34].fill(0);
+    let mut elf = Elf::load(Cursor::new(&data[..])).unwrap();
+    assert!(elf.sections().is_empty());
+    assert_eq!(elf.arm_exidx().unwrap().unwrap().entries, exidx.entries);
+    assert_eq!(ElfView::parse(&data).unwrap().arm_exidx().unwrap().unwrap().entries, exidx.entries);
+
+    // entry of length pointing past .ARM.extab
+    data[0x18c..0x190].copy_from_slice(&0x100u32.to_le_bytes());
+    assert!(ElfView::parse(&data).unwrap().arm_exidx().is_err());
+
+    // only ARM has .ARM.exidx
+    let mut elf = Elf::load(Cursor::new(seed("lib32.so"))).unwrap();
+    assert!(elf.arm_exidx().unwrap().is_none());
+}
+
+#[test]
+fn rules() {
+    let exidx = ElfView::parse(&seed("exidx32.so")).unwrap().arm_exidx().unwrap().unwrap();
+    // push {r4, lr}
+    let row = exidx.unwind_row(0x104).unwrap().unwrap();
+    assert_eq!(row.cfa, CfaRule::RegisterOffset { register: SP, offset: 8 });
+    assert_eq!(row.register(LR), Some(&RegisterRule::Offset(-4)));
+    assert_eq!(row.register(PC), Some(&RegisterRule::Register(LR)));
+    assert_eq!(exidx.unwind_row(0x114).unwrap(), None);
+    // frame pointer r7 with d8 pushed below
+    let row = exidx.unwind_row(0x120).unwrap().unwrap();
+    assert_eq!(row.cfa, CfaRule::RegisterOffset { register: R7, offset: 8 });
+    assert_eq!(row.register(R7), Some(&RegisterRule::Offset(-8)));
+    assert_eq!(row.register(D8), Some(&RegisterRule::Offset(-24)));
+    let row = exidx.unwind_row(0x15c).unwrap().unwrap();
+    assert_eq!(row.cfa, CfaRule::RegisterOffset { register: SP, offset: 0x408 });
+}
+
+#[test]
+fn opcodes() {
+    assert_eq!(arm_exidx::decode(&[0x3f, 0x40, 0xb1, 0x0f, 0xb8, 0xc7, 0x01, 0xb0, 0x00]).unwrap(), [
+        UnwindOp::AddVsp(0x100),
+        UnwindOp::AddVsp(-4),
+        UnwindOp::PopCore(0x0f),
+        UnwindOp::PopVfp { first: 8, count: 1, fstmfdx: true },
+        UnwindOp::PopWmmxControl(1),
+    ]);
+    assert_eq!(arm_exidx::decode(&[0x80, 0x00]).unwrap(), [UnwindOp::Refuse]);
+    assert_eq!(UnwindOp::PopCore(0xc010).to_string(), "pop {r4, r14, r15}");
+    // spare, reserved and truncated
+    for opcodes in [&[0x9d][..], &[0xb1, 0x10], &[0xb4], &[0xc8, 0xff], &[0x84], &[0xb2, 0x80]].iter() {
+        assert!(arm_exidx::decode(opcodes).is_err(), "{:x?}", opcodes);
+    }
+}
@@ -0,0 +1,27 @@
+// Seeds of tests/corpus for the integration tests, each includes this with mod common.
+// Not every test uses every helper.
+#![allow(dead_code)]
+use std::{fs, path::PathBuf};
+
+
+pub fn corpus_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/corpus")
+}
+
+pub fn seed(name: &str) -> Vec<u8> {
+    fs::read(corpus_dir().join(name)).unwrap()
+}
+
+// name and content of the seeds with one of extensions, sorted by name
+pub fn seeds(extensions: &[&str]) -> Vec<(String, Vec<u8>)> {
+    let dir = corpus_dir();
+    let mut seeds: Vec<(String, Vec<u8>)> = fs::read_dir(&dir)
+        .expect("tests/corpus")
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().and_then(|e| e.to_str()).is_some_and(|e| extensions.contains(&e)))
+        .map(|path| (path.file_name().unwrap().to_string_lossy().into_owned(), fs::read(&path).unwrap()))
+        .collect();
+    seeds.sort();
+    assert!(!seeds.is_empty(), "no seed in {}", dir.display());
+    seeds
+}
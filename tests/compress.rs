@@ -0,0 +1,81 @@
+// Sections compressed with SHF_COMPRESSED and legacy .zdebug sections.
+mod common;
+use std::{borrow::Cow, io::Cursor};
+use satori::elf::{compress::Format, file::Elf, view::ElfView};
+use common::seed;
+
+
+// content of the section named name in the uncompressed seed
+fn plain(seed_name: &str, name: &str) -> Vec<u8> {
+    let mut elf = Elf::load(Cursor::new(seed(seed_name))).unwrap();
+    let index = elf.section_by_name(name).unwrap().index;
+    assert_eq!(elf.section_compression(index).unwrap(), None);
+    elf.raw_section_data(index).unwrap()
+}
+
+#[test]
+fn formats() {
+    // seed, compressed section, uncompressed seed and section, format, class header size
+    let cases = [
+        ("zlib32.o", ".debug_line", "debug32.o", ".debug_line", Format::Zlib, 12),
+        ("zdebug64.so", ".zdebug_line", "debug64.so", ".debug_line", Format::ZlibGnu, 12),
+        ("zstd64.so", ".debug_info", "debug64.so", ".debug_info", Format::Zstd, 24),
+    ];
+    for (name, section, plain_name, plain_section, format, header_size) in cases.iter() {
+        let expected = plain(plain_name, plain_section);
+        let data = seed(name);
+        let mut elf = Elf::load(Cursor::new(&data[..])).unwrap();
+        let index = elf.section_by_name(section).unwrap().index;
+        let compression = elf.section_compression(index).unwrap().unwrap();
+        assert_eq!(compression.format, *format, "{}", name);
+        assert_eq!(compression.compressed_size, elf.sections()[index].header.sh_size, "{}", name);
+        assert_eq!(compression.size, expected.len() as u64, "{}", name);
+        assert_eq!(elf.section_data(index).unwrap(), expected, "{}", name);
+        assert!(elf.raw_section_data(index).unwrap().len() >= *header_size);
+
+        let view = ElfView::parse(&data).unwrap();
+        let section = view.section(index).unwrap();
+        assert_eq!(view.section_compression(&section).unwrap(), Some(compression), "{}", name);
+        assert_eq!(view.uncompressed_data(&section).unwrap().as_ref(), &expected[..], "{}", name);
+    }
+
+    // data stored as is is borrowed
+    let data = seed("zstd64.so");
+    let view = ElfView::parse(&data).unwrap();
+    let section = view.section_by_name(".debug_line").unwrap();
+    assert!(matches!(view.uncompressed_data(&section).unwrap(), Cow::Borrowed(_)));
+}
+
+#[test]
+fn line_programs() {
+    let data = seed("zlib32.o");
+    assert_eq!(ElfView::parse(&data).unwrap().addr2line(0x0).unwrap().unwrap().to_string(), "lib.c:19");
+    assert_eq!(Elf::load(Cursor::new(&data[..])).unwrap().addr2line(0x0).unwrap().unwrap().to_string(), "lib.c:19");
+    let data = seed("zdebug64.so");
+    assert_eq!(ElfView::parse(&data).unwrap().addr2line(0x4e9).unwrap().unwrap().to_string(), "./lib.c:22");
+    assert_eq!(Elf::load(Cursor::new(&data[..])).unwrap().addr2line(0x4e9).unwrap().unwrap().to_string(), "./lib.c:22");
+}
+
+#[test]
+fn malformed() {
+    let data = seed("zlib32.o");
+    let elf = Elf::load(Cursor::new(&data[..])).unwrap();
+    let header = elf.section_by_name(".debug_line").unwrap().header.clone();
+    let index = elf.section_by_name(".debug_line").unwrap().index;
+    let offset = header.sh_offset as usize;
+
+    // unknown ch_type
+    let mut bad = data.clone();
+    bad[offset] = 9;
+    assert!(Elf::load(Cursor::new(&bad[..])).unwrap().section_compression(index).is_err());
+    // corrupt stream and wrong uncompressed size
+    let mut bad = data.clone();
+    bad[offset + 12..offset + 20].fill(0xff);
+    assert!(Elf::load(Cursor::new(&bad[..])).unwrap().section_data(index).is_err());
+    let mut bad = data.clone();
+    bad[offset + 4] = bad[offset + 4].wrapping_add(1);
+    assert!(Elf::load(Cursor::new(&bad[..])).unwrap().section_data(index).is_err());
+    let view = ElfView::parse(&bad).unwrap();
+    assert!(view.uncompressed_data(&view.section(index).unwrap()).is_err());
+    assert!(view.line_programs().is_err());
+}
@@ -0,0 +1,24 @@
+// Source of the seed corpus, rebuild with
+//   F="-fPIC -O1 -fno-asynchronous-unwind-tables"
+//   L="-Wl,-z,max-page-size=0x10 -Wl,-z,noseparate-code -Wl,-z,norelro -Wl,--version-script=lib.map"
+//   gcc $F -shared -nostartfiles $L -Wl,--hash-style=both lib.c -o lib64.so
+//   gcc $F -shared -nostartfiles $L -Wl,-z,pack-relative-relocs lib.c -o relr64.so
+//   gcc $F -c lib.c -o lib64.o
+//   gcc -m32 $F -c lib.c -o lib32.o
+//   ld -m elf_i386 -shared --hash-style=both -z max-page-size=0x10 -z noseparate-code -z norelro \
+//       -z pack-relative-relocs --version-script=lib.map lib32.o -o lib32.so
+//   D="$F -g -fdebug-prefix-map=$PWD=."
+//   gcc $D -shared -nostartfiles $L lib.c -o debug64.so
+//   gcc $D -gdwarf-4 -c lib.c -o debug64.o
+//   gcc -m32 $D -gdwarf-2 -c lib.c -o debug32.o
+// noshdr64.so is lib64.so with e_shoff, e_shnum and e_shstrndx zeroed,
+// packed.aps2 holds the RELA relocations of lib64.so packed as APS2.
+unsigned long strlen(const char *);
+int counter = 3;
+int *table[4] = { &counter, &counter, 0, &counter };
+int add(int a) { return a + counter; }
+int old_add(int a) { return a - counter; }
+__asm__(".symver old_add,add@VER_1");
+int length(const char *s) { return (int)strlen(s) + add(1); }
+static int local_value = 7;
+int *locals[6] = { &local_value, &local_value, &local_value, 0, &local_value, &local_value };
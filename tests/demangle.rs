@@ -0,0 +1,47 @@
+// Itanium C++ and Rust names through the symbol API.
+use satori::elf::{demangle::{self, Scheme}, elf64, file::Symbol};
+
+
+#[test]
+fn cpp() {
+    assert_eq!(demangle::demangle("_ZNSt6vectorIiSaIiEE9push_backERKi").unwrap(),
+        "std::vector<int, std::allocator<int> >::push_back(int const&)");
+    assert_eq!(demangle::demangle("_Z3addii").unwrap(), "add(int, int)");
+    assert_eq!(demangle::demangle("_ZTVSt9exception").unwrap(), "vtable for std::exception");
+    assert_eq!(demangle::demangle("_ZTISt9exception").unwrap(), "typeinfo for std::exception");
+    assert_eq!(demangle::demangle("_ZThn16_N3Foo3barEv").unwrap(), "non-virtual thunk to Foo::bar()");
+    assert_eq!(demangle::demangle("_ZTv0_n24_N3Foo3barEv").unwrap(), "virtual thunk to Foo::bar()");
+    assert_eq!(demangle::demangle_with_scheme("_Z3addii").unwrap().1, Scheme::Cpp);
+}
+
+#[test]
+fn rust() {
+    // hash of legacy names is left out
+    let (name, scheme) = demangle::demangle_with_scheme("_ZN4core3fmt5write17h0123456789abcdefE").unwrap();
+    assert_eq!((name.as_str(), scheme), ("core::fmt::write", Scheme::Rust));
+    let (name, scheme) = demangle::demangle_with_scheme("_RNvCs1234_7mycrate3foo").unwrap();
+    assert_eq!((name.as_str(), scheme), ("mycrate::foo", Scheme::Rust));
+}
+
+#[test]
+fn not_mangled() {
+    for name in &["", "main", "i", "_Z", "_ZN3Foo", "memcpy"] {
+        assert_eq!(demangle::demangle(name), None, "{}", name);
+        assert_eq!(demangle::demangle_or_raw(name), *name);
+    }
+}
+
+#[test]
+fn symbol() {
+    let symbol = Symbol {
+        name: String::from("_ZNSt3__14coutE"),
+        entry: elf64::SymbolEntry::default(),
+        shndx: 0,
+        version: None,
+    };
+    assert_eq!(symbol.demangled().unwrap(), "std::__1::cout");
+    assert_eq!(symbol.demangled_name(), "std::__1::cout");
+    // the mangled name is kept
+    assert_eq!(symbol.name, "_ZNSt3__14coutE");
+    assert_eq!(symbol.versioned_demangled_name(), symbol.demangled_name());
+}
@@ -0,0 +1,65 @@
+// Line number programs of .debug_line and address to source lookup.
+mod common;
+use std::io::Cursor;
+use satori::elf::{dwarf::{self, LineSections}, endian::Endian, file::Elf, view::ElfView};
+use common::seed;
+
+
+// source lines of lib.c: add on 19, old_add on 20 and length on 22
+#[test]
+fn addr2line() {
+    // DWARF 5 keeps the compilation directory as directory 0
+    let mut elf = Elf::load(Cursor::new(seed("debug64.so"))).unwrap();
+    let programs = elf.line_programs().unwrap();
+    assert_eq!(programs.len(), 1);
+    assert_eq!(programs[0].version, 5);
+    assert_eq!(elf.addr2line(0x4d0).unwrap().unwrap().to_string(), "./lib.c:19");
+    let location = elf.addr2line(0x4e9).unwrap().unwrap();
+    assert_eq!((location.path.as_str(), location.line), ("./lib.c", 22));
+    // .text ends at 0x4ff, with the end of the sequence
+    assert_eq!(elf.addr2line(0x4ff).unwrap(), None);
+    assert_eq!(elf.addr2line(0x10).unwrap(), None);
+
+    // addresses of relocatable files are offsets in .text
+    for (name, version) in &[("debug64.o", 4), ("debug32.o", 3)] {
+        let data = seed(name);
+        let view = ElfView::parse(&data).unwrap();
+        assert_eq!(view.line_programs().unwrap()[0].version, *version, "{}", name);
+        assert_eq!(view.addr2line(0x0).unwrap().unwrap().to_string(), "lib.c:19", "{}", name);
+        let mut elf = Elf::load(Cursor::new(&data[..])).unwrap();
+        assert_eq!(elf.addr2line(0x0).unwrap(), view.addr2line(0x0).unwrap(), "{}", name);
+    }
+
+    // no debug info
+    let mut elf = Elf::load(Cursor::new(seed("lib64.so"))).unwrap();
+    assert!(elf.line_programs().unwrap().is_empty());
+    assert_eq!(elf.addr2line(0x4d0).unwrap(), None);
+}
+
+#[test]
+fn rows() {
+    let mut elf = Elf::load(Cursor::new(seed("debug64.so"))).unwrap();
+    let program = &elf.line_programs().unwrap()[0];
+    let last = program.rows.last().unwrap();
+    assert!(last.end_sequence);
+    assert_eq!(last.address, 0x4ff);
+    // rows of a sequence rise in address
+    assert!(program.rows.windows(2).all(|pair| pair[0].address <= pair[1].address));
+    assert_eq!(program.file_path(program.rows[0].file).unwrap(), "./lib.c");
+    assert_eq!(program.file_path(100), None);
+}
+
+#[test]
+fn malformed() {
+    let data = seed("debug64.o");
+    let view = ElfView::parse(&data).unwrap();
+    let debug_line = view.section_data(&view.section_by_name(".debug_line").unwrap().header).unwrap();
+    let sections = |debug_line| LineSections { debug_line, ..Default::default() };
+    assert!(dwarf::parse_line_programs(&sections(debug_line), Endian::Little, 8).is_ok());
+    // unit length past the section
+    assert!(dwarf::parse_line_programs(&sections(&debug_line[..debug_line.len() - 1]), Endian::Little, 8).is_err());
+    // version 6 is unknown
+    let mut copy = debug_line.to_vec();
+    copy[4] = 6;
+    assert!(dwarf::parse_line_programs(&sections(&copy), Endian::Little, 8).is_err());
+}
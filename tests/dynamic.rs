@@ -0,0 +1,127 @@
+// Dynamic section entries, their strings and what DynamicInfo reads through them.
+mod common;
+use std::io::Cursor;
+use satori::elf::{dynamic, file::Elf, value, view::ElfView};
+use common::seed;
+
+
+#[test]
+fn entries() {
+    let mut elf = Elf::load(Cursor::new(seed("lib64.so"))).unwrap();
+    let dynamic = elf.dynamic().unwrap().unwrap();
+    assert_eq!(dynamic.entries.len(), 21);
+    assert_eq!(dynamic.entries.last().unwrap().d_tag, value::DT_NULL);
+    assert_eq!(dynamic.needed(), ["libc.so.6"]);
+    assert_eq!(dynamic.soname(), None);
+    assert_eq!((dynamic.rpath(), dynamic.runpath()), (None, None));
+
+    let expected = [
+        (value::DT_HASH, 0x180), (value::DT_GNU_HASH, 0x1b8), (value::DT_STRTAB, 0x2d0),
+        (value::DT_SYMTAB, 0x1f8), (value::DT_STRSZ, 83), (value::DT_SYMENT, 24),
+        (value::DT_PLTRELSZ, 24), (value::DT_PLTREL, value::DT_RELA as u64), (value::DT_JMPREL, 0x490),
+        (value::DT_RELA, 0x3b8), (value::DT_RELASZ, 216), (value::DT_RELAENT, 24),
+        (value::DT_VERDEF, 0x338), (value::DT_VERDEFNUM, 3), (value::DT_VERNEED, 0x398),
+        (value::DT_VERNEEDNUM, 1), (value::DT_VERSYM, 0x324), (value::DT_RELACOUNT, 5),
+    ];
+    for (tag, value) in expected {
+        assert_eq!(dynamic.get(tag), Some(value), "{}", dynamic::tag_name(tag));
+    }
+    assert_eq!(dynamic.get(value::DT_REL), None);
+    assert_eq!(dynamic.get_all(value::DT_NEEDED).len(), 1);
+    assert!(dynamic::is_string_tag(value::DT_NEEDED) && !dynamic::is_string_tag(value::DT_STRTAB));
+    assert_eq!(dynamic::tag_name(value::DT_STRTAB), "DT_STRTAB");
+
+    // strings come through DT_STRTAB, out of range offsets have none
+    let needed = dynamic.entries.iter().find(|e| e.d_tag == value::DT_NEEDED).unwrap();
+    assert_eq!(dynamic.entry_string(needed).unwrap(), "libc.so.6");
+    assert_eq!(dynamic.string(83), None);
+
+    // PT_DYNAMIC gives the same entries without section headers
+    let mut noshdr = Elf::load(Cursor::new(seed("noshdr64.so"))).unwrap();
+    assert!(noshdr.sections().is_empty());
+    let other = noshdr.dynamic().unwrap().unwrap();
+    let tags = |d: &dynamic::Dynamic| d.entries.iter().map(|e| (e.d_tag, e.d_val)).collect::<Vec<_>>();
+    assert_eq!(tags(&other), tags(&dynamic));
+    assert_eq!(other.needed(), ["libc.so.6"]);
+}
+
+#[test]
+fn info() {
+    let mut elf = Elf::load(Cursor::new(seed("lib64.so"))).unwrap();
+    let info = elf.dynamic_info().unwrap().unwrap();
+    assert_eq!(info.symbols.len(), 9);
+    assert_eq!((info.rela.len(), info.plt_rela.len()), (9, 1));
+    assert!(info.rel.is_empty() && info.plt_rel.is_empty() && info.relr.is_empty());
+    assert_eq!(info.rela[0].r_offset, 0x6e0);
+    assert_eq!(info.plt_rela[0].r_offset, 0x6b0);
+
+    let mut elf = Elf::load(Cursor::new(seed("lib32.so"))).unwrap();
+    let dynamic = elf.dynamic().unwrap().unwrap();
+    assert!(dynamic.needed().is_empty());
+    let info = elf.dynamic_info().unwrap().unwrap();
+    assert_eq!((info.rel.len(), info.plt_rel.len()), (4, 1));
+    let relr: Vec<u64> = info.relr.iter().map(|r| r.r_offset).collect();
+    assert_eq!(relr, [0x414, 0x418, 0x41c, 0x424, 0x428]);
+
+    // relocatables have no dynamic section
+    let mut elf = Elf::load(Cursor::new(seed("lib64.o"))).unwrap();
+    assert!(elf.dynamic().unwrap().is_none());
+    assert!(elf.dynamic_info().unwrap().is_none());
+}
+
+// lib64.so whose section headers are of no use, PT_DYNAMIC has to do
+fn bogus_section_headers() -> Vec<(&'static str, Vec<u8>)> {
+    let data = seed("lib64.so");
+    let view = ElfView::parse(&data).unwrap();
+    let shoff = view.header.e_shoff as usize;
+    let table = shoff..shoff + view.section_count() * 0x40;
+    let mut cases = Vec::new();
+
+    let mut past_end = data.clone();
+    past_end[0x28..0x30].copy_from_slice(&(data.len() as u64 + 0x1000).to_le_bytes());
+    cases.push(("e_shoff past end of file", past_end));
+    let mut zeroed = data.clone();
+    zeroed[table.clone()].iter_mut().for_each(|b| *b = 0);
+    cases.push(("zeroed section headers", zeroed));
+    let mut names = data.clone();
+    names[0x3e..0x40].copy_from_slice(&3u16.to_le_bytes());
+    cases.push(("e_shstrndx not a string table", names));
+    let mut no_table = data.clone();
+    no_table[0x28..0x30].copy_from_slice(&[0; 8]);
+    cases.push(("e_shoff 0 with e_shnum", no_table));
+    let mut garbage = data.clone();
+    let mut state: u32 = 0x2545_f491;
+    for b in garbage[table].iter_mut() {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        *b = state as u8;
+    }
+    cases.push(("garbage section headers", garbage));
+    cases
+}
+
+#[test]
+fn without_section_headers() {
+    let mut elf = Elf::load(Cursor::new(seed("lib64.so"))).unwrap();
+    assert!(elf.warnings.is_empty());
+    let symbols: Vec<String> = elf.dynamic_symbols().unwrap().iter().map(|s| s.versioned_name()).collect();
+    let relocations = |elf: &mut Elf<Cursor<Vec<u8>>>| -> Vec<(u64, u64, Option<i64>, String)> {
+        elf.dynamic_relocations().unwrap().iter()
+            .flat_map(|s| s.relocations.iter().map(|r| (r.offset, r.info, r.addend, r.symbol_name())))
+            .collect()
+    };
+    let expected = relocations(&mut elf);
+    assert_eq!(expected.len(), 10);
+
+    for (case, data) in bogus_section_headers() {
+        let mut elf = Elf::load(Cursor::new(data)).unwrap_or_else(|e| panic!("{}: {}", case, e));
+        assert!(!elf.warnings.is_empty(), "{}", case);
+        assert!(elf.sections().iter().all(|s| s.name.is_empty()), "{}", case);
+        let names: Vec<String> = elf.dynamic_symbols().unwrap().iter().map(|s| s.versioned_name()).collect();
+        assert_eq!(names, symbols, "{}", case);
+        assert_eq!(relocations(&mut elf), expected, "{}", case);
+        assert_eq!(elf.dynamic().unwrap().unwrap().needed(), ["libc.so.6"], "{}", case);
+        assert_eq!(elf.lookup("length").unwrap().unwrap().entry.st_value, 0x4e8, "{}", case);
+    }
+}
@@ -0,0 +1,104 @@
+// Function ranges and CFA rules of .eh_frame, found by .eh_frame_hdr too.
+mod common;
+use std::io::Cursor;
+use satori::elf::{eh_frame::{CfaRule, EhFrame, FunctionRange, RegisterRule}, endian::Endian, file::Elf, value, view::ElfView};
+use common::seed;
+
+
+// DWARF numbers of x86-64 registers
+const RBX: u64 = 3;
+const RSP: u64 = 7;
+const RA: u64 = 16;
+
+// PLT, add, old_add and length of the stripped eh64.so
+fn functions() -> Vec<FunctionRange> {
+    [(0x4e0, 0x20), (0x500, 0xc), (0x50c, 0xc), (0x518, 0x17)].iter()
+        .map(|(start, size)| FunctionRange { start: *start, size: *size })
+        .collect()
+}
+
+#[test]
+fn stripped_functions() {
+    let mut elf = Elf::load(Cursor::new(seed("eh64.so"))).unwrap();
+    assert!(elf.symbols().unwrap().is_empty());
+    let eh_frame = elf.eh_frame().unwrap().unwrap();
+    assert_eq!(eh_frame.functions(), functions());
+    let length = elf.dynamic_symbols().unwrap().into_iter().find(|s| s.name == "length").unwrap();
+    assert_eq!((length.entry.st_value, length.entry.st_size), (0x518, 0x17));
+
+    // without section headers .eh_frame is found through PT_GNU_EH_FRAME
+    let mut data = seed("eh64.so");
+    data[0x28..0x30].fill(0);
+    data[0x3c..0x40].fill(0);
+    let mut elf = Elf::load(Cursor::new(&data[..])).unwrap();
+    assert!(elf.sections().is_empty());
+    assert_eq!(elf.eh_frame().unwrap().unwrap().functions(), functions());
+    let view = ElfView::parse(&data).unwrap();
+    assert_eq!(view.eh_frame().unwrap().unwrap().functions(), functions());
+}
+
+#[test]
+fn search_table() {
+    let mut elf = Elf::load(Cursor::new(seed("eh64.so"))).unwrap();
+    let hdr = elf.eh_frame_hdr().unwrap().unwrap();
+    let eh_frame = elf.eh_frame().unwrap().unwrap();
+    assert_eq!(hdr.eh_frame_ptr, eh_frame.vaddr);
+    assert_eq!(hdr.table.len(), eh_frame.fdes.len());
+    for function in functions() {
+        for address in &[function.start, function.start + function.size - 1] {
+            let fde = eh_frame.fde_at(hdr.lookup(*address).unwrap()).unwrap();
+            assert_eq!(fde.pc_begin, function.start);
+            assert_eq!(eh_frame.find_fde(*address).unwrap().offset, fde.offset);
+        }
+    }
+    assert_eq!(hdr.lookup(0x4df), None);
+}
+
+#[test]
+fn cfa_rules() {
+    let mut elf = Elf::load(Cursor::new(seed("eh64.so"))).unwrap();
+    let eh_frame = elf.eh_frame().unwrap().unwrap();
+    // length pushes rbx for the call to strlen
+    let row = eh_frame.unwind_row(0x518).unwrap().unwrap();
+    assert_eq!(row.cfa, CfaRule::RegisterOffset { register: RSP, offset: 8 });
+    assert_eq!(row.register(RA), Some(&RegisterRule::Offset(-8)));
+    assert_eq!(row.register(RBX), None);
+    let row = eh_frame.unwind_row(0x520).unwrap().unwrap();
+    assert_eq!((row.address, &row.cfa), (0x519, &CfaRule::RegisterOffset { register: RSP, offset: 16 }));
+    assert_eq!(row.register(RBX), Some(&RegisterRule::Offset(-16)));
+    let row = eh_frame.unwind_row(0x52e).unwrap().unwrap();
+    assert_eq!(row.cfa, CfaRule::RegisterOffset { register: RSP, offset: 8 });
+    // the PLT computes its CFA from rip
+    assert!(matches!(eh_frame.unwind_row(0x4f0).unwrap().unwrap().cfa, CfaRule::Expression(_)));
+    assert_eq!(eh_frame.unwind_row(0x52f).unwrap(), None);
+}
+
+#[test]
+fn malformed() {
+    // CIE: version 1, no augmentation, code 1, data -8, ra 16, DW_CFA_def_cfa rsp+8
+    let mut data = vec![0x0c, 0, 0, 0, 0, 0, 0, 0, 1, 0, 1, 0x78, 16, value::DW_CFA_DEF_CFA, 7, 8];
+    // FDE: absolute pc 0x1000, range 0x10, DW_CFA_restore_state without a state
+    data.extend_from_slice(&[0x18, 0, 0, 0, 0x14, 0, 0, 0]);
+    data.extend_from_slice(&0x1000u64.to_le_bytes());
+    data.extend_from_slice(&0x10u64.to_le_bytes());
+    data.extend_from_slice(&[value::DW_CFA_RESTORE_STATE, 0, 0, 0]);
+    let eh_frame = EhFrame::parse(data.clone(), 0, value::ELFCLASS64, Endian::Little).unwrap();
+    assert_eq!(eh_frame.functions(), [FunctionRange { start: 0x1000, size: 0x10 }]);
+    assert!(eh_frame.unwind_row(0x1000).is_err());
+
+    // FDE pointing before the section
+    data[20] = 0x40;
+    assert!(EhFrame::parse(data, 0, value::ELFCLASS64, Endian::Little).is_err());
+
+    // without sections, the PT_LOAD holding eh_frame_ptr ends past u64::MAX
+    let mut data = seed("eh64.so");
+    data[0x28..0x30].fill(0);
+    data[0x3c..0x40].fill(0);
+    let phdr = ElfView::parse(&data).unwrap().header.e_phoff as usize;
+    data[phdr + 0x08..phdr + 0x10].copy_from_slice(&(u64::MAX - 0x600).to_le_bytes());
+    data[phdr + 0x20..phdr + 0x30].copy_from_slice(&[0x1000u64.to_le_bytes(), 0x1000u64.to_le_bytes()].concat());
+    let mut elf = Elf::load(Cursor::new(&data[..])).unwrap();
+    assert!(elf.eh_frame_hdr().unwrap().is_some());
+    assert!(elf.eh_frame().is_err());
+    assert!(ElfView::parse(&data).unwrap().eh_frame().is_err());
+}
@@ -0,0 +1,90 @@
+// Big-endian files decode to what their little-endian counterparts hold.
+mod common;
+use std::io::Cursor;
+use satori::{
+    elf::{dynamic, endian::Endian, file::Elf, value, view::ElfView},
+    error::Error,
+};
+use common::seed;
+
+
+// headers have no PartialEq, their fields are all in Debug
+fn debug<T: std::fmt::Debug>(items: &[T]) -> Vec<String> {
+    items.iter().map(|item| format!("{:?}", item)).collect()
+}
+
+#[test]
+fn same_as_lsb() {
+    for (msb, lsb) in [("msb64.so", "lib64.so"), ("msb32.so", "lib32.so")] {
+        let mut big = Elf::load(Cursor::new(seed(msb))).unwrap();
+        let mut little = Elf::load(Cursor::new(seed(lsb))).unwrap();
+        assert_eq!((big.endian, little.endian), (Endian::Big, Endian::Little));
+        assert_eq!(big.class, little.class, "{}", msb);
+        assert!(big.warnings.is_empty(), "{}", msb);
+
+        // only EI_DATA tells them apart
+        assert_eq!(big.header.e_ident[value::EI_DATA], value::ELFDATA2MSB);
+        let mut header = big.header.clone();
+        header.e_ident[value::EI_DATA] = value::ELFDATA2LSB;
+        assert_eq!(format!("{:?}", header), format!("{:?}", little.header), "{}", msb);
+        assert_eq!(debug(big.segments()), debug(little.segments()), "{}", msb);
+        // yaml2obj lays out string tables its own way, offsets into them differ
+        let sections = |elf: &Elf<Cursor<Vec<u8>>>| -> Vec<String> {
+            let mut sections = elf.sections().to_vec();
+            sections.iter_mut().for_each(|s| s.header.sh_name = 0);
+            debug(&sections)
+        };
+        assert_eq!(sections(&big), sections(&little), "{}", msb);
+        let symbols = |elf: &mut Elf<Cursor<Vec<u8>>>| -> Vec<String> {
+            let mut symbols = elf.symbols().unwrap();
+            symbols.iter_mut().for_each(|s| s.entry.st_name = 0);
+            debug(&symbols)
+        };
+        assert_eq!(symbols(&mut big), symbols(&mut little), "{}", msb);
+        let names = |elf: &mut Elf<Cursor<Vec<u8>>>| -> Vec<String> {
+            elf.dynamic_symbols().unwrap().iter().map(|s| s.versioned_name()).collect()
+        };
+        assert_eq!(names(&mut big), names(&mut little), "{}", msb);
+        // strings are compared by what they say
+        let entries = |elf: &mut Elf<Cursor<Vec<u8>>>| -> Vec<(i64, u64)> {
+            let entries = elf.dynamic().unwrap().unwrap().entries;
+            entries.iter().map(|e| (e.d_tag, if dynamic::is_string_tag(e.d_tag) { 0 } else { e.d_val })).collect()
+        };
+        assert_eq!(entries(&mut big), entries(&mut little), "{}", msb);
+        assert_eq!(big.dynamic().unwrap().unwrap().needed(), little.dynamic().unwrap().unwrap().needed());
+
+        let relocations = |elf: &mut Elf<Cursor<Vec<u8>>>| -> Vec<(u64, u64, Option<i64>, String)> {
+            elf.dynamic_relocations().unwrap().iter()
+                .flat_map(|s| s.relocations.iter().map(|r| (r.offset, r.info, r.addend, r.symbol_name())))
+                .collect()
+        };
+        assert_eq!(relocations(&mut big), relocations(&mut little), "{}", msb);
+        let versions = big.versions().unwrap().unwrap();
+        assert_eq!(versions.versym, little.versions().unwrap().unwrap().versym, "{}", msb);
+        assert_eq!(big.build_id().unwrap(), little.build_id().unwrap(), "{}", msb);
+        // through both hash tables
+        let length = big.lookup_version("length", Some("VER_2")).unwrap().unwrap();
+        assert_eq!(length.entry.st_value, little.lookup("length").unwrap().unwrap().entry.st_value);
+
+        let data = seed(msb);
+        let view = ElfView::parse(&data).unwrap();
+        assert_eq!(view.endian, Endian::Big);
+        let sections: Vec<String> = view.sections().map(|s| s.name.into_owned()).collect();
+        assert_eq!(sections, little.sections().iter().map(|s| s.name.clone()).collect::<Vec<String>>());
+        let symbols: Vec<(String, u64)> = view.dynamic_symbols().map(|s| (s.name.into_owned(), s.entry.st_value)).collect();
+        let expected: Vec<(String, u64)> = little.dynamic_symbols().unwrap().into_iter().map(|s| (s.name, s.entry.st_value)).collect();
+        assert_eq!(symbols, expected, "{}", msb);
+    }
+}
+
+#[test]
+fn unsupported_encoding() {
+    for name in ["lib64.so", "msb32.so"] {
+        let mut data = seed(name);
+        data[value::EI_DATA] = value::ELFDATANONE;
+        assert!(matches!(Elf::load(Cursor::new(&data[..])), Err(Error::UnsupportedEncoding(0))), "{}", name);
+        assert!(matches!(ElfView::parse(&data), Err(Error::UnsupportedEncoding(0))), "{}", name);
+        data[value::EI_DATA] = 3;
+        assert!(matches!(Elf::load(Cursor::new(&data[..])), Err(Error::UnsupportedEncoding(3))), "{}", name);
+    }
+}
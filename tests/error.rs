@@ -0,0 +1,110 @@
+// Errors say what failed and where: file, line, table, index or offset.
+mod common;
+use std::{error::Error as _, fs, io, io::Cursor, path::PathBuf};
+use satori::{
+    elf::{file::Elf, value, view::ElfView},
+    error::Error,
+    proc::maps,
+};
+use common::seed;
+
+
+// file under the temporary directory, unique to this test process
+fn temp_file(name: &str, content: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("satori-{}-{}", std::process::id(), name));
+    fs::write(&path, content).unwrap();
+    path
+}
+
+#[test]
+fn maps() {
+    let good = "7f0000000000-7f0000001000 r-xp 00000000 08:01 1234 /usr/lib/libc.so.6\n\
+                7f0000001000-7f0000002000 rw-p 00000000 00:00 0\n";
+    let path = temp_file("good.maps", good);
+    let pages = maps::read_maps_file(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(pages.len(), 2);
+    assert_eq!((pages[0].begin, pages[0].pathname.as_str()), (0x7f00_0000_0000, "/usr/lib/libc.so.6"));
+    assert!(pages[1].pathname.is_empty() && pages[1].perms.write);
+
+    // the line that does not parse, counted from 1
+    let bad = "7f0000000000-7f0000001000 r-xp 00000000 08:01 1234 /usr/lib/libc.so.6\n\
+               7f0000001000-7f0000002000 rw-p 00000000 0001 0\n";
+    let path = temp_file("bad.maps", bad);
+    let error = maps::read_maps_file(&path).err().unwrap();
+    fs::remove_file(&path).unwrap();
+    match &error {
+        Error::Parse { path: at, line, reason } => {
+            assert_eq!((at, *line), (&path, 2));
+            assert!(reason.contains("rw-p 00000000 0001 0"), "{}", reason);
+        },
+        other => panic!("expected Parse, got {:?}", other),
+    }
+    assert!(error.to_string().starts_with(&format!("{}:2: ", path.display())), "{}", error);
+    assert!(error.source().is_none());
+}
+
+#[test]
+fn io() {
+    let path = std::env::temp_dir().join(format!("satori-{}-missing", std::process::id()));
+    for error in [Elf::open(&path).err().unwrap(), maps::read_maps_file(&path).err().unwrap()] {
+        match &error {
+            Error::Io { path: Some(at), source } => {
+                assert_eq!(at, &path);
+                assert_eq!(source.kind(), io::ErrorKind::NotFound);
+            },
+            other => panic!("expected Io with path, got {:?}", other),
+        }
+        assert!(error.to_string().starts_with(&format!("{}: ", path.display())), "{}", error);
+        // the io::Error is the source
+        let source = error.source().unwrap().downcast_ref::<io::Error>().unwrap();
+        assert_eq!(source.kind(), io::ErrorKind::NotFound);
+    }
+
+    // errors of the reader itself have no path
+    let error = Error::from(io::Error::other("reader failed"));
+    assert!(matches!(error, Error::Io { path: None, .. }));
+    assert_eq!(error.to_string(), "reader failed");
+}
+
+#[test]
+fn elf_context() {
+    // e_shstrndx past the section count leaves sections unnamed, with a warning that says so
+    let mut data = seed("lib64.so");
+    data[0x3e..0x40].copy_from_slice(&200u16.to_le_bytes());
+    let mut elf = Elf::load(Cursor::new(&data[..])).unwrap();
+    let count = elf.sections().len() as u64;
+    assert_eq!(count, 22);
+    assert_eq!(elf.warnings.len(), 1);
+    match &elf.warnings[0] {
+        Error::OutOfRange { what, index, len } => assert_eq!((what.as_str(), *index, *len), ("e_shstrndx", 200, count)),
+        other => panic!("expected OutOfRange, got {:?}", other),
+    }
+    assert_eq!(elf.warnings[0].to_string(), "e_shstrndx index 200 out of range of 22 entries");
+    assert!(elf.sections().iter().all(|s| s.name.is_empty()));
+
+    let error = elf.section_data(30).unwrap_err();
+    assert!(matches!(&error, Error::OutOfRange { what, index: 30, len: 22 } if what == "section"), "{:?}", error);
+
+    // what is read first fails with the structure, offset and size
+    let data = seed("lib64.so");
+    let mut broken = data.clone();
+    broken[value::EI_CLASS] = 9;
+    let error = Elf::load(Cursor::new(&broken[..])).err().unwrap();
+    assert_eq!(error.to_string(), "unsupported ELF class 9");
+    let error = Elf::load(Cursor::new(&data[..0x20])).err().unwrap();
+    match &error {
+        Error::Truncated { what, offset, size } => assert_eq!((what.as_str(), *offset, *size), ("ELF header", 0, 0x40)),
+        other => panic!("expected Truncated, got {:?}", other),
+    }
+    assert_eq!(error.to_string(), "truncated ELF header: 0x40 bytes at offset 0x0");
+
+    // section headers cut off are a warning that says which one
+    let shoff = ElfView::parse(&data).unwrap().header.e_shoff;
+    let elf = Elf::load(Cursor::new(&data[..shoff as usize + 0x40 * 3 + 8])).unwrap();
+    match &elf.warnings[..] {
+        [Error::Truncated { what, offset, size }] =>
+            assert_eq!((what.as_str(), *offset, *size), ("section header 3", shoff + 0xc0, 0x40)),
+        other => panic!("expected Truncated, got {:?}", other),
+    }
+}
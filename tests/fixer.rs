@@ -0,0 +1,119 @@
+// Libraries dumped from memory get their section headers back from the dynamic section.
+mod common;
+use std::io::Cursor;
+use satori::elf::{endian::Endian, file::Elf, fixer, value};
+use common::seed;
+
+
+// pointer tags the dynamic linker relocates by the load base
+const POINTER_TAGS: [i64; 9] = [
+    value::DT_HASH, value::DT_GNU_HASH, value::DT_STRTAB, value::DT_SYMTAB, value::DT_PLTGOT,
+    value::DT_JMPREL, value::DT_RELA, value::DT_REL, value::DT_INIT_ARRAY,
+];
+
+// file laid out as mapped at base, pages past the end of file read as zero.
+// extra entries go in the unused DT_NULL slots of the dynamic section.
+fn memory_image(data: &[u8], base: u64, extra: &[(i64, u64)]) -> Vec<u8> {
+    let elf = Elf::load(Cursor::new(data)).unwrap();
+    let (class, endian) = (elf.class, elf.endian);
+    let loads: Vec<_> = elf.segments().iter().filter(|p| p.p_type == value::PT_LOAD).cloned().collect();
+    let end = loads.iter().map(|p| p.p_vaddr + p.p_memsz).max().unwrap();
+    let mut image = vec![0u8; ((end + 0xfff) & !0xfff) as usize];
+    for phdr in &loads {
+        let (start, offset) = (phdr.p_vaddr as usize, phdr.p_offset as usize);
+        image[start..start + phdr.p_filesz as usize].copy_from_slice(&data[offset..offset + phdr.p_filesz as usize]);
+    }
+
+    let dynamic = elf.segments().iter().find(|p| p.p_type == value::PT_DYNAMIC).unwrap();
+    let word = if class == value::ELFCLASS32 { 4 } else { 8 };
+    let read = |image: &[u8], at: usize| match word {
+        4 => endian.read_u32(&mut &image[at..]) as u64,
+        _ => endian.read_u64(&mut &image[at..]),
+    };
+    let write = |image: &mut Vec<u8>, at: usize, n: u64| {
+        let mut buf: Vec<u8> = Vec::new();
+        match word {
+            4 => endian.write_u32(&mut buf, n as u32),
+            _ => endian.write_u64(&mut buf, n),
+        }
+        image[at..at + word].copy_from_slice(&buf);
+    };
+    let mut extra = extra.iter();
+    for at in (dynamic.p_vaddr as usize..(dynamic.p_vaddr + dynamic.p_memsz) as usize).step_by(word * 2) {
+        let mut tag = read(&image, at) as i64;
+        if tag == value::DT_NULL {
+            match extra.next() {
+                Some((extra_tag, extra_value)) => {
+                    write(&mut image, at, *extra_tag as u64);
+                    write(&mut image, at + word, *extra_value);
+                    tag = *extra_tag;
+                },
+                None => break,
+            }
+        }
+        if POINTER_TAGS.contains(&tag) {
+            let value = read(&image, at + word);
+            write(&mut image, at + word, value + base);
+        }
+    }
+    image
+}
+
+#[test]
+fn lib64() {
+    let base = 0x7f12_3456_0000;
+    // lib64.so has no constructors, table stands in for them
+    let extra = [(value::DT_INIT_ARRAY, 0x720), (value::DT_INIT_ARRAYSZ, 0x20)];
+    let image = memory_image(&seed("lib64.so"), base, &extra);
+    let out = fixer::fix_dump(&image, base).unwrap();
+
+    let mut elf = Elf::load(Cursor::new(&out[..])).unwrap();
+    assert!(elf.warnings.is_empty());
+    assert_eq!(elf.sections().last().unwrap().name, ".shstrtab");
+    // name, type, address, offset, size, link
+    let expected = [
+        (".dynsym", value::SHT_DYNSYM, 0x1f8, 0xd8, ".dynstr"),
+        (".dynstr", value::SHT_STRTAB, 0x2d0, 0x53, ""),
+        (".rela.dyn", value::SHT_RELA, 0x3b8, 0xd8, ".dynsym"),
+        (".rela.plt", value::SHT_RELA, 0x490, 0x18, ".dynsym"),
+        (".dynamic", value::SHT_DYNAMIC, 0x500, 0x190, ".dynstr"),
+        (".init_array", value::SHT_INIT_ARRAY, 0x720, 0x20, ""),
+    ];
+    for (name, sh_type, addr, size, link) in expected {
+        let section = elf.section_by_name(name).unwrap_or_else(|| panic!("no {}", name));
+        let header = &section.header;
+        assert_eq!((header.sh_type, header.sh_addr, header.sh_offset, header.sh_size), (sh_type, addr, addr, size), "{}", name);
+        assert_eq!(elf.sections()[header.sh_link as usize].name, link, "{}", name);
+    }
+
+    // pointers of dynamic section are back to file addresses
+    let dynamic = elf.dynamic().unwrap().unwrap();
+    assert_eq!(dynamic.get(value::DT_SYMTAB), Some(0x1f8));
+    assert_eq!(dynamic.get(value::DT_INIT_ARRAY), Some(0x720));
+    let info = elf.dynamic_info().unwrap().unwrap();
+    assert_eq!(info.init_array.len(), 4);
+    let add = elf.lookup("add").unwrap().unwrap();
+    assert_eq!((add.entry.st_value, add.versioned_name().as_str()), (0x4dc, "add@VER_1"));
+    let relocations = elf.relocations().unwrap();
+    let names: Vec<&str> = relocations.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, [".rela.dyn", ".rela.plt"]);
+    assert_eq!(relocations[1].relocations[0].symbol_name(), "strlen@GLIBC_2.2.5");
+}
+
+#[test]
+fn lib32() {
+    let base = 0xf7a0_0000;
+    let image = memory_image(&seed("lib32.so"), base, &[]);
+    let out = fixer::fix_dump(&image, base).unwrap();
+    let mut elf = Elf::load(Cursor::new(&out[..])).unwrap();
+    assert_eq!(elf.endian, Endian::Little);
+    let dynsym = elf.section_by_name(".dynsym").unwrap();
+    assert_eq!((dynsym.header.sh_addr, dynsym.header.sh_size), (0x12c, 9 * 0x10));
+    let length = elf.lookup("length").unwrap().unwrap();
+    assert_eq!((length.entry.st_value, length.versioned_name().as_str()), (0x2ee, "length@@VER_2"));
+
+    // no PT_LOAD, nothing to rebuild from
+    let mut broken = image.clone();
+    broken[0x2c..0x2e].copy_from_slice(&[0, 0]);
+    assert!(fixer::fix_dump(&broken, base).is_err());
+}
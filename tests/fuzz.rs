@@ -0,0 +1,194 @@
+// Parsers must return errors on hostile input, never panic. Every seed of
+// tests/corpus is fed truncated at each length and with random mutations to
+// every entry point. SATORI_FUZZ_ITERATIONS raises the number of mutations.
+mod common;
+use std::{env, fs, io::Cursor, panic};
+use satori::elf::{arm_exidx::ArmExidx, eh_frame::EhFrame, file::Elf, fixer, patch::Patcher, relocation, value, view::ElfView, writer::ElfWriter};
+
+
+const DEFAULT_ITERATIONS: u64 = 2000;
+
+// values that commonly break size and offset arithmetic
+const INTERESTING: [u64; 10] = [
+    0, 1, 0x7f, 0x80, 0xff, 0xffff, 0x7fff_ffff, 0xffff_ffff, 0x8000_0000_0000_0000, u64::MAX,
+];
+
+// xorshift64, deterministic so failures can be replayed
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n.max(1) as u64) as usize
+    }
+}
+
+fn iterations() -> u64 {
+    env::var("SATORI_FUZZ_ITERATIONS").ok()
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(DEFAULT_ITERATIONS)
+}
+
+// results are dropped, only panics matter
+fn unwind(eh_frame: &EhFrame) {
+    let _ = eh_frame.functions();
+    for fde in &eh_frame.fdes {
+        let _ = eh_frame.unwind_table(fde);
+    }
+}
+
+fn exidx_rules(exidx: &ArmExidx) {
+    let _ = exidx.function_starts();
+    for entry in &exidx.entries {
+        let _ = entry.unwind_row();
+    }
+}
+
+fn exercise(data: &[u8]) {
+    if let Ok(mut elf) = Elf::load(Cursor::new(data)) {
+        for symbols in elf.symbols().iter().chain(elf.dynamic_symbols().iter()) {
+            for symbol in symbols {
+                let _ = symbol.demangled();
+            }
+        }
+        let _ = elf.versions();
+        let _ = elf.hash_table();
+        let _ = elf.lookup("add");
+        let _ = elf.lookup_version("add", Some("VER_1"));
+        let _ = elf.relocations();
+        let _ = elf.dynamic_relocations();
+        let _ = elf.dynamic_info();
+        let _ = elf.interpreter();
+        let _ = elf.section_mapping();
+        let _ = elf.addr2line(0x4e9);
+        let _ = elf.eh_frame_hdr();
+        if let Ok(Some(exidx)) = elf.arm_exidx() {
+            exidx_rules(&exidx);
+        }
+        if let Ok(Some(eh_frame)) = elf.eh_frame() {
+            unwind(&eh_frame);
+        }
+        if let Ok(tables) = elf.notes() {
+            for table in &tables {
+                for note in table.notes() {
+                    let _ = note.decode(value::EM_X86_64).to_string();
+                }
+            }
+        }
+        let _ = elf.groups();
+        let machine = elf.header.e_machine;
+        for section in elf.sections() {
+            let _ = (section.header.section_type(machine).to_string(), section.header.flags(machine).to_string());
+        }
+        for index in 0..elf.sections().len() {
+            let _ = elf.section_data(index);
+            let _ = elf.section_compression(index);
+        }
+    }
+    if let Ok(view) = ElfView::parse(data) {
+        for section in view.sections() {
+            let _ = view.section_data(&section.header);
+            let _ = view.uncompressed_data(&section);
+        }
+        let _ = view.groups();
+        let _ = view.segments().count();
+        let _ = view.symbols().count();
+        let _ = view.dynamic_symbols().count();
+        let _ = view.dynamic().count();
+        let _ = view.interpreter();
+        let _ = view.line_programs();
+        let _ = view.eh_frame_hdr();
+        if let Ok(Some(exidx)) = view.arm_exidx() {
+            exidx_rules(&exidx);
+        }
+        if let Ok(Some(eh_frame)) = view.eh_frame() {
+            unwind(&eh_frame);
+        }
+        for note in view.notes() {
+            let _ = note.decode(value::EM_AARCH64).to_string();
+        }
+    }
+    if let Ok(mut writer) = ElfWriter::from_bytes(data) {
+        let _ = writer.to_bytes();
+    }
+    if let Ok(mut patcher) = Patcher::from_bytes(data) {
+        let _ = patcher.set_symbol_value("add", 0x1000);
+        let _ = patcher.add_needed("libfuzz.so");
+        let _ = patcher.to_bytes();
+    }
+    let _ = fixer::fix_dump(data, 0x7f00_0000_0000);
+    for class in &[value::ELFCLASS32, value::ELFCLASS64] {
+        let _ = relocation::decode_aps2(data, *class);
+    }
+}
+
+fn mutate(rng: &mut Rng, data: &mut Vec<u8>) {
+    for _ in 0..1 + rng.below(4) {
+        if data.is_empty() {
+            return;
+        }
+        let pos = rng.below(data.len());
+        match rng.below(4) {
+            0 => data[pos] ^= 1 << rng.below(8),
+            1 => data[pos] = INTERESTING[rng.below(INTERESTING.len())] as u8,
+            // fields are 2, 4 or 8 bytes wide
+            2 => {
+                let value = INTERESTING[rng.below(INTERESTING.len())].to_le_bytes();
+                let width = [2, 4, 8][rng.below(3)];
+                let end = (pos + width).min(data.len());
+                data[pos..end].copy_from_slice(&value[..end - pos]);
+            },
+            _ => data.truncate(pos),
+        }
+    }
+}
+
+fn check(name: &str, case: &str, data: &[u8]) {
+    if panic::catch_unwind(|| exercise(data)).is_err() {
+        let path = env::temp_dir().join(format!("satori-fuzz-{}-{}", name, case));
+        fs::write(&path, data).unwrap();
+        panic!("{} {} panicked, input saved to {}", name, case, path.display());
+    }
+}
+
+#[test]
+fn truncated_seeds() {
+    for (name, seed) in common::seeds(&["o", "so", "aps2"]) {
+        for len in 0..seed.len() {
+            check(&name, &format!("truncated-{}", len), &seed[..len]);
+        }
+    }
+}
+
+#[test]
+fn mutated_seeds() {
+    let mut rng = Rng(0x5a70_7215_0e1f_0001);
+    for (name, seed) in common::seeds(&["o", "so", "aps2"]) {
+        for n in 0..iterations() {
+            let mut data = seed.clone();
+            mutate(&mut rng, &mut data);
+            check(&name, &format!("mutation-{}", n), &data);
+        }
+    }
+}
+
+#[test]
+fn seeds_parse() {
+    // mutations are only useful if the seeds themselves are understood
+    for (name, seed) in common::seeds(&["o", "so", "aps2"]) {
+        if name.ends_with(".aps2") {
+            assert!(relocation::decode_aps2(&seed, value::ELFCLASS64).is_ok(), "{}", name);
+            continue;
+        }
+        let mut elf = Elf::load(Cursor::new(&seed[..])).unwrap_or_else(|e| panic!("{}: {}", name, e));
+        elf.symbols().unwrap_or_else(|e| panic!("{}: {}", name, e));
+        elf.relocations().unwrap_or_else(|e| panic!("{}: {}", name, e));
+        assert!(ElfView::parse(&seed).is_ok(), "{}", name);
+    }
+}
@@ -0,0 +1,91 @@
+// SysV and GNU hash tables give the same answers as a scan of the dynamic symbols.
+mod common;
+use std::io::Cursor;
+use satori::elf::{
+    file::{Elf, Symbol},
+    hash::{self, GnuHash, HashTable, SysvHash},
+    value,
+};
+use common::seed;
+
+
+// what the hash tables must find, without them
+fn scan(symbols: &[Symbol], name: &str, version: Option<&str>) -> Option<usize> {
+    symbols.iter().position(|s| {
+        let defined = s.entry.st_shndx as usize != value::SHN_UNDEF;
+        let version = match (version, &s.version) {
+            (Some(want), Some(have)) => have.name == want,
+            (Some(_), None) => false,
+            (None, Some(have)) => !have.hidden || have.index == 2,
+            (None, None) => true,
+        };
+        defined && s.name == name && version
+    })
+}
+
+#[test]
+fn functions() {
+    assert_eq!(hash::elf_hash(b""), 0);
+    assert_eq!(hash::elf_hash(b"printf"), 0x077905a6);
+    assert_eq!(hash::elf_hash(b"exit"), 0x0006cf04);
+    assert_eq!(hash::gnu_hash(b""), 5381);
+    assert_eq!(hash::gnu_hash(b"printf"), 0x156b2bb8);
+    assert_eq!(hash::gnu_hash(b"exit"), 0x7c967e3f);
+}
+
+#[test]
+fn lookup() {
+    for name in &["lib64.so", "lib32.so"] {
+        let mut elf = Elf::load(Cursor::new(seed(name))).unwrap();
+        let symbols = elf.dynamic_symbols().unwrap();
+        let sysv = elf.section_by_name(".hash").unwrap().index;
+        let sysv = SysvHash::parse(&elf.section_data(sysv).unwrap(), elf.endian).unwrap();
+        assert_eq!(sysv.chains.len(), symbols.len(), "{}", name);
+        let gnu = match elf.hash_table().unwrap().unwrap() {
+            HashTable::Gnu(table) => table,
+            HashTable::Sysv(_) => panic!("{}: .gnu.hash is preferred", name),
+        };
+        assert_eq!(gnu.symbol_count(), symbols.len() as u64, "{}", name);
+
+        let queries = [
+            ("add", None), ("add", Some("VER_1")), ("add", Some("VER_2")),
+            ("length", None), ("length", Some("VER_2")), ("counter", None), ("table", None),
+            ("locals", Some("VER_2")), ("strlen", None), ("local_value", None), ("", None), ("missing", None),
+        ];
+        for (query, version) in queries {
+            let expected = scan(&symbols, query, version);
+            assert_eq!(sysv.lookup_version(query, version, &symbols), expected, "{} {} {:?}", name, query, version);
+            assert_eq!(gnu.lookup_version(query, version, &symbols), expected, "{} {} {:?}", name, query, version);
+            let found = elf.lookup_version(query, version).unwrap();
+            assert_eq!(found.map(|s| s.entry.st_value), expected.map(|i| symbols[i].entry.st_value));
+        }
+
+        let counter = elf.lookup("counter").unwrap().unwrap();
+        assert_eq!(counter.versioned_name(), "counter@@VER_2");
+        let add = elf.lookup_version("add", Some("VER_1")).unwrap().unwrap();
+        assert_eq!(add.versioned_name(), "add@VER_1");
+        // undefined symbols are never found
+        assert!(elf.lookup("strlen").unwrap().is_none());
+    }
+
+    // without section headers the tables come through PT_DYNAMIC
+    let mut elf = Elf::load(Cursor::new(seed("noshdr64.so"))).unwrap();
+    assert!(matches!(elf.hash_table().unwrap(), Some(HashTable::Gnu(_))));
+    assert_eq!(elf.lookup("length").unwrap().unwrap().entry.st_value, 0x4e8);
+}
+
+#[test]
+fn malformed() {
+    let endian = Elf::load(Cursor::new(seed("lib64.so"))).unwrap().endian;
+    // counts past the end of data
+    assert!(SysvHash::parse(&[0; 4], endian).is_err());
+    assert!(SysvHash::parse(&[0xff, 0xff, 0, 0, 1, 0, 0, 0], endian).is_err());
+    assert!(GnuHash::parse(&[0; 12], endian, value::ELFCLASS64).is_err());
+    assert!(GnuHash::parse(&[1, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0], endian, value::ELFCLASS64).is_err());
+
+    // chains looping back on themselves end
+    let table = SysvHash { buckets: vec![1], chains: vec![0, 1] };
+    assert_eq!(table.lookup("add", &[]), None);
+    let table = GnuHash { symoffset: 1, buckets: vec![0], ..Default::default() };
+    assert_eq!(table.lookup("add", &[]), None);
+}
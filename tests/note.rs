@@ -0,0 +1,102 @@
+// Note entries of SHT_NOTE sections and PT_NOTE segments.
+mod common;
+use std::io::Cursor;
+use satori::elf::{endian::Endian, file::Elf, note::{self, NoteDesc, NoteIter, Property}, value, view::ElfView};
+use common::seed;
+
+
+const BUILD_ID: &str = "4de98669ebddd432bd2ba7b29edbc65026a97f2c";
+
+// little endian entry, name and descriptor padded to align
+fn entry(name: &str, n_type: u32, desc: &[u8], align: usize) -> Vec<u8> {
+    let pad = |data: &mut Vec<u8>| data.resize(data.len().div_ceil(align) * align, 0);
+    let mut data = Vec::new();
+    data.extend_from_slice(&(name.len() as u32 + 1).to_le_bytes());
+    data.extend_from_slice(&(desc.len() as u32).to_le_bytes());
+    data.extend_from_slice(&n_type.to_le_bytes());
+    data.extend_from_slice(name.as_bytes());
+    data.push(0);
+    pad(&mut data);
+    data.extend_from_slice(desc);
+    pad(&mut data);
+    data
+}
+
+fn property(pr_type: u32, bits: u32) -> Vec<u8> {
+    let mut data = Vec::new();
+    for word in &[pr_type, 4, bits, 0] {
+        data.extend_from_slice(&word.to_le_bytes());
+    }
+    data
+}
+
+#[test]
+fn build_id() {
+    let mut elf = Elf::load(Cursor::new(seed("lib64.so"))).unwrap();
+    let tables = elf.notes().unwrap();
+    assert_eq!(tables[0].section.as_deref(), Some(".note.gnu.build-id"));
+    assert_eq!(note::hex(&elf.build_id().unwrap().unwrap()), BUILD_ID);
+    let data = seed("lib64.so");
+    assert_eq!(note::hex(ElfView::parse(&data).unwrap().build_id().unwrap()), BUILD_ID);
+
+    // without section headers the PT_NOTE segment is read
+    let mut elf = Elf::load(Cursor::new(seed("noshdr64.so"))).unwrap();
+    let tables = elf.notes().unwrap();
+    assert_eq!(tables[0].section, None);
+    assert_eq!(note::hex(&elf.build_id().unwrap().unwrap()), BUILD_ID);
+    let data = seed("noshdr64.so");
+    assert_eq!(note::hex(ElfView::parse(&data).unwrap().build_id().unwrap()), BUILD_ID);
+}
+
+#[test]
+fn android_ident() {
+    let mut desc = 21u32.to_le_bytes().to_vec();
+    let mut text = [0u8; 128];
+    text[..4].copy_from_slice(b"r21e");
+    text[64..71].copy_from_slice(b"6352462");
+    desc.extend_from_slice(&text);
+    let data = entry("Android", value::NT_ANDROID_TYPE_IDENT, &desc, 4);
+    let notes: Vec<_> = NoteIter::new(&data, value::ELFCLASS64, Endian::Little, 4).collect();
+    assert_eq!(notes.len(), 1);
+    assert_eq!(note::type_name(notes[0].name, notes[0].n_type), "NT_ANDROID_TYPE_IDENT");
+    let decoded = notes[0].decode(value::EM_AARCH64);
+    assert_eq!(decoded, NoteDesc::AndroidIdent { api_level: 21, ndk_version: Some("r21e"), ndk_build: Some("6352462") });
+    assert_eq!(decoded.to_string(), "Android API level: 21, NDK: r21e (6352462)");
+
+    // older NDKs only write the API level
+    let data = entry("Android", value::NT_ANDROID_TYPE_IDENT, &16u32.to_le_bytes(), 4);
+    let notes: Vec<_> = NoteIter::new(&data, value::ELFCLASS32, Endian::Little, 4).collect();
+    assert_eq!(notes[0].decode(value::EM_ARM).to_string(), "Android API level: 16");
+}
+
+#[test]
+fn properties() {
+    let bti_pac = value::GNU_PROPERTY_AARCH64_FEATURE_1_BTI | value::GNU_PROPERTY_AARCH64_FEATURE_1_PAC;
+    let ibt_shstk = value::GNU_PROPERTY_X86_FEATURE_1_IBT | value::GNU_PROPERTY_X86_FEATURE_1_SHSTK;
+    let mut data = entry("GNU", value::NT_GNU_PROPERTY_TYPE_0,
+        &property(value::GNU_PROPERTY_AARCH64_FEATURE_1_AND, bti_pac), 8);
+    data.extend(entry("GNU", value::NT_GNU_PROPERTY_TYPE_0,
+        &property(value::GNU_PROPERTY_X86_FEATURE_1_AND, ibt_shstk), 8));
+    let notes: Vec<_> = NoteIter::new(&data, value::ELFCLASS64, Endian::Little, 8).collect();
+    assert_eq!(notes.len(), 2);
+
+    let decoded = notes[0].decode(value::EM_AARCH64);
+    assert_eq!(decoded, NoteDesc::Properties(vec![Property::Aarch64Features(bti_pac)]));
+    assert_eq!(decoded.to_string(), "Properties: AArch64 feature: BTI, PAC");
+    let decoded = notes[1].decode(value::EM_X86_64);
+    assert_eq!(decoded, NoteDesc::Properties(vec![Property::X86Features(ibt_shstk)]));
+    assert_eq!(decoded.to_string(), "Properties: x86 feature: IBT, SHSTK");
+
+    // processor-specific types mean nothing on another machine
+    match notes[1].decode(value::EM_AARCH64) {
+        NoteDesc::Properties(list) => assert!(matches!(list[0], Property::Other { .. })),
+        other => panic!("{:?}", other),
+    }
+}
+
+#[test]
+fn truncated() {
+    let data = entry("GNU", value::NT_GNU_BUILD_ID, &[1; 20], 4);
+    assert_eq!(NoteIter::new(&data[..data.len() - 1], value::ELFCLASS64, Endian::Little, 4).count(), 0);
+    assert_eq!(NoteIter::new(&data[..11], value::ELFCLASS64, Endian::Little, 4).count(), 0);
+}
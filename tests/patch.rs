@@ -0,0 +1,107 @@
+// Patched files are read back with the parsers and must show the edits.
+mod common;
+use std::io::Cursor;
+use satori::elf::{elf64, file::Elf, patch::Patcher, value, view::ElfView};
+use common::seed;
+
+
+fn libraries() -> Vec<(&'static str, Vec<u8>)> {
+    ["lib32.so", "lib64.so", "relr64.so"].iter().map(|name| (*name, seed(name))).collect()
+}
+
+#[test]
+fn add_needed() {
+    for (name, data) in libraries() {
+        let mut patcher = Patcher::from_bytes(&data).unwrap();
+        // first name fits spare slots of .dynamic, the others move it
+        for needed in &["libone.so", "libtwo.so", "libthree.so", "libfour.so", "libfive.so"] {
+            patcher.add_needed(needed).unwrap_or_else(|e| panic!("{}: {}", name, e));
+        }
+        let out = patcher.to_bytes().unwrap();
+
+        let mut elf = Elf::load(Cursor::new(&out[..])).unwrap();
+        let dynamic = elf.dynamic().unwrap().unwrap();
+        let needed = dynamic.needed();
+        assert!(needed.len() >= 5, "{}: {:?}", name, needed);
+        assert_eq!(&needed[needed.len() - 5..], &["libone.so", "libtwo.so", "libthree.so", "libfour.so", "libfive.so"], "{}", name);
+        // symbols still resolve through the moved string table
+        assert!(elf.lookup("length").unwrap().is_some(), "{}", name);
+
+        let view = ElfView::parse(&out).unwrap();
+        let loads: Vec<elf64::ProgramHeader> = view.segments().filter(|p| p.p_type == value::PT_LOAD).collect();
+        assert!(loads.windows(2).all(|w| w[0].p_vaddr + w[0].p_memsz <= w[1].p_vaddr), "{}", name);
+        for phdr in &loads {
+            assert_eq!(phdr.p_offset % 0x1000, phdr.p_vaddr % 0x1000, "{}", name);
+        }
+        // program headers are mapped by the appended segment
+        let last = loads.last().unwrap();
+        assert!(view.header.e_phoff >= last.p_offset && view.header.e_phoff < last.p_offset + last.p_filesz, "{}", name);
+    }
+}
+
+#[test]
+fn set_symbol_value() {
+    for (name, data) in libraries() {
+        let mut patcher = Patcher::from_bytes(&data).unwrap();
+        assert!(patcher.set_symbol_value("length", 0x1234).unwrap() >= 1, "{}", name);
+        assert!(patcher.set_symbol_value("no_such_symbol", 0).is_err(), "{}", name);
+        let out = patcher.to_bytes().unwrap();
+        assert_eq!(out.len(), data.len(), "{}", name);
+
+        let mut elf = Elf::load(Cursor::new(&out[..])).unwrap();
+        assert_eq!(elf.lookup("length").unwrap().unwrap().entry.st_value, 0x1234, "{}", name);
+        assert!(elf.symbols().unwrap().iter().filter(|s| s.name == "length").all(|s| s.entry.st_value == 0x1234), "{}", name);
+    }
+}
+
+#[test]
+fn write_vaddr() {
+    for (name, data) in libraries() {
+        let mut elf = Elf::load(Cursor::new(&data[..])).unwrap();
+        let address = elf.lookup("length").unwrap().unwrap().entry.st_value;
+        let mut patcher = Patcher::from_bytes(&data).unwrap();
+        patcher.write_vaddr(address, &[0xcc; 4]).unwrap();
+        assert!(patcher.write_vaddr(0xdead_0000_0000, &[0]).is_err(), "{}", name);
+        let out = patcher.to_bytes().unwrap();
+
+        let view = ElfView::parse(&out).unwrap();
+        let text = view.section_by_name(".text").unwrap();
+        let offset = text.header.sh_offset + address - text.header.sh_addr;
+        assert_eq!(&out[offset as usize..offset as usize + 4], &[0xcc; 4], "{}", name);
+        assert_eq!(patcher.log().len(), 1, "{}", name);
+    }
+}
+
+#[test]
+fn add_section() {
+    for name in &["lib32.o", "lib64.o", "lib32.so", "lib64.so", "noshdr64.so"] {
+        let data = seed(name);
+        let mut patcher = Patcher::from_bytes(&data).unwrap();
+        let note = elf64::SectionHeader { sh_type: value::SHT_PROGBITS, sh_addralign: 1, ..Default::default() };
+        patcher.add_section(".comment.patch", note, b"patched\0".to_vec()).unwrap();
+        let mapped = elf64::SectionHeader {
+            sh_type: value::SHT_PROGBITS,
+            sh_flags: value::SHF_ALLOC as u64,
+            sh_addralign: 8,
+            ..Default::default()
+        };
+        let mapped_result = patcher.add_section(".rodata.patch", mapped, vec![0x5a; 24]);
+        // relocatable files have no segment to map it
+        assert_eq!(mapped_result.is_ok(), name.ends_with(".so"), "{}", name);
+        let out = patcher.to_bytes().unwrap();
+
+        let view = ElfView::parse(&out).unwrap();
+        let section = view.section_by_name(".comment.patch").unwrap_or_else(|| panic!("{}", name));
+        assert_eq!(view.section_data(&section.header).unwrap(), b"patched\0", "{}", name);
+        if let Some(section) = view.section_by_name(".rodata.patch") {
+            assert_eq!(section.header.sh_addr % 8, 0, "{}", name);
+            let phdr = view.segments()
+                .find(|p| p.p_type == value::PT_LOAD && section.header.sh_addr >= p.p_vaddr &&
+                    section.header.sh_addr + 24 <= p.p_vaddr + p.p_filesz)
+                .unwrap_or_else(|| panic!("{}: section not mapped", name));
+            assert_eq!(section.header.sh_offset - phdr.p_offset, section.header.sh_addr - phdr.p_vaddr, "{}", name);
+            assert_eq!(view.section_data(&section.header).unwrap(), &[0x5a; 24][..], "{}", name);
+        }
+        assert!(Elf::load(Cursor::new(&out[..])).is_ok(), "{}", name);
+    }
+}
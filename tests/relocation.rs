@@ -0,0 +1,173 @@
+// Relocation types by e_machine, their symbols as readelf -r shows them, APS2 and RELR tables.
+mod common;
+use std::io::Cursor;
+use satori::elf::{endian::Endian, file::Elf, relocation::{self, RelocationSection}, value};
+use common::seed;
+
+
+// offset, type name, symbol name of each relocation
+fn listing(section: &RelocationSection) -> Vec<(u64, String, String)> {
+    section.relocations.iter().map(|r| (r.offset, r.type_name(), r.symbol_name())).collect()
+}
+
+fn expect(entries: &[(u64, &str, &str)]) -> Vec<(u64, String, String)> {
+    entries.iter().map(|(offset, kind, name)| (*offset, kind.to_string(), name.to_string())).collect()
+}
+
+#[test]
+fn type_names() {
+    let names = [
+        (value::EM_386, 7, "R_386_JUMP_SLOT"),
+        (value::EM_X86_64, 8, "R_X86_64_RELATIVE"),
+        (value::EM_X86_64, 42, "R_X86_64_REX_GOTPCRELX"),
+        (value::EM_ARM, 22, "R_ARM_JUMP_SLOT"),
+        (value::EM_ARM, 23, "R_ARM_RELATIVE"),
+        (value::EM_AARCH64, 1026, "R_AARCH64_JUMP_SLOT"),
+        (value::EM_AARCH64, 1027, "R_AARCH64_RELATIVE"),
+        (value::EM_MIPS, 3, "R_MIPS_REL32"),
+        (value::EM_RISCV, 5, "R_RISCV_JUMP_SLOT"),
+    ];
+    for (machine, rel_type, name) in names {
+        assert_eq!(relocation::type_name(machine, rel_type), Some(name));
+    }
+    assert_eq!(relocation::type_name(value::EM_X86_64, 0xffff), None);
+    assert_eq!(relocation::type_name(0xffff, 1), None);
+
+    // RELR entries stand for the relative type of each machine
+    let relative = [
+        (value::EM_386, "R_386_RELATIVE"), (value::EM_X86_64, "R_X86_64_RELATIVE"),
+        (value::EM_ARM, "R_ARM_RELATIVE"), (value::EM_AARCH64, "R_AARCH64_RELATIVE"),
+        (value::EM_MIPS, "R_MIPS_REL32"), (value::EM_RISCV, "R_RISCV_RELATIVE"),
+    ];
+    for (machine, name) in relative {
+        let rel_type = relocation::relative_type(machine).unwrap();
+        assert_eq!(relocation::type_name(machine, rel_type), Some(name));
+    }
+    assert_eq!(relocation::relative_type(0xffff), None);
+
+    // the type takes all 32 bits of ELF64 r_info, MIPS64 little-endian keeps the symbol first
+    let info = value::relocation64_info(5, 1027);
+    assert_eq!(relocation::split_info(value::EM_AARCH64, value::ELFCLASS64, Endian::Little, info), (5, 1027));
+    let info = 0x0300_0000_0000_0007;
+    assert_eq!(relocation::split_info(value::EM_MIPS, value::ELFCLASS64, Endian::Little, info), (7, 3));
+}
+
+#[test]
+fn lib64() {
+    let mut elf = Elf::load(Cursor::new(seed("lib64.so"))).unwrap();
+    let sections = elf.relocations().unwrap();
+    let names: Vec<(&str, u64)> = sections.iter().map(|s| (s.name.as_str(), s.offset)).collect();
+    assert_eq!(names, [(".rela.dyn", 0x3b8), (".rela.plt", 0x490)]);
+    let counter = "counter@@VER_2";
+    assert_eq!(listing(&sections[0]), expect(&[
+        (0x6e0, "R_X86_64_RELATIVE", ""), (0x6e8, "R_X86_64_RELATIVE", ""), (0x6f0, "R_X86_64_RELATIVE", ""),
+        (0x700, "R_X86_64_RELATIVE", ""), (0x708, "R_X86_64_RELATIVE", ""),
+        (0x690, "R_X86_64_GLOB_DAT", counter), (0x720, "R_X86_64_64", counter),
+        (0x728, "R_X86_64_64", counter), (0x738, "R_X86_64_64", counter),
+    ]));
+    assert!(sections[0].relocations[..5].iter().all(|r| r.addend == Some(0x6c0) && r.symbol.is_none()));
+    assert_eq!(listing(&sections[1]), expect(&[(0x6b0, "R_X86_64_JUMP_SLOT", "strlen@GLIBC_2.2.5")]));
+
+    // the same tables through DT_RELA and DT_JMPREL
+    let dynamic = elf.dynamic_relocations().unwrap();
+    assert_eq!(dynamic.len(), 2);
+    assert_eq!(listing(&dynamic[0]), listing(&sections[0]));
+    assert_eq!(listing(&dynamic[1]), listing(&sections[1]));
+
+    // relocatables refer to .symtab, section symbols have no name
+    let mut elf = Elf::load(Cursor::new(seed("lib64.o"))).unwrap();
+    let sections = elf.relocations().unwrap();
+    assert_eq!(sections[0].name, ".rela.text");
+    assert_eq!(listing(&sections[0]), expect(&[
+        (0x3, "R_X86_64_REX_GOTPCRELX", "counter"), (0xf, "R_X86_64_REX_GOTPCRELX", "counter"),
+        (0x1a, "R_X86_64_PLT32", "strlen"), (0x27, "R_X86_64_PLT32", "add"),
+    ]));
+    assert!(sections[0].relocations.iter().all(|r| r.addend == Some(-4)));
+}
+
+#[test]
+fn lib32() {
+    let mut elf = Elf::load(Cursor::new(seed("lib32.so"))).unwrap();
+    let sections = elf.relocations().unwrap();
+    let names: Vec<&str> = sections.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, [".rel.dyn", ".rel.plt", ".relr.dyn"]);
+    let counter = "counter@@VER_2";
+    assert_eq!(listing(&sections[0]), expect(&[
+        (0x3f8, "R_386_GLOB_DAT", counter), (0x42c, "R_386_32", counter),
+        (0x430, "R_386_32", counter), (0x438, "R_386_32", counter),
+    ]));
+    assert!(sections[0].relocations.iter().all(|r| r.addend.is_none()));
+    // strlen has no version requirement
+    assert_eq!(listing(&sections[1]), expect(&[(0x408, "R_386_JUMP_SLOT", "strlen")]));
+
+    let mut elf = Elf::load(Cursor::new(seed("lib32.o"))).unwrap();
+    let text = &elf.relocations().unwrap()[0];
+    assert_eq!(text.name, ".rel.text");
+    let kinds: Vec<String> = text.relocations.iter().map(|r| r.type_name()).collect();
+    assert_eq!(kinds, [
+        "R_386_PC32", "R_386_GOTPC", "R_386_GOT32X", "R_386_PC32", "R_386_GOTPC",
+        "R_386_GOT32X", "R_386_PC32", "R_386_GOTPC", "R_386_PLT32", "R_386_PLT32",
+    ]);
+    assert_eq!(text.relocations[2].symbol_name(), "counter");
+}
+
+#[test]
+fn packed() {
+    // packed.aps2 holds the symbol relocations of lib64.so, RELATIVE ones would go to RELR
+    let mut elf = Elf::load(Cursor::new(seed("lib64.so"))).unwrap();
+    let info = elf.dynamic_info().unwrap().unwrap();
+    let relative = relocation::relative_type(value::EM_X86_64).unwrap();
+    let expected: Vec<(u64, u64, i64)> = info.rela.iter().chain(&info.plt_rela)
+        .filter(|r| value::relocation64_type(r.r_info) != relative)
+        .map(|r| (r.r_offset, r.r_info, r.r_addend))
+        .collect();
+    assert_eq!(expected.len(), 5);
+    let data = seed("packed.aps2");
+    let entries = relocation::decode_aps2(&data, value::ELFCLASS64).unwrap();
+    let entries: Vec<(u64, u64, i64)> = entries.iter().map(|r| (r.r_offset, r.r_info, r.r_addend)).collect();
+    assert_eq!(entries, expected);
+
+    // ELF32 r_info is widened like the plain tables
+    let entries = relocation::decode_aps2(&data, value::ELFCLASS32).unwrap();
+    assert_eq!(entries.len(), 5);
+    assert_eq!(entries[0].r_info, value::relocation64_info(0, 6));
+
+    assert!(relocation::decode_aps2(b"APS1", value::ELFCLASS64).is_err());
+    assert!(relocation::decode_aps2(&data[..data.len() - 1], value::ELFCLASS64).is_err());
+    // groups larger than what is left of count
+    assert!(relocation::decode_aps2(b"APS2\x01\x00\x02\x08", value::ELFCLASS64).is_err());
+    assert!(relocation::decode_aps2(b"APS2\x00\x00", value::ELFCLASS64).unwrap().is_empty());
+}
+
+#[test]
+fn relr() {
+    let offsets = [0x680, 0x688, 0x690, 0x6a0, 0x6a8];
+    let mut elf = Elf::load(Cursor::new(seed("relr64.so"))).unwrap();
+    let info = elf.dynamic_info().unwrap().unwrap();
+    let relr: Vec<u64> = info.relr.iter().map(|r| r.r_offset).collect();
+    assert_eq!(relr, offsets);
+    let section = elf.relocations().unwrap().into_iter().find(|s| s.name == ".relr.dyn").unwrap();
+    assert_eq!(section.relocations.len(), offsets.len());
+    assert!(section.relocations.iter().all(|r| r.type_name() == "R_X86_64_RELATIVE" && r.symbol.is_none()));
+    let dynamic = elf.dynamic_relocations().unwrap();
+    let table = dynamic.iter().find(|s| s.name == "DT_RELR").unwrap();
+    assert_eq!(listing(table), listing(&section));
+
+    // an address, then a bitmap of the words after it
+    let mut data: Vec<u8> = Vec::new();
+    for word in [0x1000u32, (0b101 << 1) | 1, 0x2000] {
+        Endian::Big.write_u32(&mut data, word);
+    }
+    let entries = relocation::decode_relr(&data, Endian::Big, value::ELFCLASS32, 8);
+    let entries: Vec<u64> = entries.iter().map(|r| r.r_offset).collect();
+    assert_eq!(entries, [0x1000, 0x1004, 0x100c, 0x2000]);
+    // bitmaps go on where the last one ended, a trailing partial word is ignored
+    let mut data: Vec<u8> = Vec::new();
+    for word in [0x1000u64, 0x3, 0x3] {
+        Endian::Little.write_u64(&mut data, word);
+    }
+    data.push(0);
+    let entries = relocation::decode_relr(&data, Endian::Little, value::ELFCLASS64, 8);
+    let entries: Vec<u64> = entries.iter().map(|r| r.r_offset).collect();
+    assert_eq!(entries, [0x1000, 0x1008, 0x1008 + 63 * 8]);
+}
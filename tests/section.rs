@@ -0,0 +1,199 @@
+// Section types and flags, SHT_GROUP and extended section numbering.
+mod common;
+use std::io::Cursor;
+use satori::elf::{
+    elf64, file::Elf, section::{SectionFlags, SectionType}, value, view::ElfView,
+    writer::{self, ElfWriter, OutputSection},
+};
+use common::seed;
+
+
+#[test]
+fn decode() {
+    assert_eq!(SectionType::new(value::SHT_GROUP, value::EM_X86_64).to_string(), "GROUP");
+    assert_eq!(SectionType::new(value::SHT_SYMTAB_SHNDX, value::EM_386).to_string(), "SYMTAB SECTION INDICES");
+    assert_eq!(SectionType::new(value::SHT_GNU_VERSYM, value::EM_X86_64), SectionType::GnuVersym);
+    assert_eq!(SectionType::new(0x70000001, value::EM_ARM), SectionType::ArmExidx);
+    assert_eq!(SectionType::new(0x70000001, value::EM_X86_64), SectionType::X86_64Unwind);
+    assert_eq!(SectionType::new(0x70000003, value::EM_386).to_string(), "LOPROC+0x3");
+    assert_eq!(SectionType::new(0x60000010, value::EM_386).to_string(), "LOOS+0x10");
+    assert_eq!(SectionType::new(0x80000001, value::EM_386).to_string(), "LOUSER+0x1");
+    assert_eq!(SectionType::new(0x20, value::EM_386).to_string(), "<unknown>: 0x20");
+
+    let flags = (value::SHF_ALLOC | value::SHF_EXECINSTR | value::SHF_GROUP) as u64;
+    assert_eq!(SectionFlags::new(flags, value::EM_X86_64).to_string(), "AXG");
+    assert_eq!(SectionFlags::new(0x30, value::EM_X86_64).to_string(), "MS");
+    assert_eq!(SectionFlags::new((value::SHF_ALLOC | value::SHF_X86_64_LARGE) as u64, value::EM_X86_64).to_string(), "Al");
+    assert_eq!(SectionFlags::new((value::SHF_ALLOC | value::SHF_X86_64_LARGE) as u64, value::EM_ARM).to_string(), "Ap");
+    assert_eq!(SectionFlags::new((value::SHF_EXECINSTR | value::SHF_ARM_PURECODE) as u64, value::EM_ARM).to_string(), "Xy");
+    assert_eq!(SectionFlags::new((value::SHF_GNU_RETAIN | value::SHF_EXCLUDE) as u64, value::EM_386).to_string(), "RE");
+    assert_eq!(SectionFlags::new(0x0010_0000 | 0x8, value::EM_386).to_string(), "ox");
+    assert!(SectionFlags::new(flags, value::EM_386).contains(value::SHF_GROUP));
+}
+
+#[test]
+fn groups() {
+    let data = seed("group64.o");
+    let mut elf = Elf::load(Cursor::new(&data[..])).unwrap();
+    let groups = elf.groups().unwrap();
+    let view = ElfView::parse(&data).unwrap();
+    assert_eq!(view.groups().unwrap(), groups);
+
+    // signature, COMDAT, member names
+    let expected: [(&str, bool, &[&str]); 2] = [
+        ("f", true, &[".text.f", ".data.f", ".rela.data.f"]),
+        ("g", false, &[".text.g"]),
+    ];
+    assert_eq!(groups.len(), expected.len());
+    for (group, (signature, comdat, members)) in groups.iter().zip(expected.iter()) {
+        assert_eq!(elf.sections()[group.index].header.sh_type, value::SHT_GROUP);
+        assert_eq!(group.signature, *signature);
+        assert_eq!(group.is_comdat(), *comdat);
+        let names: Vec<&str> = group.members.iter().map(|i| elf.sections()[*i as usize].name.as_str()).collect();
+        assert_eq!(names, *members);
+        for member in &group.members {
+            assert!(elf.sections()[*member as usize].header.flags(value::EM_X86_64).contains(value::SHF_GROUP));
+        }
+    }
+
+    // group words must be whole and hold the flags
+    let index = groups[1].index;
+    let mut writer = ElfWriter::from_bytes(&data).unwrap();
+    writer.sections[index].data.truncate(2);
+    let broken = writer.to_bytes().unwrap();
+    assert!(Elf::load(Cursor::new(&broken[..])).unwrap().groups().is_err());
+    assert!(ElfView::parse(&broken).unwrap().groups().is_err());
+}
+
+// Relocatable seed grown past SHN_LORESERVE sections and PN_XNUM segments.
+// The section string table goes last and symbol add is moved to one of the new sections.
+fn extended(name: &str) -> (Vec<u8>, usize) {
+    let data = seed(name);
+    let mut elf = Elf::load(Cursor::new(&data[..])).unwrap();
+    let add = elf.symbols().unwrap().iter().position(|s| s.name == "add").unwrap();
+    let mut writer = ElfWriter::from_bytes(&data).unwrap();
+    let (class, endian) = (writer.class, writer.endian);
+    let symtab = writer.sections.iter().position(|s| s.header.sh_type == value::SHT_SYMTAB).unwrap();
+
+    let count = value::SHN_LORESERVE + 0x10;
+    while writer.sections.len() < count - 2 {
+        writer.sections.push(OutputSection {
+            name: String::from(".x"),
+            header: elf64::SectionHeader { sh_type: value::SHT_PROGBITS, sh_addralign: 1, ..Default::default() },
+            data: Vec::new(),
+        });
+    }
+    let target = writer.sections.len() - 1;
+
+    // st_shndx of add is SHN_XINDEX, its index is in .symtab_shndx
+    let entry_size = writer.sections[symtab].header.sh_entsize as usize;
+    let symbols = writer.sections[symtab].data.len() / entry_size;
+    let view = ElfView::parse(&data).unwrap();
+    let mut entry = view.symbols().get(add).unwrap().entry;
+    entry.st_shndx = value::SHN_XINDEX as u16;
+    let mut buf: Vec<u8> = Vec::new();
+    writer::write_symbol(&mut buf, class, endian, &entry);
+    writer.sections[symtab].data[add * entry_size..(add + 1) * entry_size].copy_from_slice(&buf);
+    let mut words: Vec<u8> = Vec::new();
+    for i in 0..symbols {
+        endian.write_u32(&mut words, if i == add { target as u32 } else { 0 });
+    }
+    writer.sections.push(OutputSection {
+        name: String::from(".symtab_shndx"),
+        header: elf64::SectionHeader {
+            sh_type: value::SHT_SYMTAB_SHNDX, sh_link: symtab as u32, sh_addralign: 4, sh_entsize: 4, ..Default::default()
+        },
+        data: words,
+    });
+    writer.sections.push(OutputSection {
+        name: String::from(".shstrtab"),
+        header: elf64::SectionHeader { sh_type: value::SHT_STRTAB, sh_addralign: 1, ..Default::default() },
+        data: vec![0],
+    });
+    writer.set_shstrndx(count - 1);
+
+    // program header table at end of file, relocatables have none to grow
+    writer.segments = vec![elf64::ProgramHeader::default(); value::PN_XNUM as usize + 1];
+    writer.header.e_phoff = (writer.file_end() + 7) & !7;
+    (writer.to_bytes().unwrap(), target)
+}
+
+#[test]
+fn extended_numbering() {
+    for name in &["lib64.o", "lib32.o"] {
+        let (data, target) = extended(name);
+        let count = value::SHN_LORESERVE + 0x10;
+        let segments = value::PN_XNUM as usize + 1;
+
+        // the 16-bit fields only say where to look
+        let view = ElfView::parse(&data).unwrap();
+        assert_eq!(view.header.e_shnum, 0, "{}", name);
+        assert_eq!(view.header.e_shstrndx as usize, value::SHN_XINDEX, "{}", name);
+        assert_eq!(view.header.e_phnum, value::PN_XNUM, "{}", name);
+        assert_eq!(view.section_count(), count, "{}", name);
+        assert_eq!(view.segment_count(), segments, "{}", name);
+        assert_eq!(view.section(count - 1).unwrap().name, ".shstrtab");
+        assert_eq!(view.section(target).unwrap().name, ".x");
+        let add = view.symbols().find(|s| s.name == "add").unwrap();
+        assert_eq!((add.entry.st_shndx as usize, add.shndx as usize), (value::SHN_XINDEX, target));
+
+        let mut elf = Elf::load(Cursor::new(&data[..])).unwrap();
+        assert_eq!(elf.sections().len(), count, "{}", name);
+        assert_eq!(elf.segments().len(), segments, "{}", name);
+        assert!(elf.sections().iter().zip(view.sections()).all(|(a, b)| a.name == b.name));
+        assert_eq!(elf.section_by_name(".text").unwrap().header.sh_type, value::SHT_PROGBITS);
+        let symbols = elf.symbols().unwrap();
+        let add = symbols.iter().find(|s| s.name == "add").unwrap();
+        assert_eq!(add.shndx as usize, target);
+        assert!(symbols.iter().filter(|s| s.name != "add").all(|s| s.shndx == s.entry.st_shndx as u32));
+
+        // written back as read
+        let mut writer = ElfWriter::from_bytes(&data).unwrap();
+        assert_eq!(writer.shstrndx(), count - 1);
+        assert!(writer.to_bytes().unwrap() == data, "{} differs after round trip", name);
+    }
+}
+
+#[test]
+fn malformed() {
+    // count in section 0 past the end of file
+    let mut data = seed("lib64.o");
+    let view = ElfView::parse(&data).unwrap();
+    let (shoff, shnum) = (view.header.e_shoff as usize, view.section_count());
+    data[0x3c..0x3e].copy_from_slice(&[0, 0]);
+    data[shoff + 0x20..shoff + 0x28].copy_from_slice(&0x1_0000_0000u64.to_le_bytes());
+    let elf = Elf::load(Cursor::new(&data[..])).unwrap();
+    assert!(elf.sections().is_empty());
+    assert_eq!(elf.warnings.len(), 1);
+    // the view keeps what is in data
+    let view = ElfView::parse(&data).unwrap();
+    assert!(view.section_count() >= shnum);
+    assert!(view.section_count() <= (data.len() - shoff) / 0x40);
+    assert_eq!(view.section(1).unwrap().name, ".text");
+
+    // no section string table at SHN_XINDEX without section 0 saying where
+    let mut data = seed("lib64.o");
+    data[0x3e..0x40].copy_from_slice(&[0xff, 0xff]);
+    let elf = Elf::load(Cursor::new(&data[..])).unwrap();
+    assert!(elf.sections().iter().all(|s| s.name.is_empty()));
+
+    // e_shoff 0 is no table, the ELF header is not read as section 0
+    let mut data = seed("noshdr64.so");
+    let mut reader = Cursor::new(&data[..]);
+    let header = elf64::ELFHeader::load(&mut reader).unwrap();
+    assert_eq!((header.e_shoff, header.e_shnum), (0, 0));
+    let table = elf64::SectionTable::load(&mut reader, &header).unwrap();
+    assert!(table.data.is_empty());
+    assert_eq!(table.num, 0);
+    // nor is e_shentsize of a file without sections checked
+    data[0x3a..0x3c].copy_from_slice(&[0, 0]);
+    let mut reader = Cursor::new(&data[..]);
+    let header = elf64::ELFHeader::load(&mut reader).unwrap();
+    assert!(elf64::SectionTable::load(&mut reader, &header).unwrap().data.is_empty());
+    // with sections it is
+    let mut data = seed("lib64.so");
+    data[0x3a..0x3c].copy_from_slice(&[0x20, 0]);
+    let mut reader = Cursor::new(&data[..]);
+    let header = elf64::ELFHeader::load(&mut reader).unwrap();
+    assert!(elf64::SectionTable::load(&mut reader, &header).is_err());
+}
@@ -0,0 +1,39 @@
+// Segment types, flags and the section to segment mapping of readelf -l.
+mod common;
+use std::io::Cursor;
+use satori::elf::{file::Elf, segment::{SegmentFlags, SegmentType}, value};
+use common::seed;
+
+
+#[test]
+fn decode() {
+    assert_eq!(SegmentType::new(value::PT_ARM_EXIDX, value::EM_ARM), SegmentType::ArmExidx);
+    assert_eq!(SegmentType::new(value::PT_ARM_EXIDX, value::EM_X86_64), SegmentType::Processor(value::PT_ARM_EXIDX));
+    assert_eq!(SegmentType::new(value::PT_GNU_RELRO, value::EM_X86_64).to_string(), "GNU_RELRO");
+    assert_eq!(SegmentType::new(0x6000_0010, value::EM_X86_64).to_string(), "LOOS+0x10");
+    assert_eq!(SegmentFlags(value::PF_R | value::PF_X).to_string(), "R E");
+    assert_eq!(SegmentFlags(value::PF_R | value::PF_W | 0x0010_0000).to_string(), "RW  0x100000");
+}
+
+#[test]
+fn mapping() {
+    let mut elf = Elf::load(Cursor::new(seed("lib64.so"))).unwrap();
+    assert_eq!(elf.interpreter().unwrap(), None);
+    let mapping = elf.section_mapping();
+    assert_eq!(mapping.len(), elf.segments().len());
+    for section in elf.sections() {
+        let loads = elf.segments().iter().zip(&mapping)
+            .filter(|(phdr, sections)| phdr.p_type == value::PT_LOAD && sections.contains(&section.index))
+            .count();
+        let alloc = (section.header.sh_flags & value::SHF_ALLOC as u64) != 0;
+        match (alloc, section.header.sh_size) {
+            (false, _) => assert_eq!(loads, 0, "{}", section.name),
+            // empty sections belong to a segment only when inside it
+            (true, 0) => assert!(loads <= 1, "{}", section.name),
+            (true, _) => assert_eq!(loads, 1, "{}", section.name),
+        }
+    }
+    let dynamic = elf.segments().iter().position(|p| p.p_type == value::PT_DYNAMIC).unwrap();
+    let names: Vec<&str> = mapping[dynamic].iter().map(|i| elf.sections()[*i].name.as_str()).collect();
+    assert_eq!(names, [".dynamic"]);
+}
@@ -0,0 +1,76 @@
+// .symtab and .dynsym with names from their linked string tables and decoded st_info/st_other.
+mod common;
+use std::io::Cursor;
+use satori::elf::{
+    elf64, file::Elf, symbol::{SymbolBind, SymbolType, SymbolVisibility}, value,
+};
+use common::seed;
+
+
+#[test]
+fn tables() {
+    // dynamic symbols: name, value, size, bind, type
+    let expected: [(&str, u64, u64, SymbolBind, SymbolType); 9] = [
+        ("", 0, 0, SymbolBind::Local, SymbolType::NoType),
+        ("strlen", 0, 0, SymbolBind::Global, SymbolType::Func),
+        ("length", 0x4e8, 23, SymbolBind::Global, SymbolType::Func),
+        ("VER_1", 0, 0, SymbolBind::Global, SymbolType::Object),
+        ("VER_2", 0, 0, SymbolBind::Global, SymbolType::Object),
+        ("add", 0x4dc, 12, SymbolBind::Global, SymbolType::Func),
+        ("table", 0x720, 32, SymbolBind::Global, SymbolType::Object),
+        ("locals", 0x6e0, 48, SymbolBind::Global, SymbolType::Object),
+        ("counter", 0x6c4, 4, SymbolBind::Global, SymbolType::Object),
+    ];
+    let mut elf = Elf::load(Cursor::new(seed("lib64.so"))).unwrap();
+    let symbols = elf.dynamic_symbols().unwrap();
+    assert_eq!(symbols.len(), expected.len());
+    for (symbol, (name, st_value, st_size, bind, kind)) in symbols.iter().zip(expected) {
+        assert_eq!(symbol.name, name);
+        assert_eq!((symbol.entry.st_value, symbol.entry.st_size), (st_value, st_size), "{}", name);
+        assert_eq!((symbol.entry.bind(), symbol.entry.symbol_type()), (bind, kind), "{}", name);
+        assert_eq!(symbol.entry.visibility(), SymbolVisibility::Default);
+    }
+    assert_eq!(symbols[1].entry.st_shndx as usize, value::SHN_UNDEF);
+    assert_eq!(symbols[3].entry.st_shndx as usize, value::SHN_ABS);
+
+    // .symtab links to .strtab, not .dynstr
+    let symbols = elf.symbols().unwrap();
+    assert_eq!(symbols.len(), 16);
+    let local = symbols.iter().find(|s| s.name == "local_value").unwrap();
+    assert_eq!((local.entry.bind(), local.entry.symbol_type()), (SymbolBind::Local, SymbolType::Object));
+    assert_eq!(symbols[1].name, "lib.c");
+    assert_eq!(symbols[1].entry.symbol_type(), SymbolType::File);
+    assert!(symbols.iter().all(|s| s.version.is_none()));
+
+    let mut elf = Elf::load(Cursor::new(seed("lib32.so"))).unwrap();
+    let names: Vec<String> = elf.dynamic_symbols().unwrap().into_iter().map(|s| s.name).collect();
+    assert_eq!(names, ["", "strlen", "length", "VER_1", "table", "add", "VER_2", "locals", "counter"]);
+    assert_eq!(elf.symbols().unwrap().len(), 18);
+}
+
+#[test]
+fn load_linked() {
+    let data = seed("lib64.so");
+    let mut reader = Cursor::new(&data[..]);
+    let header = elf64::ELFHeader::load(&mut reader).unwrap();
+    let sections = elf64::SectionTable::load(&mut reader, &header).unwrap();
+    let dynsym = sections.data.iter().find(|s| s.sh_type == value::SHT_DYNSYM).unwrap();
+    let table = elf64::SymbolTable::load_linked(&mut reader, &header, &sections, dynsym).unwrap();
+    assert_eq!(table.data.len(), 9);
+    assert_eq!(table.name(&table.data[8]), "counter");
+    // names past the string table are empty
+    let mut entry = table.data[8].clone();
+    entry.st_name = 0xffff;
+    assert_eq!(table.name(&entry), "");
+
+    // without load_linked there are no names
+    let table = elf64::SymbolTable::load(&mut reader, &header, dynsym).unwrap();
+    assert_eq!(table.name(&table.data[8]), "");
+
+    // anything else is not a symbol table
+    let strtab = &sections.data[dynsym.sh_link as usize];
+    assert!(elf64::SymbolTable::load(&mut reader, &header, strtab).is_err());
+    let mut broken = dynsym.clone();
+    broken.sh_size -= 1;
+    assert!(elf64::SymbolTable::load(&mut reader, &header, &broken).is_err());
+}
@@ -0,0 +1,64 @@
+// Symbol versions of .gnu.version, .gnu.version_d and .gnu.version_r joined onto dynamic symbols.
+mod common;
+use std::io::Cursor;
+use satori::elf::{file::Elf, value};
+use common::seed;
+
+
+#[test]
+fn tables() {
+    for name in &["lib64.so", "noshdr64.so"] {
+        let mut elf = Elf::load(Cursor::new(seed(name))).unwrap();
+        let versions = elf.versions().unwrap().unwrap();
+        assert_eq!(versions.versym, [0, 4, 3, 2, 3, 0x8002, 3, 3, 3], "{}", name);
+
+        // base definition names the file, VER_2 has VER_1 as parent
+        let defs: Vec<(u16, &[String])> = versions.defs.iter().map(|d| (d.index, &d.names[..])).collect();
+        assert_eq!(defs.len(), 3, "{}", name);
+        assert_eq!(defs[0], (1, &[String::from("lib64.so")][..]));
+        assert_eq!(defs[1], (2, &[String::from("VER_1")][..]));
+        assert_eq!(defs[2], (3, &[String::from("VER_2"), String::from("VER_1")][..]));
+        assert_ne!(versions.defs[0].flags & value::VER_FLG_BASE, 0);
+        assert_eq!(versions.defs[1].name(), "VER_1");
+
+        assert_eq!(versions.needs.len(), 1);
+        assert_eq!(versions.needs[0].file, "libc.so.6");
+        let required: Vec<(u16, &str)> = versions.needs[0].versions.iter().map(|v| (v.index, v.name.as_str())).collect();
+        assert_eq!(required, [(4, "GLIBC_2.2.5")]);
+
+        assert!(versions.symbol_version(0).is_none());
+        assert!(versions.symbol_version(9).is_none());
+        let add = versions.symbol_version(5).unwrap();
+        assert_eq!((add.index, add.name.as_str(), add.hidden, add.file.as_deref()), (2, "VER_1", true, None));
+        let strlen = versions.symbol_version(1).unwrap();
+        assert_eq!(strlen.file.as_deref(), Some("libc.so.6"));
+    }
+
+    // versions without requirements
+    let mut elf = Elf::load(Cursor::new(seed("lib32.so"))).unwrap();
+    let versions = elf.versions().unwrap().unwrap();
+    assert_eq!(versions.defs.len(), 3);
+    assert!(versions.needs.is_empty());
+    // relocatables have none
+    let mut elf = Elf::load(Cursor::new(seed("lib64.o"))).unwrap();
+    assert!(elf.versions().unwrap().is_none());
+}
+
+#[test]
+fn symbols() {
+    let mut elf = Elf::load(Cursor::new(seed("lib64.so"))).unwrap();
+    let names: Vec<String> = elf.dynamic_symbols().unwrap().iter().map(|s| s.versioned_name()).collect();
+    assert_eq!(names, [
+        "", "strlen@GLIBC_2.2.5", "length@@VER_2", "VER_1@@VER_1", "VER_2@@VER_2",
+        "add@VER_1", "table@@VER_2", "locals@@VER_2", "counter@@VER_2",
+    ]);
+
+    // the hidden first version still binds unversioned references
+    let add = elf.lookup("add").unwrap().unwrap();
+    assert_eq!((add.entry.st_value, add.versioned_name().as_str()), (0x4dc, "add@VER_1"));
+    assert_eq!(elf.lookup_version("add", Some("VER_1")).unwrap().unwrap().entry.st_value, 0x4dc);
+    assert!(elf.lookup_version("add", Some("VER_2")).unwrap().is_none());
+    assert!(elf.lookup_version("length", Some("VER_1")).unwrap().is_none());
+    assert_eq!(elf.lookup_version("length", Some("VER_2")).unwrap().unwrap().entry.st_value, 0x4e8);
+    assert!(elf.lookup_version("strlen", Some("GLIBC_2.2.5")).unwrap().is_none());
+}
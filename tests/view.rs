@@ -0,0 +1,143 @@
+// Borrowed parsing over byte slices gives what Elf reads through its reader.
+mod common;
+use std::io::Cursor;
+use satori::{
+    elf::{file::Elf, value, view::{self, ElfView}},
+    error::Error,
+};
+use common::seed;
+
+
+// str lies inside data, nothing was copied
+fn borrowed(data: &[u8], s: &str) -> bool {
+    s.is_empty() || data.as_ptr_range().contains(&s.as_ptr())
+}
+
+#[test]
+fn same_as_elf() {
+    for name in &["lib32.so", "lib64.so", "lib32.o", "lib64.o", "relr64.so", "noshdr64.so"] {
+        let data = seed(name);
+        let view = ElfView::parse(&data).unwrap();
+        let mut elf = Elf::load(Cursor::new(&data[..])).unwrap();
+        assert_eq!((view.class, view.endian), (elf.class, elf.endian), "{}", name);
+        assert_eq!(view.header.e_entry, elf.header.e_entry, "{}", name);
+        assert!(std::ptr::eq(view.data(), &data[..]));
+
+        assert_eq!(view.section_count(), elf.sections().len(), "{}", name);
+        for (section, other) in view.sections().zip(elf.sections().to_vec()) {
+            assert_eq!((section.index, section.name.as_ref()), (other.index, other.name.as_str()), "{}", name);
+            assert_eq!(section.header.sh_offset, other.header.sh_offset);
+            assert!(borrowed(&data, &section.name));
+            let content = view.section_data(&section.header).unwrap();
+            assert_eq!(content, &elf.raw_section_data(section.index).unwrap()[..], "{} {}", name, section.name);
+        }
+        let segments: Vec<(u32, u64)> = view.segments().map(|p| (p.p_type, p.p_vaddr)).collect();
+        let expected: Vec<(u32, u64)> = elf.segments().iter().map(|p| (p.p_type, p.p_vaddr)).collect();
+        assert_eq!(segments, expected, "{}", name);
+
+        // names and decoded entries of both symbol tables
+        let symbols = elf.symbols().unwrap();
+        assert_eq!(view.symbols().len(), symbols.len(), "{}", name);
+        for (symbol, other) in view.symbols().zip(&symbols) {
+            assert_eq!((symbol.name.as_ref(), symbol.entry.st_value, symbol.shndx), (other.name.as_str(), other.entry.st_value, other.shndx));
+            assert!(borrowed(&data, &symbol.name));
+        }
+        // without section headers Elf reads them through DT_SYMTAB, the view has none
+        if elf.sections().is_empty() {
+            assert_eq!(view.dynamic_symbols().len(), 0);
+        } else {
+            let names: Vec<String> = elf.dynamic_symbols().unwrap().into_iter().map(|s| s.name).collect();
+            assert_eq!(view.dynamic_symbols().map(|s| s.name.into_owned()).collect::<Vec<String>>(), names, "{}", name);
+        }
+
+        // entries up to DT_NULL
+        let entries: Vec<(i64, u64)> = view.dynamic().map(|e| (e.d_tag, e.d_val)).collect();
+        let expected: Vec<(i64, u64)> = match elf.dynamic().unwrap() {
+            Some(d) => d.entries.iter().take_while(|e| e.d_tag != value::DT_NULL).map(|e| (e.d_tag, e.d_val)).collect(),
+            None => Vec::new(),
+        };
+        assert_eq!(entries, expected, "{}", name);
+    }
+}
+
+#[test]
+fn lookups() {
+    let data = seed("lib64.so");
+    let view = ElfView::parse(&data).unwrap();
+    let dynsym = view.section_by_name(".dynsym").unwrap();
+    assert_eq!(dynsym.header.sh_type, value::SHT_DYNSYM);
+    assert!(view.section_by_name(".missing").is_none());
+    assert!(view.section(view.section_count()).is_none());
+    assert!(view.segment(view.segment_count()).is_none());
+
+    let symbols = view.dynamic_symbols();
+    assert_eq!(symbols.len(), 9);
+    let counter = symbols.get(8).unwrap();
+    assert_eq!((counter.index, counter.name.as_ref(), counter.entry.st_value), (8, "counter", 0x6c4));
+    assert!(symbols.get(9).is_none());
+    // get does not move the iteration
+    let mut symbols = view.dynamic_symbols();
+    symbols.next();
+    assert_eq!(symbols.get(0).unwrap().index, 0);
+    assert_eq!(symbols.len(), 8);
+
+    assert_eq!(view.slice(0, 4).unwrap(), b"\x7fELF");
+    assert!(view.slice(data.len() as u64, 1).is_none());
+    assert!(view.slice(u64::MAX, 2).is_none());
+    assert_eq!(view.offset_to_vaddr(0x500), Some(0x500));
+    assert_eq!(view.section_for_vaddr(0x4e8).unwrap().name, ".text");
+    assert_eq!(view.interpreter(), None);
+
+    assert_eq!(view::cstr(b"ab\0cd", 0), Some(&b"ab"[..]));
+    assert_eq!(view::cstr(b"ab\0cd", 3), Some(&b"cd"[..]));
+    assert_eq!(view::cstr(b"ab", 2), Some(&b""[..]));
+    assert_eq!(view::cstr(b"ab", 3), None);
+    assert_eq!(view::cstr(b"\xff\0", 0), Some(&b"\xff"[..]));
+}
+
+#[test]
+fn names_not_utf8() {
+    // .text renamed to bytes that are not UTF-8, and the name of symbol add
+    let mut data = seed("lib64.so");
+    let view = ElfView::parse(&data).unwrap();
+    let shstrtab = view.section(view.header.e_shstrndx as usize).unwrap().header.sh_offset;
+    let text = view.section_by_name(".text").unwrap().header.sh_name as u64;
+    let strtab = view.section_by_name(".strtab").unwrap().header.sh_offset;
+    let add = view.symbols().find(|s| s.name == "add").unwrap().entry.st_name as u64;
+    for offset in [shstrtab + text + 1, strtab + add] {
+        data[offset as usize] = 0xff;
+    }
+
+    let view = ElfView::parse(&data).unwrap();
+    let mut elf = Elf::load(Cursor::new(&data[..])).unwrap();
+    let section = view.section(12).unwrap();
+    assert_eq!(section.name, ".\u{fffd}ext");
+    assert_eq!(section.name, elf.sections()[12].name);
+    // both find it by the same name
+    assert_eq!(view.section_by_name(".\u{fffd}ext").unwrap().index, 12);
+    assert_eq!(elf.section_by_name(".\u{fffd}ext").unwrap().index, 12);
+
+    let names: Vec<String> = view.symbols().map(|s| s.name.into_owned()).collect();
+    let expected: Vec<String> = elf.symbols().unwrap().into_iter().map(|s| s.name).collect();
+    assert_eq!(names, expected);
+    assert!(names.iter().any(|n| n == "\u{fffd}dd"));
+}
+
+#[test]
+fn malformed() {
+    let data = seed("lib64.so");
+    assert!(matches!(ElfView::parse(&data[..8]), Err(Error::Truncated { .. })));
+    assert!(matches!(ElfView::parse(b"\x7fELG\x02\x01\x01\0\0\0\0\0\0\0\0\0"), Err(Error::BadMagic)));
+    let mut broken = data.clone();
+    broken[value::EI_CLASS] = 3;
+    assert!(matches!(ElfView::parse(&broken), Err(Error::UnsupportedClass(3))));
+
+    // headers cut off keep what is in data
+    let shoff = ElfView::parse(&data).unwrap().header.e_shoff as usize;
+    let view = ElfView::parse(&data[..shoff + 0x40 * 3]).unwrap();
+    assert_eq!(view.sections().count(), 3);
+    let view = ElfView::parse(&data[..0x100]).unwrap();
+    assert_eq!(view.sections().count(), 0);
+    assert_eq!(view.dynamic_symbols().len(), 0);
+    assert_eq!(view.dynamic().count(), 0);
+}
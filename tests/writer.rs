@@ -0,0 +1,106 @@
+// Reading then writing an unmodified file must give the same bytes back.
+mod common;
+use std::io::Cursor;
+use satori::elf::{
+    elf64,
+    endian::Endian,
+    file::Elf,
+    value,
+    view::ElfView,
+    writer::{entry_sizes, ElfWriter, OutputSection},
+};
+
+
+#[test]
+fn round_trip() {
+    for (name, seed) in common::seeds(&["o", "so"]) {
+        let mut writer = ElfWriter::from_bytes(&seed).unwrap_or_else(|e| panic!("{}: {}", name, e));
+        let data = writer.to_bytes().unwrap_or_else(|e| panic!("{}: {}", name, e));
+        assert!(data == seed, "{} differs after round trip", name);
+    }
+}
+
+#[test]
+fn renamed_section_moves() {
+    for (name, seed) in common::seeds(&["o", "so"]) {
+        let mut writer = ElfWriter::from_bytes(&seed).unwrap();
+        let section = match writer.section_by_name_mut(".comment") {
+            Some(s) => s,
+            None => continue,
+        };
+        section.name = String::from(".comment.renamed.to.grow.shstrtab");
+        section.data.extend_from_slice(b"satori\0");
+        let data = writer.to_bytes().unwrap_or_else(|e| panic!("{}: {}", name, e));
+
+        let view = ElfView::parse(&data).unwrap();
+        let section = view.section_by_name(".comment.renamed.to.grow.shstrtab")
+            .unwrap_or_else(|| panic!("{}: renamed section not found", name));
+        assert!(view.section_data(&section.header).unwrap().ends_with(b"satori\0"), "{}", name);
+        // everything mapped stays where it was
+        for segment in view.segments().filter(|p| p.p_type == value::PT_LOAD) {
+            let start = segment.p_offset as usize;
+            let end = start + segment.p_filesz as usize;
+            assert!(data[start..end] == seed[start..end], "{}: segment content changed", name);
+        }
+    }
+}
+
+#[test]
+fn from_scratch() {
+    let code = b"\x31\xff\xb8\x3c\x00\x00\x00\x0f\x05".to_vec();
+    for (class, endian) in [(value::ELFCLASS64, Endian::Little), (value::ELFCLASS32, Endian::Big)] {
+        let mut writer = ElfWriter::new(class, endian);
+        writer.header.e_type = value::ET_EXEC;
+        writer.header.e_machine = value::EM_X86_64;
+        writer.header.e_entry = 0x401000;
+        writer.segments.push(elf64::ProgramHeader {
+            p_type: value::PT_LOAD, p_flags: value::PF_R | value::PF_X,
+            p_offset: 0x1000, p_vaddr: 0x401000, p_paddr: 0x401000,
+            p_filesz: code.len() as u64, p_memsz: code.len() as u64, p_align: 0x1000,
+        });
+        let text = elf64::SectionHeader {
+            sh_type: value::SHT_PROGBITS, sh_flags: (value::SHF_ALLOC | value::SHF_EXECINSTR) as u64,
+            sh_addr: 0x401000, sh_offset: 0x1000, sh_size: code.len() as u64, sh_addralign: 16,
+            ..Default::default()
+        };
+        let strtab = elf64::SectionHeader { sh_type: value::SHT_STRTAB, sh_addralign: 1, ..Default::default() };
+        writer.sections = vec![
+            OutputSection::default(),
+            OutputSection { name: String::from(".text"), header: text, data: code.clone() },
+            // names are filled in by layout
+            OutputSection { name: String::from(".shstrtab"), header: strtab, data: Vec::new() },
+        ];
+        writer.set_shstrndx(2);
+        let data = writer.to_bytes().unwrap();
+
+        // program headers right after the ELF header
+        let (ehdr_size, phdr_size, _) = entry_sizes(class);
+        let mut elf = Elf::load(Cursor::new(&data[..])).unwrap();
+        assert!(elf.warnings.is_empty());
+        assert_eq!((elf.class, elf.endian), (class, endian));
+        assert_eq!((elf.header.e_phoff, elf.header.e_phentsize as u64), (ehdr_size, phdr_size));
+        assert_eq!((elf.header.e_type, elf.header.e_entry), (value::ET_EXEC, 0x401000));
+        let segments: Vec<(u32, u64, u64, u64)> = elf.segments().iter().map(|p| (p.p_type, p.p_offset, p.p_vaddr, p.p_filesz)).collect();
+        assert_eq!(segments, [(value::PT_LOAD, 0x1000, 0x401000, code.len() as u64)]);
+        let names: Vec<&str> = elf.sections().iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["", ".text", ".shstrtab"]);
+        assert_eq!(elf.section_data(1).unwrap(), code);
+        let shstrtab = &elf.sections()[2].header;
+        assert!(shstrtab.sh_offset >= 0x1000 + code.len() as u64);
+        assert!(elf.header.e_shoff >= shstrtab.sh_offset + shstrtab.sh_size);
+    }
+
+    // no room after the ELF header, the table goes to the end of file
+    let mut writer = ElfWriter::new(value::ELFCLASS64, Endian::Little);
+    let header = elf64::SectionHeader { sh_type: value::SHT_PROGBITS, sh_offset: 0x40, sh_size: 0x10, ..Default::default() };
+    writer.sections = vec![
+        OutputSection::default(),
+        OutputSection { name: String::from(".data"), header, data: vec![0xaa; 0x10] },
+    ];
+    writer.segments.push(elf64::ProgramHeader { p_type: value::PT_NOTE, ..Default::default() });
+    let data = writer.to_bytes().unwrap();
+    let view = ElfView::parse(&data).unwrap();
+    assert_eq!(view.header.e_phoff, 0x50);
+    assert_eq!(view.segments().map(|p| p.p_type).collect::<Vec<u32>>(), [value::PT_NOTE]);
+    assert_eq!(view.section_data(&view.section(1).unwrap().header).unwrap(), &[0xaa; 0x10][..]);
+}